@@ -1,7 +1,9 @@
 import init, { hydrate } from "./pkg/client.js";
+// connects the Rust router to the event bus of Aleph.js
+import { wasmURL } from "aleph/rust";
 
 // reload page on rebuild
 import.meta.hot?.decline();
 
 // run app main
-init().then(hydrate);
+init(wasmURL("./pkg/client_bg.wasm")).then(hydrate);
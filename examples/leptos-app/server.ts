@@ -1,11 +1,8 @@
 import { serve } from "aleph/server";
-import init, { ssr } from "./pkg/server.js";
-
-const wasmUrl = new URL("./pkg/server_bg.wasm", import.meta.url);
-await init(await Deno.readFile(wasmUrl));
+import rust from "aleph/plugins/rust";
 
 serve({
-  ssr: ({ url }) => {
-    return ssr(url.href);
-  },
+  plugins: [
+    rust({ module: "./pkg/server.js", clientModule: "./pkg/client.js" }),
+  ],
 });
@@ -8,28 +8,28 @@ cfg_if! {
 
         #[wasm_bindgen]
         pub fn hydrate() {
-            console_error_panic_hook::set_once();
             _ = console_log::init_with_level(log::Level::Debug);
 
-            mount_to_body(|cx| {
+            aleph::leptos::hydrate(|cx| {
                 view! { cx,  <App/> }
             })
         }
     }
 
     else if #[cfg(feature = "ssr")] {
-        use wasm_bindgen::prelude::wasm_bindgen;
+        use wasm_bindgen::prelude::*;
         use leptos::*;
         use leptos_router::{ServerIntegration, RouterIntegrationContext};
+        use aleph::leptos::use_ssr_context;
         pub mod routes;
         use routes::{App, AppProps};
 
         #[wasm_bindgen]
-        pub fn ssr(url: String) -> String {
-            let history = ServerIntegration { path: url };
-            let router_integration = RouterIntegrationContext::new(history);
-            render_to_string(move |cx| {
-                provide_context::<RouterIntegrationContext>(cx, router_integration);
+        pub fn ssr(ctx: String) -> Result<JsValue, JsValue> {
+            aleph::leptos::render(ctx, |cx| {
+                let url = use_ssr_context(cx).unwrap().url().to_owned();
+                let history = ServerIntegration { path: url };
+                provide_context(cx, RouterIntegrationContext::new(history));
                 view! { cx, <App/> }
             })
         }
@@ -1,3 +1,4 @@
+use aleph::leptos::{RouterEvents, RouterEventsProps};
 use leptos::*;
 use leptos_meta::*;
 use leptos_router::*;
@@ -11,6 +12,7 @@ pub fn App(cx: Scope) -> impl IntoView {
     view! {
         cx,
         <Router>
+            <RouterEvents/>
             <main>
                 <Routes>
                     <Route path="" view=|cx| view! {
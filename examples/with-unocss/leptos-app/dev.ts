@@ -29,6 +29,7 @@ async function start() {
       "build",
       "--target",
       "web",
+      "--dev",
       "--out-name",
       "server",
       "--features",
@@ -44,6 +45,7 @@ async function start() {
       "build",
       "--target",
       "web",
+      "--dev",
       "--out-name",
       "client",
       "--features",
@@ -58,7 +60,6 @@ async function start() {
     await buildClientProc.status();
     buildServerProc.close();
     buildClientProc.close();
-    await Deno.remove(`${cwd}/pkg/.gitignore`);
     // start aleph dev server
     dev(join(cwd, "server.ts"));
   } finally {
@@ -19,7 +19,8 @@ async function start() {
     buildProc.close();
   }
   buildProc = Deno.run({
-    cmd: ["wasm-pack", "build", "--target", "web"],
+    // the dev build keeps the debug info for the symbolication of the panics
+    cmd: ["wasm-pack", "build", "--target", "web", "--dev"],
     stdout: "inherit",
     stderr: "inherit",
     cwd,
@@ -27,7 +28,6 @@ async function start() {
   try {
     await buildProc.status();
     buildProc.close();
-    await Deno.remove(`${cwd}/pkg/.gitignore`);
     dev(join(cwd, "server.ts"));
   } finally {
     buildProc = null;
@@ -1,4 +1,6 @@
 import init, { main } from "./pkg/yew_app.js";
+// connects the Rust router to the event bus of Aleph.js
+import "aleph/rust";
 
 // reload page on rebuild
 import.meta.hot?.decline();
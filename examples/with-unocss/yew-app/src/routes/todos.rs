@@ -74,7 +74,7 @@ pub fn Todos() -> Html {
   let onadd = {
     let todos = todos.clone();
     let input_node_ref = input_node_ref.clone();
-    Callback::from(move |e: FocusEvent| {
+    Callback::from(move |e: SubmitEvent| {
       e.prevent_default();
       let input = input_node_ref.cast::<HtmlInputElement>().unwrap();
       let mut v = todos.to_vec();
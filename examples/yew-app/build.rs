@@ -0,0 +1,6 @@
+fn main() {
+  // regenerate the routes when a page is added or removed
+  println!("cargo:rerun-if-changed=src/routes");
+  // build the `.module.css` files for the `css_module!` macro
+  aleph::build::css_modules();
+}
@@ -0,0 +1,36 @@
+use aleph::IslandHandle;
+use wasm_bindgen::prelude::*;
+use yew::prelude::*;
+
+#[function_component]
+fn About() -> Html {
+  html! {
+    <div class="about screen">
+      <h1>{"About"}</h1>
+      <p>
+        {"This page is split into its own wasm module, it's loaded when the page is visited or a link to it is "}
+        {"prefetched."}
+      </p>
+      <ul>
+        <li>{"The server renders it with the module loaded by the `chunks` option of the rust plugin."}</li>
+        <li>{"The browser imports the module on demand and hydrates the server-rendered html."}</li>
+      </ul>
+    </div>
+  }
+}
+
+#[wasm_bindgen]
+pub async fn ssr_island(name: String, ctx: String, props: String) -> Result<JsValue, JsValue> {
+  match name.as_str() {
+    "/about" => aleph::yew::render_island::<About>(ctx, props).await,
+    _ => Err(JsValue::from_str("unknown route")),
+  }
+}
+
+#[wasm_bindgen]
+pub fn hydrate_island(name: String, root: web_sys::Element, props: String) -> Result<IslandHandle, JsValue> {
+  match name.as_str() {
+    "/about" => aleph::yew::hydrate_island::<About>(root, props),
+    _ => Err(JsValue::from_str("unknown route")),
+  }
+}
@@ -2,37 +2,51 @@ import { fromFileUrl, join } from "std/path/mod.ts";
 import dev, { createWatchFsEmitter } from "aleph/dev";
 
 const emitter = createWatchFsEmitter();
-emitter.on("modify", ({ specifier }) => {
-  if (specifier.endsWith(".rs")) {
-    // rebuild the yew app then restart the dev server
+emitter.on("*", (kind, { specifier }) => {
+  if (!["create", "remove", "modify"].includes(kind)) {
+    return;
+  }
+  if (specifier.endsWith(".rs") || specifier.endsWith(".module.css")) {
+    // rebuild the yew app then restart the dev server, the routes are regenerated when a page is added or removed
     start();
   }
 });
 
 let buildProc: Deno.Process | null = null;
 
+// the app crate and the route chunks, the chunks are built into `pkg/<name>` for the `CHUNK` const of the pages
+const crates = [
+  { dir: ".", args: [] },
+  { dir: "chunks/about", args: ["--out-dir", "../../pkg/about"] },
+];
+
 // build the yew app then start the dev server
 async function start() {
   const cwd = fromFileUrl(new URL(".", import.meta.url));
   if (buildProc) {
     buildProc.kill("SIGTERM");
     buildProc.close();
+    buildProc = null;
   }
-  buildProc = Deno.run({
-    cmd: ["wasm-pack", "build", "--target", "web"],
-    stdout: "inherit",
-    stderr: "inherit",
-    cwd,
-  });
-  try {
-    await buildProc.status();
-    buildProc.close();
-    await Deno.remove(`${cwd}/pkg/.gitignore`);
-    // start aleph dev server
-    dev(join(cwd, "server.ts"));
-  } finally {
+  for (const { dir, args } of crates) {
+    const proc = Deno.run({
+      // the dev build keeps the debug info for the symbolication of the panics
+      cmd: ["wasm-pack", "build", "--target", "web", "--dev", ...args],
+      stdout: "inherit",
+      stderr: "inherit",
+      cwd: join(cwd, dir),
+    });
+    buildProc = proc;
+    await proc.status();
+    // restarted by a change during the build
+    if (buildProc !== proc) {
+      return;
+    }
+    proc.close();
     buildProc = null;
   }
+  // start aleph dev server
+  dev(join(cwd, "server.ts"));
 }
 
 start();
@@ -1,7 +1,9 @@
 import init, { main } from "./pkg/yew_app.js";
+// connects the Rust router to the event bus of Aleph.js
+import { wasmURL } from "aleph/rust";
 
 // reload page on rebuild
 import.meta.hot?.decline();
 
 // run app main
-init().then(main);
+init(wasmURL("./pkg/yew_app_bg.wasm")).then(main);
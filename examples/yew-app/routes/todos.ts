@@ -0,0 +1,42 @@
+// The data of the `/todos` page rendered by `src/routes/todos.rs`, the page reads it with `use_data` and posts
+// the `<Form>` to the `mutation`, it works without the wasm as well.
+
+type Todo = {
+  id: number;
+  message: string;
+  completed: boolean;
+};
+
+const store = {
+  todos: [] as Todo[],
+};
+
+export const data = () => {
+  return Response.json(store);
+};
+
+export async function mutation(req: Request): Promise<Response> {
+  const form = await req.formData();
+  const id = Number(form.get("id"));
+  switch (form.get("intent")) {
+    case "toggle": {
+      const todo = store.todos.find((todo) => todo.id === id);
+      if (todo) {
+        todo.completed = !todo.completed;
+      }
+      break;
+    }
+    case "remove": {
+      store.todos = store.todos.filter((todo) => todo.id !== id);
+      break;
+    }
+    default: {
+      const message = form.get("message")?.toString().trim();
+      if (!message) {
+        return Response.json({ message: "The todo is empty" }, { status: 400 });
+      }
+      store.todos.push({ id: Date.now(), message, completed: false });
+    }
+  }
+  return Response.json(store);
+}
@@ -1,9 +1,16 @@
 import { serve } from "aleph/server";
-import init, { ssr } from "./pkg/yew_app.js";
-
-const wasmUrl = new URL("./pkg/yew_app_bg.wasm", import.meta.url);
-await init(await Deno.readFile(wasmUrl));
+import rust from "aleph/plugins/rust";
 
 serve({
-  ssr: ({ url }) => ssr(url.href),
+  plugins: [
+    // the native library built by `cargo build --profile native` renders the pages if it exists
+    rust({
+      module: "./pkg/yew_app.js",
+      native: "./target/native/yew_app",
+      // the wasm module of the `/about` page split by its `CHUNK` const, built from the `chunks/about` crate
+      chunks: ["./pkg/about/about.js"],
+      // the messages of `./locales/*.json`, the locale is detected by the `Accept-Language` header
+      i18n: { locales: ["en", "zh-CN"] },
+    }),
+  ],
 });
@@ -1,22 +1,23 @@
+use aleph::yew::{use_ssr_context, Switch};
+use aleph::HtmlRewriterHandlers;
 use std::collections::HashMap;
 use url::Url;
 use yew::prelude::*;
 use yew_router::history::{AnyHistory, History, MemoryHistory};
 use yew_router::prelude::*;
 
-use crate::components::header::Header;
 use crate::routes::{switch, Route};
 
-#[derive(Properties, PartialEq, Default)]
-pub struct AppProps {
-  pub ssr_url: Option<String>,
-}
-
 #[function_component]
-pub fn App(props: &AppProps) -> Html {
-  if let Some(url) = &props.ssr_url {
+pub fn App() -> Html {
+  if let Some(ctx) = use_ssr_context() {
+    // add `rel="noopener noreferrer"` to the external links
+    ctx.html_rewriter().on(
+      "a[target=_blank]",
+      HtmlRewriterHandlers::new().element(|el| el.set_attribute("rel", "noopener noreferrer")),
+    );
     let history = AnyHistory::from(MemoryHistory::new());
-    let url = Url::parse(url).unwrap();
+    let url = Url::parse(ctx.url()).unwrap();
     let mut queries: HashMap<String, String> = HashMap::new();
     for (key, value) in url.query_pairs() {
       queries.insert(key.into(), value.into());
@@ -24,14 +25,12 @@ pub fn App(props: &AppProps) -> Html {
     history.push_with_query(url.path(), queries).unwrap();
     html! {
       <Router history={history}>
-        <Header/>
         <Switch<Route> render={switch} />
      </Router>
     }
   } else {
     html! {
       <BrowserRouter>
-        <Header/>
         <Switch<Route> render={switch} />
       </BrowserRouter>
     }
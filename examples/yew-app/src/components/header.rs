@@ -1,15 +1,18 @@
+use aleph::css_module;
+use aleph::yew::{use_css_module, Link, NavLink};
 use yew::prelude::*;
-use yew_router::prelude::*;
 
 use crate::routes::Route;
 
 #[function_component]
 pub fn Header() -> Html {
+  let styles = use_css_module(css_module!("src/components/header.module.css"));
+
   html! {
-    <header>
-      <div class={"header-wrapper"}>
+    <header class={styles.header}>
+      <div class={styles.wrapper}>
         <h1>
-          <Link<Route> to={Route::Home}>
+          <Link<Route> to={Route::Index}>
             <svg
               viewbox="0 0 60 12.09"
               fill="currentColor"
@@ -27,6 +30,7 @@ pub fn Header() -> Html {
           </Link<Route>>
         </h1>
         <nav>
+          <NavLink<Route> to={Route::Todos} active_class={styles.active}>{"Todos"}</NavLink<Route>>
           <a
             href="https://deno.land/x/aleph"
           >
@@ -6,13 +6,13 @@ use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 pub fn main() {
-  yew::Renderer::<app::App>::new().hydrate();
+  aleph::yew::hydrate::<app::App>();
 }
 
 #[wasm_bindgen]
-pub async fn ssr(url: String) -> Result<JsValue, JsValue> {
-  let html = yew::ServerRenderer::<app::App>::with_props(app::AppProps { ssr_url: Some(url) })
-    .render()
-    .await;
-  Ok(serde_wasm_bindgen::to_value(&html).unwrap())
+pub async fn ssr(ctx: String) -> Result<JsValue, JsValue> {
+  aleph::yew::render::<app::App>(ctx).await
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+aleph::export_native_ssr!(aleph::yew::render_html::<app::App>, routes = routes::__aleph_routes);
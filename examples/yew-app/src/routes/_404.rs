@@ -1,17 +1,18 @@
+use aleph::yew::{use_i18n, Link};
 use yew::prelude::*;
-use yew_router::prelude::*;
 
 use crate::routes::Route;
 
 #[function_component]
-pub fn NotFound() -> Html {
+pub fn Page() -> Html {
+  let i18n = use_i18n();
   html! {
     <div class="screen e404">
       <h2>
-        {"Ooooooops, nothing here!"}
+        {i18n.t("e404.title")}
       </h2>
       <p>
-        <Link<Route> to={Route::Home}>{"Go back to the homepage"}</Link<Route>>
+        <Link<Route> to={Route::Index}>{i18n.t("e404.back")}</Link<Route>>
       </p>
     </div>
   }
@@ -0,0 +1,17 @@
+use aleph::yew::{ErrorBoundary, Outlet};
+use yew::prelude::*;
+
+use crate::components::header::Header;
+
+#[function_component]
+pub fn App() -> Html {
+  html! {
+    <>
+      <Header/>
+      // renders the error page with the status of the error, e.g. the `data` of the route throws a `FetchError`
+      <ErrorBoundary>
+        <Outlet/>
+      </ErrorBoundary>
+    </>
+  }
+}
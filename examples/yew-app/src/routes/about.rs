@@ -0,0 +1,3 @@
+// The page is split into the wasm module of the `chunks/about` crate, the file is not compiled into the app: the
+// `routes!` macro only reads the `CHUNK` const.
+pub const CHUNK: &str = "/pkg/about/about.js";
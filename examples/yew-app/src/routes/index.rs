@@ -1,37 +1,45 @@
+use aleph::yew::{use_i18n, use_page_cache, Link};
 use yew::prelude::*;
-use yew_router::prelude::*;
 
 use crate::routes::Route;
 
 #[function_component]
-pub fn Index() -> Html {
+pub fn Page() -> Html {
+  // the page never changes, the Aleph server serves it from the page cache
+  use_page_cache(86400, &[]);
+  let i18n = use_i18n();
+
   html! {
     <div class="index screen">
       <p class="logo">
         <img src="/assets/logo.svg" width="70" height="70" title="Aleph.js" />
         <img src="/assets/yew.png" width="70" height="70" title="Yew" />
       </p>
-      <h1>{"The Fullstack Framework in Deno."}</h1>
+      <h1>{i18n.t("index.title")}</h1>
       <p>
         <strong>{"Aleph.js"}</strong>
-        {" gives you the best developer experience for building web applications"}
+        {i18n.t("index.description")}
         <br />
-        {"with modern toolings."} <label>{"Yew SSR experimental version"}</label>{"."}
+        {i18n.t("index.toolings")} <label>{i18n.t("index.experimental")}</label>{"."}
       </p>
       <div class="external-links">
         <a href="https://alephjs.org/docs/get-started" target="_blank">
-          {"Get Started"}
+          {i18n.t("index.getStarted")}
         </a>
         <a href="https://alephjs.org/docs" target="_blank">
-          {"Docs"}
+          {i18n.t("index.docs")}
         </a>
         <a href="https://github.com/alephjs/aleph.js" target="_blank">
           {"Github"}
         </a>
       </div>
       <nav>
-        <Link<Route> to={Route::Todos}>
-          {"Todos App Demo"}
+        <Link<Route> to={Route::Todos} prefetch=true>
+          {i18n.t("index.todos")}
+        </Link<Route>>
+        // the link prefetches the wasm module of the `/about` chunk
+        <Link<Route> to={Route::About} prefetch=true>
+          {i18n.t("index.about")}
         </Link<Route>>
       </nav>
     </div>
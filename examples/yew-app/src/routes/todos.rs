@@ -1,125 +1,104 @@
-use web_sys::HtmlInputElement;
+use std::rc::Rc;
+
+use aleph::yew::{use_data, Form};
+use aleph::FormError;
+use serde::Deserialize;
 use yew::prelude::*;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 struct Todo {
-  id: usize,
+  id: u64,
+  message: String,
   completed: bool,
-  title: String,
 }
 
+/// The data of `routes/todos.ts`, the mutation responds the updated store as well.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+struct Store {
+  todos: Vec<Todo>,
+}
+
+type OnResult = Callback<Result<serde_json::Value, FormError>>;
+
 #[derive(PartialEq, Properties, Clone)]
 struct EntryProps {
   pub todo: Todo,
-  pub ontoggle: Callback<usize>,
-  pub onremove: Callback<usize>,
+  pub onresult: OnResult,
 }
 
 #[function_component]
 fn Entry(props: &EntryProps) -> Html {
-  let id = props.todo.id;
-  let lablel_class = if props.todo.completed {
-    "completed"
-  } else {
-    ""
-  };
-  let ontoggle = {
-    let ontoggle = props.ontoggle.clone();
-    move |_| ontoggle.emit(id)
-  };
-  let onremove = {
-    let onremove = props.onremove.clone();
-    move |_| onremove.emit(id)
-  };
+  let todo = &props.todo;
+  let lablel_class = if todo.completed { "completed" } else { "" };
 
+  // each todo is a form, so toggling or removing it works before the wasm is loaded
   html! {
     <li>
-      <input type="checkbox" checked={props.todo.completed} onclick={ontoggle} />
-      <label class={lablel_class}>{&props.todo.title}</label>
-      <button onclick={onremove}></button>
+      <Form onresult={props.onresult.clone()}>
+        <input type="hidden" name="id" value={todo.id.to_string()} />
+        <button
+          class="toggle"
+          name="intent"
+          value="toggle"
+          aria-pressed={todo.completed.to_string()}
+          aria-label="Toggle"
+        ></button>
+        <label class={lablel_class}>{&todo.message}</label>
+        <button class="remove" name="intent" value="remove" aria-label="Remove"></button>
+      </Form>
     </li>
   }
 }
 
 #[function_component]
-pub fn Todos() -> Html {
-  let todos = use_state(|| Vec::<Todo>::new());
-  let all_todos = use_memo(|todos| todos.len(), todos.clone());
-  let completed_todos = use_memo(|todos| todos.iter().filter(|t| t.completed).count(), todos.clone());
-  let input_node_ref = use_node_ref();
-
-  let onadd = {
-    let todos = todos.clone();
-    let input_node_ref = input_node_ref.clone();
-    Callback::from(move |e: FocusEvent| {
-      e.prevent_default();
-      let input = input_node_ref.cast::<HtmlInputElement>().unwrap();
-      let mut v = todos.to_vec();
-      v.push(Todo {
-        id: todos.to_vec().len() + 1,
-        completed: false,
-        title: input.value().trim().to_string(),
-      });
-      input.set_value("");
-      todos.set(v)
-    })
-  };
+pub fn Page() -> HtmlResult {
+  let data = use_data::<Store>()?;
+  // the store responded by the mutation replaces the data of the page
+  let updated = use_state(|| None::<Rc<Store>>);
+  let error = use_state(|| None::<String>);
+  let store = (*updated).clone().or(data).unwrap_or_default();
+  let all_todos = store.todos.len();
+  let completed_todos = store.todos.iter().filter(|t| t.completed).count();
 
-  let ontoggle = {
-    let todos = todos.clone();
-    Callback::from(move |id: usize| {
-      todos.set(
-        todos
-          .to_vec()
-          .into_iter()
-          .map(|t| {
-            if t.id == id {
-              Todo {
-                id: t.id,
-                completed: !t.completed,
-                title: t.title,
-              }
-            } else {
-              t
-            }
-          })
-          .collect(),
-      )
+  let onresult = {
+    let updated = updated.clone();
+    let error = error.clone();
+    Callback::from(move |result: Result<serde_json::Value, FormError>| match result {
+      Ok(value) => {
+        if let Ok(store) = serde_json::from_value(value) {
+          updated.set(Some(Rc::new(store)));
+        }
+        error.set(None);
+      }
+      Err(err) => error.set(Some(err.message)),
     })
   };
 
-  let onremove = {
-    let todos = todos.clone();
-    Callback::from(move |id: usize| todos.set(todos.to_vec().into_iter().filter(|t| t.id != id).collect()))
-  };
-
-  html! {
+  Ok(html! {
     <div class="todos-app">
       <h1>
         <span>{"Todos"}</span>
-        if *all_todos > 0 {
+        if all_todos > 0 {
           <em>{completed_todos}{"/"}{all_todos}</em>
         }
       </h1>
       <ul>
-      { for todos.iter().map(|todo| html! {
-        <Entry
-          todo={todo.clone()}
-          ontoggle={ontoggle.clone()}
-          onremove={onremove.clone()}
-        />
+      { for store.todos.iter().map(|todo| html! {
+        <Entry key={todo.id} todo={todo.clone()} onresult={onresult.clone()} />
       }) }
       </ul>
-      <form onsubmit={onadd}>
+      <Form {onresult}>
         <input
           type="text"
-          ref={input_node_ref}
           name="message"
           placeholder="What needs to be done?"
           autocomplete="off"
           autofocus={true}
         />
-      </form>
+      </Form>
+      if let Some(message) = &*error {
+        <p class="error">{message}</p>
+      }
     </div>
-  }
+  })
 }
@@ -237,6 +237,65 @@ export function showTransformError(err: TransformError) {
   document.body.appendChild(modalEl);
 }
 
+/** A node of the hydrated DOM that differs from the server markup. */
+export type HydrationMismatch = {
+  /** The components rendering the node, from the root to the nearest one. */
+  components: string[];
+  /** The path of the node in the DOM, e.g. `body > div.todos-app > h1`. */
+  path: string;
+  /** The node of the server markup, empty if the client renders an extra node. */
+  server: string;
+  /** The hydrated node, empty if the client doesn't render it. */
+  client: string;
+};
+
+export function showHydrationMismatch(mismatches: HydrationMismatch[]) {
+  const modalEl = document.createElement("div");
+  modalEl.className = "aleph--error-modal hydration-mismatch";
+  modalEl.innerHTML = `
+    <div class="box">
+      <pre><code><strong>Hydration Mismatch</strong> the hydrated DOM differs from the server markup</code></pre>
+      ${
+    mismatches.map(({ components, path, server, client }) => `
+      <pre class="source"><code>${escapeHTML(`in ${components.join(" > ") || "<root>"}\nat ${path}`)}
+- ${escapeHTML(server || "(none)")}
++ ${escapeHTML(client || "(none)")}</code></pre>`).join("")
+  }
+      <div class="actions">
+        <button>Dismiss</button>
+      </div>
+      ${helperLinks}
+    </div>
+  `;
+  modalEl.querySelector("button")!.addEventListener("click", () => modalEl.remove());
+  document.body.appendChild(modalEl);
+}
+
+/** Shows the panic of a Rust app, the wasm frames of its stack are symbolized with the debug info of the dev build. */
+export function showRustPanic(message: string, stack: string) {
+  const modalEl = document.createElement("div");
+  modalEl.className = "aleph--error-modal rust-panic";
+  modalEl.innerHTML = `
+    <div class="box">
+      <pre><code><strong>Panic</strong> ${escapeHTML(message)}</code></pre>
+      <pre class="source"><code>${escapeHTML(stack)}</code></pre>
+      <div class="actions">
+        <button>Reload</button>
+        <button>Dismiss</button>
+      </div>
+      ${helperLinks}
+    </div>
+  `;
+  const [reload, dismiss] = modalEl.querySelectorAll("button");
+  reload.addEventListener("click", () => location.reload());
+  dismiss.addEventListener("click", () => modalEl.remove());
+  document.body.appendChild(modalEl);
+}
+
+function escapeHTML(s: string): string {
+  return s.replaceAll("&", "&amp;").replaceAll("<", "&lt;").replaceAll(">", "&gt;");
+}
+
 if (globalThis.document) {
   const styleEl = document.createElement("style");
   styleEl.appendChild(document.createTextNode(style));
@@ -1,6 +1,11 @@
 /** @format */
 
-import mitt from "https://esm.sh/v126/mitt@3.0.0";
+import mitt, { type Emitter } from "https://esm.sh/v126/mitt@3.0.0";
 
-// shared event emitter for client(browser)
-export default mitt<Record<string, Record<string, unknown>>>();
+type Events = Record<string, Record<string, unknown>>;
+
+// shared event emitter for client(browser), it's exposed as `__ALEPH_EVENTS` for the Rust runtime
+const events: Emitter<Events> = Reflect.get(globalThis, "__ALEPH_EVENTS") ?? mitt<Events>();
+Reflect.set(globalThis, "__ALEPH_EVENTS", events);
+
+export default events;
@@ -3,15 +3,19 @@ import events from "./events.ts";
 import { matchRoutes, type Router } from "./router.ts";
 
 let router: Router | null = null;
+let routerReady = false;
 let preRedirect: URL | null = null;
 
+// the `router` event is emitted by the React router and the Rust routers, only the React router has the route table.
 const onrouter = (e: Record<string, unknown>) => {
-  events.off("router", onrouter);
   if (preRedirect) {
     events.emit("popstate", { type: "popstate", url: preRedirect });
     preRedirect = null;
   }
-  router = e.router as Router;
+  if (e.router) {
+    router = e.router as Router;
+  }
+  routerReady = true;
 };
 events.on("router", onrouter);
 
@@ -41,7 +45,8 @@ export function redirect(href: string, replace?: boolean) {
     history.pushState(null, "", url);
   }
 
-  if (router) {
+  if (routerReady) {
+    // with the Navigation API, the routers listen to the events of `navigation` instead
     if (!Reflect.has(globalThis, "navigation")) {
       events.emit("popstate", { type: "popstate", url });
     }
@@ -178,7 +178,7 @@ export async function createCSRContext(): Promise<CSRContext> {
           }
         }
         if (rest.error) {
-          rest.data = new FetchError(500, rest.error.message, { stack: rest.error.stack });
+          rest.data = new FetchError(rest.error.status ?? 500, rest.error.message, { stack: rest.error.stack });
           rest.error = undefined;
         }
         return <RouteModule> {
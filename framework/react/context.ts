@@ -21,6 +21,7 @@ export type RouterContextProps = {
   params: Record<string, string>;
   e404?: boolean;
   ssrHeadCollection?: string[];
+  ssrNonce?: string;
   createPortal?: (children: ReactNode, container: Element, key?: null | string) => ReactPortal;
 };
 
@@ -0,0 +1,153 @@
+import type { CSSProperties } from "react";
+import { createElement, useContext, useEffect, useRef } from "react";
+import { RouterContext } from "./context.ts";
+
+type IslandHandle = {
+  destroy(): void;
+  free(): void;
+};
+
+type IslandModule = {
+  default: (input?: string) => Promise<unknown>;
+  ssr_island: (name: string, ctx: string, props: string) => Promise<string> | string;
+  hydrate_island: (name: string, root: Element, props: string) => IslandHandle;
+};
+
+type IslandOutput = {
+  html: string;
+  headCollection?: string[];
+};
+
+export type RustIslandProps = {
+  /** The wasm-bindgen module that exports the `ssr_island` and `hydrate_island` functions, e.g. "./pkg/islands.js". */
+  module: string;
+  /** The name of the island passed to the `ssr_island` and `hydrate_island` functions. */
+  name: string;
+  /** The props of the island, they are serialized as JSON. */
+  props?: unknown;
+  /** The tag of the root element, default is `div`. */
+  as?: string;
+  className?: string;
+  style?: CSSProperties;
+};
+
+// the renders of the islands on the server, the output is removed once it's read
+const ssrOutputs = new Map<string, Promise<void> | IslandOutput | Error>();
+
+// the initialized wasm modules in the browser
+const islandModules = new Map<string, Promise<IslandModule>>();
+
+/**
+ * The `<RustIsland>` component mounts a Yew/Leptos component in the page, it's rendered by the wasm module on the
+ * server and hydrated in the browser independently. The wasm modules must be loaded by the `RustIslandsPlugin` of
+ * `aleph/plugins/rust`. The Leptos islands are rendered on the client instead of being hydrated, the server html is
+ * replaced once the wasm is loaded, see `aleph::leptos::hydrate_island`.
+ *
+ * ```jsx
+ * <RustIsland module="./pkg/islands.js" name="counter" props={{ initial: 1 }} />
+ * ```
+ */
+export function RustIsland(props: RustIslandProps) {
+  const { module, name, props: islandProps, as = "div", className, style } = props;
+  const { url, ssrHeadCollection, ssrNonce } = useContext(RouterContext);
+  const ref = useRef<HTMLElement>(null);
+  const propsJSON = JSON.stringify(islandProps ?? null);
+  const html = ssrHeadCollection ? renderIsland(module, name, url, ssrNonce, propsJSON, ssrHeadCollection) : "";
+
+  useEffect(() => {
+    const root = ref.current;
+    if (!root) {
+      return;
+    }
+    let handle: IslandHandle | null = null;
+    let disposed = false;
+    loadIslandModule(module).then((mod) => {
+      if (!disposed) {
+        handle = mod.hydrate_island(name, root, propsJSON);
+      }
+    }).catch((err) => console.error(`[aleph] Failed to hydrate the Rust island "${name}":`, err));
+    return () => {
+      disposed = true;
+      if (handle) {
+        handle.destroy();
+        handle.free();
+      }
+    };
+  }, [module, name, propsJSON]);
+
+  // the html rendered on the server is kept by the hydration since the `__html` is not patched by React
+  return createElement(as, {
+    ref,
+    className,
+    style,
+    "data-rust-island": name,
+    suppressHydrationWarning: true,
+    dangerouslySetInnerHTML: { __html: html },
+  });
+}
+
+/** Returns the key of the wasm module, it must match the registry of the `RustIslandsPlugin`. */
+function islandModuleKey(specifier: string): string {
+  return "/" + specifier.replace(/^\.?\//, "");
+}
+
+/**
+ * Renders the island with the wasm module loaded by the `RustIslandsPlugin`, suspends until it's rendered. The nonce of
+ * the CSP is applied to the `<style>` tags of the CSS modules used by the island.
+ */
+function renderIsland(
+  module: string,
+  name: string,
+  url: URL,
+  nonce: string | undefined,
+  props: string,
+  headCollection: string[],
+): string {
+  const key = [module, name, url.href, nonce, props].join("\n");
+  const output = ssrOutputs.get(key);
+  if (output instanceof Promise) {
+    throw output;
+  }
+  if (output) {
+    ssrOutputs.delete(key);
+    if (output instanceof Error) {
+      throw output;
+    }
+    if (output.headCollection) {
+      headCollection.push(...output.headCollection);
+    }
+    return output.html;
+  }
+
+  const islands: Record<string, IslandModule> | undefined = Reflect.get(globalThis, "__ALEPH_RUST_ISLANDS");
+  const mod = islands?.[islandModuleKey(module)];
+  if (!mod) {
+    throw new Error(`The Rust island module "${module}" is not loaded, please add it to the \`RustIslandsPlugin\``);
+  }
+  const promise = Promise.resolve(mod.ssr_island(name, JSON.stringify({ url: url.href, nonce }), props)).then(
+    (output) => {
+      ssrOutputs.set(key, JSON.parse(output));
+    },
+    (err) => {
+      ssrOutputs.set(key, err instanceof Error ? err : new Error(String(err)));
+    },
+  );
+  ssrOutputs.set(key, promise);
+  throw promise;
+}
+
+function loadIslandModule(specifier: string): Promise<IslandModule> {
+  const key = islandModuleKey(specifier);
+  let promise = islandModules.get(key);
+  if (!promise) {
+    const v = document.body.getAttribute("data-deployment-id");
+    const q = v ? "?v=" + v : "";
+    promise = import(key + q).then(async (mod: IslandModule) => {
+      // fetches the `*_bg.wasm` file next to the module, versioned like the module
+      await mod.default(key.replace(/\.js$/, "_bg.wasm") + q);
+      return mod;
+    });
+    islandModules.set(key, promise);
+  }
+  return promise;
+}
@@ -1,6 +1,7 @@
 export { bootstrap } from "./client.ts";
 export { useData } from "./data.ts";
 export { Head } from "./head.ts";
+export { RustIsland, type RustIslandProps } from "./island.ts";
 export { Link, type LinkProps, NavLink, type NavLinkProps } from "./link.ts";
 export { usePortal } from "./portal.ts";
 export { App, Router, type RouterProps, useRouter } from "./router.ts";
@@ -60,6 +60,7 @@ export const Router: FC<RouterProps> = (props) => {
     params,
     e404: modules[modules.length - 1].url.pathname === "/_404" ? true : undefined,
     ssrHeadCollection: ssrContext?.headCollection,
+    ssrNonce: ssrContext?.nonce,
     createPortal,
   };
   return createElement(
@@ -0,0 +1,275 @@
+//! The static file handler, it serves the files of the build manifest like the `staticHandler` of
+//! `server/handler.ts`.
+
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use axum::body::StreamBody;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use base64::Engine;
+use tokio_util::io::ReaderStream;
+
+use crate::manifest::BuildAsset;
+
+/// The extensions of the precompressed files, see `precompressedEncodings` of `server/helpers.ts`.
+fn encoding_ext(encoding: &str) -> Option<&'static str> {
+  match encoding {
+    "br" => Some(".br"),
+    "gzip" => Some(".gz"),
+    _ => None,
+  }
+}
+
+/// Checks whether the encoding is accepted by the `Accept-Encoding` header, see `acceptsEncoding` of
+/// `server/helpers.ts`.
+fn accepts_encoding(accept_encoding: &str, encoding: &str) -> bool {
+  let mut wildcard = false;
+  for part in accept_encoding.split(',') {
+    let mut params = part.split(';').map(str::trim);
+    let name = params.next().unwrap_or_default();
+    let accepted = params
+      .find_map(|param| param.strip_prefix("q="))
+      .is_none_or(|q| q.parse::<f32>().is_ok_and(|q| q > 0.0));
+    if name.eq_ignore_ascii_case(encoding) {
+      return accepted;
+    }
+    if name == "*" {
+      wildcard = accepted;
+    }
+  }
+  wildcard
+}
+
+pub(crate) async fn serve(
+  app_dir: &Path,
+  deployment_id: Option<&str>,
+  pathname: &str,
+  versioned: bool,
+  asset: &BuildAsset,
+  req_headers: &HeaderMap,
+) -> Response {
+  let file_path = app_dir.join(&asset.file);
+  let mut headers = HeaderMap::new();
+  headers.insert(header::CONTENT_TYPE, header_value(&asset.content_type));
+
+  // serve the precompressed file next to the original one, e.g. `app_bg.wasm.br`
+  let accept_encoding = req_headers
+    .get(header::ACCEPT_ENCODING)
+    .and_then(|value| value.to_str().ok())
+    .unwrap_or_default();
+  let mut encoding = None;
+  let mut encoded_file_path = file_path.clone();
+  if !asset.encodings.is_empty() {
+    headers.insert(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+  }
+  if let Some((name, ext)) = asset
+    .encodings
+    .iter()
+    .filter_map(|name| Some((name, encoding_ext(name)?)))
+    .find(|(name, _)| accepts_encoding(accept_encoding, name))
+  {
+    encoding = Some(name.as_str());
+    let mut path = file_path.clone().into_os_string();
+    path.push(ext);
+    encoded_file_path = path.into();
+  }
+  if let Some(encoding) = encoding {
+    headers.insert(header::CONTENT_ENCODING, header_value(encoding));
+  }
+
+  let mut etag = match deployment_id {
+    Some(id) => {
+      let encoded = base64::engine::general_purpose::STANDARD.encode(pathname);
+      let name = encoded
+        .chars()
+        .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        .collect::<String>();
+      Some(format!("W/{}-{}", name, id))
+    }
+    None => match tokio::fs::metadata(&file_path).await {
+      Ok(stat) => stat.modified().ok().map(|mtime| {
+        let ms = mtime.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        headers.insert(header::LAST_MODIFIED, header_value(&httpdate::fmt_http_date(mtime)));
+        format!("W/{:x}-{:x}", ms, stat.len())
+      }),
+      Err(_) => return not_found(),
+    },
+  };
+  if let (Some(etag), Some(encoding)) = (&mut etag, encoding) {
+    etag.push('-');
+    etag.push_str(encoding);
+  }
+  if let Some(etag) = etag {
+    if req_headers
+      .get(header::IF_NONE_MATCH)
+      .and_then(|value| value.to_str().ok())
+      == Some(etag.as_str())
+    {
+      return StatusCode::NOT_MODIFIED.into_response();
+    }
+    headers.insert(header::ETAG, header_value(&etag));
+  }
+  if versioned || has_full_version(pathname) {
+    headers.insert(
+      header::CACHE_CONTROL,
+      HeaderValue::from_static("public, max-age=31536000, immutable"),
+    );
+  }
+
+  match tokio::fs::File::open(&encoded_file_path).await {
+    Ok(file) => (headers, StreamBody::new(ReaderStream::new(file))).into_response(),
+    Err(_) => not_found(),
+  }
+}
+
+/// Checks the full version in the pathname like the `regFullVersion` of `server/helpers.ts`, e.g.
+/// `/-/esm.sh/react@18.2.0`.
+fn has_full_version(pathname: &str) -> bool {
+  pathname.split('@').skip(1).any(|s| {
+    let mut parts = s.splitn(3, '.');
+    let major = parts.next().unwrap_or_default();
+    let minor = parts.next().unwrap_or_default();
+    let patch = parts.next().unwrap_or_default();
+    let is_num = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let patch_digits = patch.bytes().take_while(u8::is_ascii_digit).count();
+    is_num(major) && is_num(minor) && patch_digits > 0
+  })
+}
+
+fn header_value(value: &str) -> HeaderValue {
+  HeaderValue::from_str(value).unwrap_or_else(|_| HeaderValue::from_static(""))
+}
+
+pub(crate) fn not_found() -> Response {
+  (StatusCode::NOT_FOUND, "Not found").into_response()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::*;
+
+  struct App(std::path::PathBuf);
+
+  impl App {
+    /// Writes `main.js` with its precompressed files, the mtime is `1700000000123` ms.
+    fn new(name: &str) -> Self {
+      let dir = std::env::temp_dir().join(format!("aleph-axum-{}-{}", name, std::process::id()));
+      std::fs::create_dir_all(dir.join("output")).unwrap();
+      for (file, content) in [("main.js", "hello world"), ("main.js.br", "br"), ("main.js.gz", "gzip")] {
+        let file = std::fs::File::create(dir.join("output").join(file)).unwrap();
+        std::io::Write::write_all(&mut &file, content.as_bytes()).unwrap();
+        file
+          .set_modified(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123))
+          .unwrap();
+      }
+      Self(dir)
+    }
+
+    async fn serve(&self, deployment_id: Option<&str>, headers: &[(header::HeaderName, &str)]) -> Response {
+      let asset = BuildAsset {
+        file: "output/main.js".to_owned(),
+        content_type: "application/javascript; charset=utf-8".to_owned(),
+        encodings: vec!["br".to_owned(), "gzip".to_owned()],
+      };
+      let mut req_headers = HeaderMap::new();
+      for (name, value) in headers {
+        req_headers.insert(name, HeaderValue::from_str(value).unwrap());
+      }
+      serve(&self.0, deployment_id, "/main.js", false, &asset, &req_headers).await
+    }
+  }
+
+  impl Drop for App {
+    fn drop(&mut self) {
+      let _ = std::fs::remove_dir_all(&self.0);
+    }
+  }
+
+  async fn text(res: Response) -> String {
+    let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+  }
+
+  fn header(res: &Response, name: header::HeaderName) -> Option<&str> {
+    res.headers().get(name).and_then(|value| value.to_str().ok())
+  }
+
+  #[tokio::test]
+  async fn etag() {
+    let app = App::new("etag");
+    // the same etags as the `staticHandler` of `server/handler.ts`: `W/${mtime.toString(16)}-${size.toString(16)}`,
+    // or `W/${btoa(pathname).replace(/[^a-z0-9]/g, "")}-${deployId}` with the deployment id
+    let res = app.serve(None, &[]).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(header(&res, header::ETAG), Some("W/18bcfe5687b-b"));
+    assert_eq!(
+      header(&res, header::LAST_MODIFIED),
+      Some("Tue, 14 Nov 2023 22:13:20 GMT")
+    );
+    assert_eq!(text(res).await, "hello world");
+    let res = app.serve(Some("1a2b3c"), &[]).await;
+    assert_eq!(header(&res, header::ETAG), Some("W/21ha4uan-1a2b3c"));
+    let res = app.serve(Some("1a2b3c"), &[(header::ACCEPT_ENCODING, "gzip")]).await;
+    assert_eq!(header(&res, header::ETAG), Some("W/21ha4uan-1a2b3c-gzip"));
+  }
+
+  #[tokio::test]
+  async fn content_encoding() {
+    let app = App::new("encoding");
+    let res = app.serve(None, &[(header::ACCEPT_ENCODING, "gzip, deflate, br")]).await;
+    assert_eq!(header(&res, header::CONTENT_ENCODING), Some("br"));
+    assert_eq!(header(&res, header::VARY), Some("Accept-Encoding"));
+    assert_eq!(header(&res, header::ETAG), Some("W/18bcfe5687b-b-br"));
+    assert_eq!(text(res).await, "br");
+    let res = app.serve(None, &[(header::ACCEPT_ENCODING, "gzip")]).await;
+    assert_eq!(header(&res, header::CONTENT_ENCODING), Some("gzip"));
+    assert_eq!(text(res).await, "gzip");
+    let res = app.serve(None, &[(header::ACCEPT_ENCODING, "deflate")]).await;
+    assert_eq!(header(&res, header::CONTENT_ENCODING), None);
+    assert_eq!(text(res).await, "hello world");
+    let res = app.serve(None, &[(header::ACCEPT_ENCODING, "gzip, br;q=0")]).await;
+    assert_eq!(header(&res, header::CONTENT_ENCODING), Some("gzip"));
+  }
+
+  #[test]
+  fn accept_encoding() {
+    assert!(accepts_encoding("gzip, deflate, br", "br"));
+    assert!(!accepts_encoding("gzip, br;q=0", "br"));
+    assert!(accepts_encoding("br;q=0.5, gzip;q=1.0", "br"));
+    assert!(!accepts_encoding("gzip", "br"));
+    assert!(accepts_encoding("*", "br"));
+    assert!(!accepts_encoding("gzip, *;q=0", "br"));
+    assert!(!accepts_encoding("br;q=0, *", "br"));
+    assert!(!accepts_encoding("", "gzip"));
+  }
+
+  #[tokio::test]
+  async fn not_modified() {
+    let app = App::new("not-modified");
+    let res = app.serve(None, &[(header::IF_NONE_MATCH, "W/18bcfe5687b-b")]).await;
+    assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(text(res).await, "");
+    // the etag of another encoding
+    let res = app
+      .serve(
+        None,
+        &[
+          (header::IF_NONE_MATCH, "W/18bcfe5687b-b"),
+          (header::ACCEPT_ENCODING, "br"),
+        ],
+      )
+      .await;
+    assert_eq!(res.status(), StatusCode::OK);
+  }
+
+  #[test]
+  fn full_version() {
+    assert!(has_full_version("/-/esm.sh/react@18.2.0"));
+    assert!(has_full_version("/-/esm.sh/react@18.2.0-beta"));
+    assert!(!has_full_version("/-/esm.sh/react@18.2"));
+    assert!(!has_full_version("/main.js"));
+  }
+}
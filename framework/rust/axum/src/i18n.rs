@@ -0,0 +1,204 @@
+//! The message catalogs of the app, it's a port of `framework/rust/i18n.ts`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use axum::http::{header, HeaderMap};
+use serde::Serialize;
+use serde_json::Value;
+
+/// The messages of a locale sent to the render function, see `I18n` of the `aleph` crate.
+#[derive(Serialize)]
+pub(crate) struct Catalog {
+  pub locale: String,
+  pub messages: HashMap<String, String>,
+}
+
+pub(crate) struct Catalogs {
+  locales: Vec<String>,
+  catalogs: HashMap<String, Catalog>,
+}
+
+impl Catalogs {
+  /// Loads the catalogs `<dir>/<locale>.json`, the missing messages of a locale fall back to the default locale.
+  pub fn load(dir: &Path, locales: &[&str]) -> Self {
+    let loaded = locales
+      .iter()
+      .map(|locale| {
+        let filename = dir.join(format!("{}.json", locale));
+        let mut messages = HashMap::new();
+        match std::fs::read_to_string(&filename) {
+          Ok(json) => match serde_json::from_str(&json) {
+            Ok(value) => flatten_messages(&value, "", &mut messages),
+            Err(err) => log::warn!("Failed to load the catalog {}: {}", filename.display(), err),
+          },
+          Err(_) => log::warn!(
+            "The catalog of the locale \"{}\" is not found: {}",
+            locale,
+            filename.display()
+          ),
+        }
+        messages
+      })
+      .collect::<Vec<_>>();
+    let default_messages = loaded.first().cloned().unwrap_or_default();
+    let catalogs = locales
+      .iter()
+      .zip(loaded)
+      .map(|(locale, messages)| {
+        let mut merged = default_messages.clone();
+        merged.extend(messages);
+        let catalog = Catalog {
+          locale: locale.to_string(),
+          messages: merged,
+        };
+        (locale.to_string(), catalog)
+      })
+      .collect();
+    Self {
+      locales: locales.iter().map(|locale| locale.to_string()).collect(),
+      catalogs,
+    }
+  }
+
+  pub fn get(&self, locale: &str) -> Option<&Catalog> {
+    self.catalogs.get(locale)
+  }
+
+  /// Strips the prefix of a supported locale from the pathname case-insensitively, e.g. `/zh-cn/todos` returns
+  /// `("zh-CN", "/todos")`, like `Catalogs.stripLocale` of `framework/rust/i18n.ts`.
+  pub fn strip_locale<'a>(&self, pathname: &'a str) -> Option<(&str, &'a str)> {
+    let segment = pathname.get(1..)?.split('/').next()?;
+    let locale = self.resolve(segment)?;
+    match &pathname[segment.len() + 1..] {
+      "" => Some((locale, "/")),
+      rest => Some((locale, rest)),
+    }
+  }
+
+  /// Detects the locale of the request by the `locale` cookie and the `Accept-Language` header, the prefix of the path
+  /// is stripped by `strip_locale` before, like `Catalogs.detect` of `framework/rust/i18n.ts`.
+  pub fn detect(&self, headers: &HeaderMap) -> Option<&str> {
+    let cookie = headers
+      .get_all(header::COOKIE)
+      .iter()
+      .filter_map(|value| value.to_str().ok())
+      .flat_map(|value| value.split(';'))
+      .find_map(|pair| pair.trim().strip_prefix("locale="));
+    if let Some(locale) = cookie.and_then(|cookie| self.resolve(cookie)) {
+      return Some(locale);
+    }
+    let accept_language = headers
+      .get(header::ACCEPT_LANGUAGE)
+      .and_then(|value| value.to_str().ok())
+      .unwrap_or_default();
+    for lang in parse_accept_language(accept_language) {
+      // falls back to the locale of the same language, e.g. `zh-TW` matches `zh-CN`
+      let language = lang.split('-').next().unwrap_or_default();
+      let locale = self.resolve(lang).or_else(|| {
+        self
+          .locales
+          .iter()
+          .find(|locale| {
+            locale
+              .split('-')
+              .next()
+              .unwrap_or_default()
+              .eq_ignore_ascii_case(language)
+          })
+          .map(String::as_str)
+      });
+      if locale.is_some() {
+        return locale;
+      }
+    }
+    self.locales.first().map(String::as_str)
+  }
+
+  /// Matches the language tag with the supported locales case-insensitively, e.g. `zh-cn` matches `zh-CN`.
+  fn resolve(&self, tag: &str) -> Option<&str> {
+    self
+      .locales
+      .iter()
+      .find(|locale| !tag.is_empty() && locale.eq_ignore_ascii_case(tag))
+      .map(String::as_str)
+  }
+}
+
+/// Parses the `Accept-Language` header, returns the languages ordered by the quality.
+fn parse_accept_language(header: &str) -> Vec<&str> {
+  let mut langs = header
+    .split(',')
+    .filter_map(|part| {
+      let mut params = part.split(';');
+      let lang = params.next()?.trim();
+      let q = params
+        .find_map(|param| param.trim().strip_prefix("q="))
+        .map_or(1.0, |q| q.parse::<f32>().unwrap_or(0.0));
+      (!lang.is_empty() && lang != "*" && q > 0.0).then_some((lang, q))
+    })
+    .collect::<Vec<_>>();
+  // the sort is stable, so the languages of the same quality keep the order
+  langs.sort_by(|a, b| b.1.total_cmp(&a.1));
+  langs.into_iter().map(|(lang, _)| lang).collect()
+}
+
+/// Flattens the nested messages, e.g. `{ "index": { "title": "..." } }` to `{ "index.title": "..." }`.
+fn flatten_messages(value: &Value, prefix: &str, messages: &mut HashMap<String, String>) {
+  match value {
+    Value::Object(map) => {
+      for (key, value) in map {
+        match value {
+          Value::Object(_) => flatten_messages(value, &format!("{}{}.", prefix, key), messages),
+          Value::String(s) => {
+            messages.insert(format!("{}{}", prefix, key), s.clone());
+          }
+          value => {
+            messages.insert(format!("{}{}", prefix, key), value.to_string());
+          }
+        }
+      }
+    }
+    _ => log::warn!("The catalog must be a JSON object"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn detect_the_locale() {
+    let catalogs = Catalogs::load(Path::new("locales"), &["en", "zh-CN", "de"]);
+    let detect = |pairs: &[(header::HeaderName, &str)]| {
+      let mut headers = HeaderMap::new();
+      for (name, value) in pairs {
+        headers.append(name, value.parse().unwrap());
+      }
+      catalogs.detect(&headers).map(str::to_owned)
+    };
+    assert_eq!(detect(&[]).as_deref(), Some("en"));
+    assert_eq!(detect(&[(header::COOKIE, "foo=bar; locale=de")]).as_deref(), Some("de"));
+    assert_eq!(
+      detect(&[(header::COOKIE, "locale=zh-cn"), (header::ACCEPT_LANGUAGE, "de")]).as_deref(),
+      Some("zh-CN")
+    );
+    assert_eq!(
+      detect(&[(header::ACCEPT_LANGUAGE, "fr;q=0.9, de;q=0.5, zh-TW")]).as_deref(),
+      Some("zh-CN")
+    );
+    assert_eq!(
+      detect(&[(header::COOKIE, "locale=fr"), (header::ACCEPT_LANGUAGE, "fr, *")]).as_deref(),
+      Some("en")
+    );
+  }
+
+  #[test]
+  fn strip_the_locale() {
+    let catalogs = Catalogs::load(Path::new("locales"), &["en", "zh-CN"]);
+    assert_eq!(catalogs.strip_locale("/zh-cn/todos"), Some(("zh-CN", "/todos")));
+    assert_eq!(catalogs.strip_locale("/en"), Some(("en", "/")));
+    assert_eq!(catalogs.strip_locale("/todos"), None);
+    assert_eq!(catalogs.strip_locale("/"), None);
+  }
+}
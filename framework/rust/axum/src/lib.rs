@@ -0,0 +1,313 @@
+//! Serves the build output of [Aleph.js](https://alephjs.org) apps from a native Rust server with
+//! [axum](https://docs.rs/axum).
+//!
+//! The build of Aleph.js (`deno run -A server.ts --build`) writes the `manifest.json` to the output dir, the server
+//! serves the static files listed in the manifest, and renders the pages of the Yew/Leptos app natively:
+//!
+//! ```ignore
+//! let app = aleph_axum::Aleph::load("./")?
+//!   .render(aleph::yew::render_html::<app::App>)
+//!   .routes(routes::__aleph_routes)
+//!   .client_module("./pkg/yew_app.js")
+//!   .i18n(&["en", "zh-CN"])
+//!   .into_router();
+//! axum::Server::bind(&"0.0.0.0:3000".parse()?).serve(app.into_make_service()).await?;
+//! ```
+//!
+//! The data loaders and the middlewares of the Aleph server are written in JS, they are not supported. So the requests
+//! other than `GET` and `HEAD`, e.g. the `<Form>` posting to the `mutation` of a route module, get `405 Method Not
+//! Allowed`, add the routes handling them to the router of the server:
+//!
+//! ```ignore
+//! let app = axum::Router::new()
+//!   .route("/todos", axum::routing::post(todos::mutation))
+//!   .merge(aleph.into_router());
+//! ```
+//!
+//! The errors are logged with the [log](https://docs.rs/log) facade, e.g. the render errors and the missing message
+//! catalogs.
+
+mod assets;
+mod i18n;
+mod manifest;
+mod render;
+mod router;
+
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{header, HeaderValue, Method, Request, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::Router;
+
+pub use manifest::{BuildAsset, BuildManifest, RouteMeta, RoutePattern, RouterManifest, BUILD_MANIFEST_VERSION};
+
+use i18n::Catalogs;
+use render::{Document, SsrInput, SsrOutput};
+
+type RenderFn = dyn Fn(String) -> Result<String, String> + Send + Sync;
+
+/// The server of the build output of an Aleph.js app.
+#[derive(Clone)]
+pub struct Aleph(Arc<Inner>);
+
+struct Inner {
+  app_dir: PathBuf,
+  manifest: BuildManifest,
+  render: Option<Box<RenderFn>>,
+  routes: Option<Vec<String>>,
+  client_modules: Vec<String>,
+  catalogs: Option<Catalogs>,
+}
+
+impl Aleph {
+  /// Loads the build manifest of the app in the default output dir, i.e. `<app_dir>/output/manifest.json`.
+  pub fn load(app_dir: impl AsRef<Path>) -> io::Result<Self> {
+    Self::load_with_output_dir(app_dir, "output")
+  }
+
+  /// Loads the build manifest of the app in the `build.outputDir` of the Aleph config.
+  pub fn load_with_output_dir(app_dir: impl AsRef<Path>, output_dir: impl AsRef<Path>) -> io::Result<Self> {
+    let app_dir = app_dir.as_ref().to_path_buf();
+    let json = std::fs::read_to_string(app_dir.join(output_dir).join("manifest.json"))?;
+    let manifest: BuildManifest =
+      serde_json::from_str(&json).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if manifest.version != BUILD_MANIFEST_VERSION {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+          "unsupported build manifest version {}, expected {}",
+          manifest.version, BUILD_MANIFEST_VERSION
+        ),
+      ));
+    }
+    Ok(Self(Arc::new(Inner {
+      app_dir,
+      manifest,
+      render: None,
+      routes: None,
+      client_modules: Vec::new(),
+      catalogs: None,
+    })))
+  }
+
+  /// Sets the render function of the app, e.g. `aleph::yew::render_html::<app::App>`. The pages are rendered on the
+  /// blocking threads of tokio, every thread reuses its single-threaded runtime for the renders, see
+  /// `aleph::native::render`. Without it the `index.html` is served for the client-side rendering.
+  pub fn render<F, Fut>(self, render: F) -> Self
+  where
+    F: Fn(String) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<String, String>>,
+  {
+    self.with_inner(|inner| inner.render = Some(Box::new(move |ctx| aleph::native::render(ctx, &render))))
+  }
+
+  /// Sets the route table generated by the `routes!` macro, e.g. `routes::__aleph_routes`.
+  pub fn routes(self, routes: fn() -> String) -> Self {
+    let routes = serde_json::from_str(&routes()).unwrap_or_default();
+    self.with_inner(|inner| inner.routes = Some(routes))
+  }
+
+  /// Preloads the js glue and the `*_bg.wasm` file of the wasm-bindgen module imported by the `main.ts`, e.g.
+  /// `./pkg/yew_app.js`.
+  pub fn client_module(self, specifier: impl Into<String>) -> Self {
+    let specifier = specifier.into();
+    self.with_inner(|inner| inner.client_modules.push(specifier))
+  }
+
+  /// Loads the message catalogs `<app_dir>/locales/<locale>.json` for the `I18n` of the `aleph` crate, the first
+  /// locale is the default, see the `i18n` option of `aleph/plugins/rust`. The locale is detected by the path prefix,
+  /// the `locale` cookie or the `Accept-Language` header, and the prefix is stripped before the routing, e.g.
+  /// `/zh-CN/todos` renders the `/todos` page in `zh-CN`.
+  pub fn i18n(self, locales: &[&str]) -> Self {
+    self.with_inner(|inner| inner.catalogs = Some(Catalogs::load(&inner.app_dir.join("locales"), locales)))
+  }
+
+  fn with_inner(self, f: impl FnOnce(&mut Inner)) -> Self {
+    let mut inner = Arc::try_unwrap(self.0).unwrap_or_else(|_| panic!("`Aleph` is configured after it's cloned"));
+    f(&mut inner);
+    Self(Arc::new(inner))
+  }
+
+  /// The build manifest of the app.
+  pub fn manifest(&self) -> &BuildManifest {
+    &self.0.manifest
+  }
+
+  /// Returns the router that handles all the requests, merge it into the router of the server for the other routes.
+  pub fn into_router<S>(self) -> Router<S> {
+    Router::new().fallback(handle).with_state(self)
+  }
+
+  /// Handles the request, it serves the static files, or renders the page. The other methods than `GET` and `HEAD` are
+  /// not allowed, the mutations of the route modules run in the Aleph server only.
+  pub async fn handle(&self, req: Request<Body>) -> Response {
+    let Inner { app_dir, manifest, .. } = &*self.0;
+    let pathname = req.uri().path();
+    let query = req.uri().query().unwrap_or_default();
+    if !matches!(*req.method(), Method::GET | Method::HEAD) {
+      return method_not_allowed(req.method());
+    }
+
+    if let Some(asset) = manifest.assets.get(pathname) {
+      let versioned = query
+        .split('&')
+        .any(|pair| pair.strip_prefix("v=").is_some_and(|v| !v.is_empty()));
+      let deployment_id = manifest.deployment_id.as_deref();
+      return assets::serve(app_dir, deployment_id, pathname, versioned, asset, req.headers()).await;
+    }
+
+    // don't render special asset files
+    if matches!(pathname, "/favicon.ico" | "/robots.txt") || pathname.starts_with("/-/") || pathname.starts_with("/.") {
+      return assets::not_found();
+    }
+
+    match self.render_page(&req).await {
+      Ok(Some(mut res)) => {
+        res.headers_mut().insert(
+          header::CACHE_CONTROL,
+          HeaderValue::from_static("public, max-age=0, must-revalidate"),
+        );
+        if self.0.catalogs.is_some() {
+          // the html depends on the locale detected by the headers
+          res
+            .headers_mut()
+            .insert(header::VARY, HeaderValue::from_static("Accept-Language, Cookie"));
+        }
+        res
+      }
+      Ok(None) => assets::not_found(),
+      Err(err) => {
+        log::error!("Failed to render {}: {}", req.uri(), err);
+        (StatusCode::INTERNAL_SERVER_ERROR, err).into_response()
+      }
+    }
+  }
+
+  async fn render_page(&self, req: &Request<Body>) -> Result<Option<Response>, String> {
+    let inner = self.0.clone();
+    let Inner { manifest, .. } = &*inner;
+    let index_html = match &manifest.index_html {
+      Some(index_html) => index_html,
+      None => return Ok(None),
+    };
+    let deployment_id = manifest.deployment_id.as_deref();
+    // the prefix of the locale is stripped before the routing, e.g. `/zh-CN/todos` is routed as `/todos`
+    let prefixed = inner
+      .catalogs
+      .as_ref()
+      .and_then(|catalogs| catalogs.strip_locale(req.uri().path()));
+    let pathname = prefixed.map_or(req.uri().path(), |(_, pathname)| pathname);
+    let search = req.uri().query().map(|q| format!("?{}", q)).unwrap_or_default();
+
+    let mut head_collection = inner
+      .client_modules
+      .iter()
+      .flat_map(|specifier| render::preload_links(specifier, deployment_id))
+      .collect::<Vec<_>>();
+    let locale = match prefixed {
+      Some((locale, _)) => Some(locale),
+      None => inner
+        .catalogs
+        .as_ref()
+        .and_then(|catalogs| catalogs.detect(req.headers())),
+    };
+    let mut body = None;
+    let mut status = StatusCode::OK;
+    if inner.render.is_some() {
+      let host = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or("localhost");
+      let url = format!("http://{}{}{}", host, pathname, search);
+      let route = inner
+        .routes
+        .as_ref()
+        .map(|routes| aleph::match_route(routes.iter().map(String::as_str), pathname));
+      let ctx = serde_json::to_string(&SsrInput {
+        url: &url,
+        route,
+        deployment_id,
+        i18n: locale.and_then(|locale| inner.catalogs.as_ref()?.get(locale)),
+      })
+      .map_err(|err| err.to_string())?;
+      let output = {
+        let inner = inner.clone();
+        tokio::task::spawn_blocking(move || (inner.render.as_ref().unwrap())(ctx))
+          .await
+          .map_err(|err| err.to_string())??
+      };
+      let output: SsrOutput = serde_json::from_str(&output).map_err(|err| err.to_string())?;
+      if let Some(redirect) = output.redirect {
+        let status = StatusCode::from_u16(redirect.status).unwrap_or(StatusCode::FOUND);
+        let location = HeaderValue::try_from(redirect.location).map_err(|err| err.to_string())?;
+        return Ok(Some((status, [(header::LOCATION, location)]).into_response()));
+      }
+      head_collection.extend(output.head_collection);
+      // the errors rendered by the `ErrorBoundary`, the `onError` of the Aleph server is not available here
+      for error in &output.errors {
+        log::error!("Failed to render {}: {}", req.uri(), error);
+      }
+      if let Some(code) = output.status.and_then(|code| StatusCode::from_u16(code).ok()) {
+        status = code;
+      }
+      body = Some(output.html);
+    }
+
+    Document {
+      index_html,
+      router: &manifest.router,
+      deployment_id,
+      pathname,
+      search: &search,
+      lang: locale,
+      head_collection,
+      body,
+    }
+    .render()
+    .map(|html| Some((status, Html(html)).into_response()))
+  }
+}
+
+async fn handle(State(aleph): State<Aleph>, req: Request<Body>) -> Response {
+  aleph.handle(req).await
+}
+
+fn method_not_allowed(method: &Method) -> Response {
+  let message = format!(
+    "Method {} is not allowed, the mutations of the route modules are not supported by aleph-axum",
+    method
+  );
+  (
+    StatusCode::METHOD_NOT_ALLOWED,
+    [(header::ALLOW, HeaderValue::from_static("GET, HEAD"))],
+    message,
+  )
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[tokio::test]
+  async fn method_not_allowed() {
+    let dir = std::env::temp_dir().join(format!("aleph-axum-method-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("output")).unwrap();
+    let manifest = r#"{"version":1,"indexHtml":null,"router":{"routes":[],"prefix":"./routes"},"assets":{}}"#;
+    std::fs::write(dir.join("output").join("manifest.json"), manifest).unwrap();
+    let aleph = Aleph::load(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let req = Request::post("/todos").body(Body::empty()).unwrap();
+    let res = aleph.handle(req).await;
+    assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(res.headers().get(header::ALLOW).unwrap(), "GET, HEAD");
+    let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+    assert!(String::from_utf8_lossy(&body).starts_with("Method POST is not allowed"));
+  }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The version of the build manifest supported by this crate, see `buildManifestVersion` of `server/build.ts`.
+pub const BUILD_MANIFEST_VERSION: u32 = 1;
+
+/// The manifest written to `<outputDir>/manifest.json` by the build of Aleph.js.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildManifest {
+  pub version: u32,
+  #[serde(default)]
+  pub deployment_id: Option<String>,
+  /// The `index.html` fixed for SSR, the `<ssr-body>` element is replaced with the rendered html.
+  pub index_html: Option<String>,
+  pub router: RouterManifest,
+  /// The files to serve keyed by the pathname.
+  pub assets: HashMap<String, BuildAsset>,
+}
+
+/// The router manifest of the route modules, it's injected into the html as `<script id="router-manifest">`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RouterManifest {
+  pub routes: Vec<RouteMeta>,
+  pub prefix: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RouteMeta {
+  pub filename: String,
+  pub pattern: RoutePattern,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub nesting: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RoutePattern {
+  pub pathname: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildAsset {
+  /// The file path relative to the app dir, e.g. `output/main.js`.
+  pub file: String,
+  pub content_type: String,
+  /// The precompressed files next to the file in the order of preference, e.g. `["br", "gzip"]`.
+  #[serde(default)]
+  pub encodings: Vec<String>,
+}
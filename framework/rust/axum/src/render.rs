@@ -0,0 +1,266 @@
+//! The SSR of the pages, it injects the rendered html into the `index.html` like `server/renderer.ts`.
+
+use std::collections::HashMap;
+
+use lol_html::html_content::ContentType;
+use lol_html::{element, rewrite_str, RewriteStrSettings};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Catalog;
+use crate::manifest::RouterManifest;
+use crate::router::match_routes;
+
+/// The SSR context sent to the render function, it's the JSON read by `SsrContext::from_json` of the `aleph` crate.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SsrInput<'a> {
+  pub url: &'a str,
+  // `null` if no route matched, or missing if the app doesn't use the `routes!` macro
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub route: Option<Option<aleph::RouteMatch>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub deployment_id: Option<&'a str>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub i18n: Option<&'a Catalog>,
+}
+
+/// The JSON output of the render function.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SsrOutput {
+  pub html: String,
+  #[serde(default)]
+  pub head_collection: Vec<String>,
+  /// The status of the first error rendered by the `ErrorBoundary`.
+  pub status: Option<u16>,
+  #[serde(default)]
+  pub errors: Vec<aleph::PageError>,
+  /// The redirect of the page, see `SsrContext::redirect` of the `aleph` crate.
+  pub redirect: Option<Redirect>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Redirect {
+  pub location: String,
+  pub status: u16,
+}
+
+/// The route module of the `ssr-data`, the data loaders of the route modules only run in the Aleph server.
+#[derive(Serialize)]
+struct SsrModule<'a> {
+  url: String,
+  params: &'a HashMap<String, String>,
+  filename: &'a str,
+}
+
+pub(crate) struct Document<'a> {
+  pub index_html: &'a str,
+  pub router: &'a RouterManifest,
+  pub deployment_id: Option<&'a str>,
+  /// The pathname and the search of the request url.
+  pub pathname: &'a str,
+  pub search: &'a str,
+  /// The locale detected by the message catalogs, it's set to the `lang` attribute of the `<html>`.
+  pub lang: Option<&'a str>,
+  pub head_collection: Vec<String>,
+  /// The rendered html, the `<ssr-body>` is removed if it's `None`.
+  pub body: Option<String>,
+}
+
+impl Document<'_> {
+  /// Injects the router manifest, the `ssr-data`, the modulepreload links of the route modules and the head collection
+  /// into the `<head>`, and replaces the `<ssr-body>` with the rendered html.
+  pub fn render(self) -> Result<String, String> {
+    let Document {
+      index_html,
+      router,
+      deployment_id,
+      pathname,
+      search,
+      lang,
+      head_collection,
+      body,
+    } = self;
+    let modules = match_routes(router, pathname);
+    let q = deployment_id.map(|id| format!("?v={}", id)).unwrap_or_default();
+
+    // replace "/" to "\/" to prevent xss
+    let modules_json = serde_json::to_string(
+      &modules
+        .iter()
+        .map(|matched| SsrModule {
+          url: format!("{}{}", matched.pathname, search),
+          params: &matched.params,
+          filename: &matched.meta.filename,
+        })
+        .collect::<Vec<_>>(),
+    )
+    .map_err(|err| err.to_string())?
+    .replace('/', "\\/");
+    let router_json = serde_json::to_string(router).map_err(|err| err.to_string())?;
+    let preload_links = modules
+      .iter()
+      .map(|matched| {
+        let filename = &matched.meta.filename;
+        format!(
+          r#"<link rel="modulepreload" href="{}{}" data-module-id="{}" />"#,
+          &filename[1..],
+          q,
+          filename
+        )
+      })
+      .collect::<String>();
+
+    rewrite_str(
+      index_html,
+      RewriteStrSettings {
+        element_content_handlers: vec![
+          element!("html", |el| {
+            if let Some(lang) = lang {
+              el.set_attribute("lang", lang)?;
+            }
+            Ok(())
+          }),
+          element!("head", |el| {
+            if !router.routes.is_empty() {
+              el.append(
+                &format!(
+                  r#"<script id="router-manifest" type="application/json">{}</script>"#,
+                  router_json
+                ),
+                ContentType::Html,
+              );
+            }
+            el.append(
+              &format!(
+                r#"<script id="ssr-data" type="application/json">{}</script>"#,
+                modules_json
+              ),
+              ContentType::Html,
+            );
+            el.append(&preload_links, ContentType::Html);
+            for html in head_collection.iter().filter(|html| !html.is_empty()) {
+              el.append(html, ContentType::Html);
+            }
+            Ok(())
+          }),
+          element!("ssr-body", |el| {
+            match &body {
+              Some(body) => el.replace(body, ContentType::Html),
+              None => el.remove(),
+            }
+            Ok(())
+          }),
+        ],
+        ..RewriteStrSettings::default()
+      },
+    )
+    .map_err(|err| err.to_string())
+  }
+}
+
+/// Returns the `<link>` tags to preload the js glue and the `*_bg.wasm` file of a wasm-bindgen module, it's the
+/// `preloadLinks` function of `aleph/plugins/rust`.
+pub(crate) fn preload_links(specifier: &str, deployment_id: Option<&str>) -> [String; 2] {
+  let q = deployment_id.map(|id| format!("?v={}", id)).unwrap_or_default();
+  let specifier = specifier
+    .strip_prefix("./")
+    .or_else(|| specifier.strip_prefix('/'))
+    .unwrap_or(specifier);
+  let glue = format!("/{}", specifier);
+  let wasm = format!("{}_bg.wasm", glue.strip_suffix(".js").unwrap_or(&glue));
+  [
+    format!(
+      r#"<link rel="modulepreload" href="{}{}" data-module-id="{}">"#,
+      glue, q, glue
+    ),
+    format!(
+      r#"<link rel="preload" href="{}{}" as="fetch" type="application/wasm" crossorigin>"#,
+      wasm, q
+    ),
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::manifest::{RouteMeta, RoutePattern};
+
+  const INDEX_HTML: &str =
+    r#"<!DOCTYPE html><html><head><title>App</title></head><body><ssr-body></ssr-body></body></html>"#;
+
+  fn router() -> RouterManifest {
+    let route = |filename: &str, pathname: &str| RouteMeta {
+      filename: filename.to_owned(),
+      pattern: RoutePattern {
+        pathname: pathname.to_owned(),
+      },
+      nesting: None,
+    };
+    RouterManifest {
+      routes: vec![
+        route("./routes/_app.tsx", "/_app"),
+        route("./routes/todos/:id.tsx", "/todos/:id"),
+      ],
+      prefix: "./routes".to_owned(),
+    }
+  }
+
+  #[test]
+  fn inject_the_document() {
+    let router = router();
+    let html = Document {
+      index_html: INDEX_HTML,
+      router: &router,
+      deployment_id: Some("1a2b3c"),
+      pathname: "/todos/1",
+      search: "?filter=done",
+      lang: Some("zh-CN"),
+      head_collection: vec!["<title ssr>Todos</title>".to_owned(), String::new()],
+      body: Some("<main>Todo 1</main>".to_owned()),
+    }
+    .render()
+    .unwrap();
+    assert!(html.contains(r#"<html lang="zh-CN">"#));
+    assert!(html.contains(&format!(
+      r#"<script id="router-manifest" type="application/json">{}</script>"#,
+      serde_json::to_string(&router).unwrap()
+    )));
+    assert!(html.contains(concat!(
+      r#"<script id="ssr-data" type="application/json">"#,
+      r#"[{"url":"\/_app?filter=done","params":{},"filename":".\/routes\/_app.tsx"},"#,
+      r#"{"url":"\/todos\/1?filter=done","params":{"id":"1"},"filename":".\/routes\/todos\/:id.tsx"}]</script>"#,
+    )));
+    assert!(html.contains(concat!(
+      r#"<link rel="modulepreload" href="/routes/_app.tsx?v=1a2b3c" data-module-id="./routes/_app.tsx" />"#,
+      r#"<link rel="modulepreload" href="/routes/todos/:id.tsx?v=1a2b3c" data-module-id="./routes/todos/:id.tsx" />"#,
+      r#"<title ssr>Todos</title></head>"#,
+    )));
+    assert!(html.contains("<body><main>Todo 1</main></body>"));
+    assert!(!html.contains("ssr-body"));
+  }
+
+  #[test]
+  fn remove_the_ssr_body() {
+    let router = RouterManifest {
+      routes: Vec::new(),
+      prefix: "./routes".to_owned(),
+    };
+    let html = Document {
+      index_html: INDEX_HTML,
+      router: &router,
+      deployment_id: None,
+      pathname: "/",
+      search: "",
+      lang: None,
+      head_collection: Vec::new(),
+      body: None,
+    }
+    .render()
+    .unwrap();
+    assert_eq!(
+      html,
+      r#"<!DOCTYPE html><html><head><title>App</title><script id="ssr-data" type="application/json">[]</script></head><body></body></html>"#
+    );
+  }
+}
@@ -0,0 +1,105 @@
+//! The matching of the route modules, it's a port of `matchRoutes` of `framework/core/router.ts`.
+
+use std::collections::HashMap;
+
+use crate::manifest::{RouteMeta, RouterManifest};
+
+/// A route module matched with the url.
+pub(crate) struct RouteModuleMatch<'a> {
+  /// The matched pathname, e.g. `/todos/1` or `/_app`.
+  pub pathname: String,
+  pub params: HashMap<String, String>,
+  pub meta: &'a RouteMeta,
+}
+
+fn exec<'a>(meta: &'a RouteMeta, pathname: &str) -> Option<RouteModuleMatch<'a>> {
+  aleph::match_route([meta.pattern.pathname.as_str()], pathname).map(|matched| RouteModuleMatch {
+    pathname: pathname.to_owned(),
+    params: matched.params,
+    meta,
+  })
+}
+
+fn static_match<'a>(meta: &'a RouteMeta, pathname: &str) -> RouteModuleMatch<'a> {
+  RouteModuleMatch {
+    pathname: pathname.to_owned(),
+    params: HashMap::new(),
+    meta,
+  }
+}
+
+/// Matches the pathname with the route modules, returns the `_app`, the nesting layouts and the page in order.
+pub(crate) fn match_routes<'a>(router: &'a RouterManifest, pathname: &str) -> Vec<RouteModuleMatch<'a>> {
+  let routes = &router.routes;
+  let pathname = match pathname {
+    "/" => "/",
+    pathname => pathname.trim_end_matches('/'),
+  };
+  let find = |pathname: &str| routes.iter().find(|meta| meta.pattern.pathname == pathname);
+  let mut matches = Vec::new();
+  if routes.is_empty() {
+    return matches;
+  }
+
+  // find the direct match
+  if let Some(matched) = routes
+    .iter()
+    .filter(|meta| !matches!(meta.pattern.pathname.as_str(), "/_app" | "/_404"))
+    .find_map(|meta| exec(meta, pathname))
+  {
+    matches.push(matched);
+  }
+  // find index route if no direct match
+  if matches.is_empty() {
+    let index = format!("{}/index", pathname);
+    if let Some(matched) = routes
+      .iter()
+      .filter(|meta| meta.pattern.pathname.ends_with("/index"))
+      .find_map(|meta| exec(meta, &index))
+    {
+      matches.push(matched);
+    }
+  }
+
+  if let Some(direct) = matches.last().map(|matched| matched.meta) {
+    let nest_routes = routes
+      .iter()
+      .filter(|meta| {
+        meta.nesting == Some(true)
+          && meta.pattern.pathname != "/_app"
+          && direct
+            .pattern
+            .pathname
+            .starts_with(&format!("{}/", meta.pattern.pathname))
+      })
+      .collect::<Vec<_>>();
+    // lookup nesting parent
+    let parts = pathname.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>();
+    for i in (1..parts.len()).rev() {
+      let pathname = format!("/{}", parts[..i].join("/"));
+      if let Some(matched) = nest_routes.iter().find_map(|meta| exec(meta, &pathname)) {
+        matches.insert(0, matched);
+      }
+    }
+
+    if direct.nesting == Some(true) {
+      // find index route
+      let index = format!("{}/index", pathname);
+      if let Some(matched) = find(&format!("{}/index", direct.pattern.pathname)).and_then(|meta| exec(meta, &index)) {
+        matches.push(matched);
+      }
+    }
+  }
+
+  if matches.iter().all(|matched| matched.meta.nesting == Some(true)) {
+    if let Some(meta) = find("/_404") {
+      matches.push(static_match(meta, "/_404"));
+    }
+  }
+  if !matches.is_empty() {
+    if let Some(meta) = find("/_app") {
+      matches.insert(0, static_match(meta, "/_app"));
+    }
+  }
+  matches
+}
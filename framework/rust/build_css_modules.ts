@@ -0,0 +1,7 @@
+// Builds the CSS modules of a crate, it's run by `aleph::build::css_modules()` in the `build.rs` of the crate:
+// `deno run -A build_css_modules.ts <crate dir> [--minify]`
+
+import { buildCSSModules } from "./plugin.ts";
+
+const [cwd = Deno.cwd()] = Deno.args.filter((arg) => !arg.startsWith("--"));
+await buildCSSModules({ cwd, minify: Deno.args.includes("--minify") });
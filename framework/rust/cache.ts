@@ -0,0 +1,149 @@
+import { ensureDir, path } from "../../server/deps.ts";
+import { existsDir, getDeploymentId } from "../../server/helpers.ts";
+import log from "../../server/log.ts";
+import { utf8Enc } from "../../shared/util.ts";
+
+/** The cache control of the page set by `SsrContext::cache` of the `aleph` crate. */
+export type PageCacheControl = {
+  /** The seconds the page is fresh. */
+  ttl: number;
+  tags: string[];
+};
+
+/** The cached output of the `ssr` function. */
+export type PageCacheEntry<T> = {
+  /** The pathname and the search of the page url. */
+  key: string;
+  output: T;
+  /** The time in milliseconds when the page becomes stale. */
+  expires: number;
+  tags: string[];
+  deploymentId?: string;
+};
+
+export type InvalidateOptions = {
+  /** Invalidates the pages of the path, e.g. "/todos". */
+  path?: string;
+  /** Invalidates the pages with the tag. */
+  tag?: string;
+};
+
+/**
+ * The cache of the pages rendered by the `ssr` function of the Rust apps. The entries are kept in memory and written
+ * to the `dir` as JSON files, so they survive restarts of the server.
+ */
+export class PageCache<T = unknown> {
+  #entries = new Map<string, PageCacheEntry<T>>();
+  #revalidating = new Set<string>();
+  #dir: string | null;
+
+  constructor(dir: string | null = null) {
+    this.#dir = dir;
+  }
+
+  /** Loads the entries written by the previous server of the same deployment. */
+  async load(): Promise<void> {
+    if (!this.#dir || !(await existsDir(this.#dir))) {
+      return;
+    }
+    const deploymentId = getDeploymentId();
+    for await (const entry of Deno.readDir(this.#dir)) {
+      if (!entry.isFile || !entry.name.endsWith(".json")) {
+        continue;
+      }
+      const filename = path.join(this.#dir, entry.name);
+      try {
+        const cached: PageCacheEntry<T> = JSON.parse(await Deno.readTextFile(filename));
+        if (cached.deploymentId === deploymentId) {
+          this.#entries.set(cached.key, cached);
+        } else {
+          await Deno.remove(filename);
+        }
+      } catch (err) {
+        log.warn(`Failed to load the page cache ${filename}:`, err.message);
+      }
+    }
+    log.debug(`${this.#entries.size} page(s) loaded from the cache`);
+  }
+
+  /** Returns the cached output of the page, it may be stale, check `isStale` for it. */
+  get(key: string): PageCacheEntry<T> | undefined {
+    return this.#entries.get(key);
+  }
+
+  isStale(entry: PageCacheEntry<T>): boolean {
+    return entry.expires <= Date.now();
+  }
+
+  /** Caches the output of the page with the cache control. */
+  async set(key: string, output: T, control: PageCacheControl): Promise<void> {
+    const entry: PageCacheEntry<T> = {
+      key,
+      output,
+      expires: Date.now() + control.ttl * 1000,
+      tags: control.tags,
+      deploymentId: getDeploymentId(),
+    };
+    this.#entries.set(key, entry);
+    if (this.#dir) {
+      try {
+        await ensureDir(this.#dir);
+        await Deno.writeTextFile(await this.#filename(key), JSON.stringify(entry));
+      } catch (err) {
+        // e.g. the file system is read-only
+        log.debug(`Failed to write the page cache of ${key}:`, err.message);
+      }
+    }
+  }
+
+  /**
+   * Regenerates the stale page in the background, the requests are served from the stale page until it's done. The
+   * `render` function returns `null` if the page is not cacheable any more.
+   */
+  revalidate(key: string, render: () => Promise<[output: T, control: PageCacheControl] | null>): void {
+    if (this.#revalidating.has(key)) {
+      return;
+    }
+    this.#revalidating.add(key);
+    render().then(async (ret) => {
+      if (ret) {
+        await this.set(key, ...ret);
+      } else {
+        await this.#delete(key);
+      }
+    }).catch((err) => {
+      log.error(`Failed to regenerate the page ${key}:`, err);
+    }).finally(() => {
+      this.#revalidating.delete(key);
+    });
+  }
+
+  /** Invalidates the pages by the path or the tag, returns the count of the invalidated pages. */
+  async invalidate({ path: pathname, tag }: InvalidateOptions): Promise<number> {
+    const keys = Array.from(this.#entries.values()).filter((entry) =>
+      (pathname !== undefined && new URL(entry.key, "http://localhost").pathname === pathname) ||
+      (tag !== undefined && entry.tags.includes(tag))
+    ).map((entry) => entry.key);
+    await Promise.all(keys.map((key) => this.#delete(key)));
+    return keys.length;
+  }
+
+  async #delete(key: string): Promise<void> {
+    this.#entries.delete(key);
+    if (this.#dir) {
+      try {
+        await Deno.remove(await this.#filename(key));
+      } catch (err) {
+        if (!(err instanceof Deno.errors.NotFound)) {
+          log.debug(`Failed to remove the page cache of ${key}:`, err.message);
+        }
+      }
+    }
+  }
+
+  async #filename(key: string): Promise<string> {
+    const hash = await crypto.subtle.digest("SHA-1", utf8Enc.encode(key));
+    const hex = Array.from(new Uint8Array(hash)).map((b) => b.toString(16).padStart(2, "0")).join("");
+    return path.join(this.#dir!, hex + ".json");
+  }
+}
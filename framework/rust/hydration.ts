@@ -0,0 +1,140 @@
+// The hydration check of the Rust apps in development, the renderer of `aleph/plugins/rust` imports it in the SSR
+// output. It clones the server markup before the app is hydrated, then compares it with the hydrated DOM once the
+// Rust runtime emits the `hydrated` event.
+
+import events from "../core/events.ts";
+import { type HydrationMismatch, showHydrationMismatch } from "../core/error.ts";
+
+// the max length of the nodes printed in the overlay
+const maxNodeLength = 300;
+
+// the attributes added by the runtimes
+const ignoredAttrs = new Set(["data-hk"]);
+
+// the elements not rendered by the app, e.g. the scripts of the head collection and the error overlay
+const isIgnoredElement = (el: Element) =>
+  ["SCRIPT", "STYLE", "LINK", "TEMPLATE"].includes(el.tagName) || el.classList.contains("aleph--error-modal");
+
+type Entry = {
+  node: Element | Text;
+  components: string[];
+};
+
+/** Starts the hydration check, the mismatches are shown in the error overlay and logged to the console. */
+export function watchHydration() {
+  if (Reflect.get(globalThis, "__ALEPH_RUST_HYDRATED")) {
+    console.warn("[aleph] The app is hydrated before the hydration check is loaded, skipped.");
+    return;
+  }
+  const snapshot = document.body.cloneNode(true) as HTMLElement;
+  let checked = false;
+  const check = () => {
+    if (checked) {
+      return;
+    }
+    checked = true;
+    events.off("hydrated", onHydrated);
+    removeEventListener("error", check);
+    const mismatches = compareHydration(snapshot, document.body);
+    if (mismatches.length > 0) {
+      mismatches.forEach(({ components, path, server, client }) => {
+        console.warn(`[aleph] Hydration mismatch in ${components.join(" > ")} at ${path}`, { server, client });
+      });
+      showHydrationMismatch(mismatches);
+    }
+  };
+  // the suspended components are hydrated later, they keep the server markup until then
+  const onHydrated = () => setTimeout(check, 0);
+  events.on("hydrated", onHydrated);
+  // the Yew app panics if the structure of the DOM doesn't match
+  addEventListener("error", check);
+}
+
+/** Compares the children of the server markup with the hydrated DOM, the children of a mismatched node are skipped. */
+export function compareHydration(server: Element, client: Element, path = "body"): HydrationMismatch[] {
+  const mismatches: HydrationMismatch[] = [];
+  const walk = (server: Element, client: Element, components: string[], path: string) => {
+    const serverEntries = childEntries(server, components);
+    const clientEntries = childEntries(client, []);
+    const len = Math.max(serverEntries.length, clientEntries.length);
+    for (let i = 0; i < len; i++) {
+      const s = serverEntries[i];
+      const c = clientEntries[i]?.node;
+      if (!s || !c || !isSameNode(s.node, c)) {
+        mismatches.push({
+          components: (s ?? serverEntries.at(-1))?.components ?? components,
+          path,
+          server: s ? printNode(s.node) : "",
+          client: c ? printNode(c) : "",
+        });
+        continue;
+      }
+      if (s.node instanceof Element) {
+        walk(s.node, c as Element, s.components, path + " > " + describe(s.node));
+      }
+    }
+  };
+  walk(server, client, [], path);
+  return mismatches;
+}
+
+/**
+ * Returns the elements and the texts of the parent, the adjacent texts are joined since the markers of the components
+ * are removed by Yew. The components are read from the markers, `<!--<[name]>-->` of Yew and
+ * `<!--hk=0-0|leptos-name-start-->` of Leptos in the debug build.
+ */
+function childEntries(parent: Element, components: string[]): Entry[] {
+  const entries: Entry[] = [];
+  let stack = components;
+  let text: string | null = null;
+  const flushText = () => {
+    if (text !== null && text.trim() !== "") {
+      entries.push({ node: document.createTextNode(text), components: stack });
+    }
+    text = null;
+  };
+  for (const node of parent.childNodes) {
+    if (node.nodeType === Node.COMMENT_NODE) {
+      const data = (node as Comment).data;
+      const yew = data.match(/^<(\/?)\[(.+)\]>$/);
+      const leptos = data.match(/\|leptos-(.+)-(start|end)$/);
+      if (yew?.[1] === "" || leptos?.[2] === "start") {
+        stack = [...stack, yew?.[2] ?? leptos![1]];
+      } else if (yew || leptos) {
+        stack = stack.slice(0, -1);
+      }
+    } else if (node.nodeType === Node.TEXT_NODE) {
+      text = (text ?? "") + (node as Text).data;
+    } else if (node instanceof Element && !isIgnoredElement(node)) {
+      flushText();
+      entries.push({ node, components: stack });
+    }
+  }
+  flushText();
+  return entries;
+}
+
+function isSameNode(a: Element | Text, b: Element | Text): boolean {
+  if (a instanceof Text || b instanceof Text) {
+    return a instanceof Text && b instanceof Text && a.data.trim() === b.data.trim();
+  }
+  if (a.tagName !== b.tagName) {
+    return false;
+  }
+  const attrs = (el: Element) => {
+    const names = el.getAttributeNames().filter((name) => !ignoredAttrs.has(name)).sort();
+    return names.map((name) => `${name}=${el.getAttribute(name)}`).join(" ");
+  };
+  return attrs(a) === attrs(b);
+}
+
+function printNode(node: Element | Text): string {
+  const html = node instanceof Text
+    ? JSON.stringify(node.data.trim())
+    : node.outerHTML.replace(/<!--.*?-->/g, "").replace(/\s+/g, " ");
+  return html.length > maxNodeLength ? html.slice(0, maxNodeLength) + "…" : html;
+}
+
+function describe(el: Element): string {
+  return el.tagName.toLowerCase() + (el.id ? "#" + el.id : "") + [...el.classList].map((name) => "." + name).join("");
+}
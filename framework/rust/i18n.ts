@@ -0,0 +1,142 @@
+import { path } from "../../server/deps.ts";
+import { getAppDir } from "../../server/helpers.ts";
+import log from "../../server/log.ts";
+import { isPlainObject } from "../../shared/util.ts";
+
+export type I18nOptions = {
+  /** The supported locales, e.g. ["en", "zh-CN"], the first one is the default locale. */
+  locales: string[];
+  /** The directory of the message catalogs, e.g. "./locales/en.json", default is "./locales". */
+  dir?: string;
+  /** The cookie that stores the locale chosen by the user, default is "locale". */
+  cookie?: string;
+};
+
+/** The messages of a locale sent to the `ssr` function, see `I18n` of the crate. */
+export type Catalog = {
+  locale: string;
+  messages: Record<string, string>;
+};
+
+/**
+ * The message catalogs of the Rust app. The catalogs are JSON files named by the locale, the nested objects are
+ * flattened to the keys joined by dots:
+ *
+ * ```json
+ * { "index": { "title": "The Fullstack Framework in Deno." }, "todos": { "left": "{count} items left" } }
+ * ```
+ */
+export class Catalogs {
+  #options: I18nOptions;
+  #catalogs = new Map<string, Catalog>();
+
+  constructor(options: I18nOptions) {
+    if (options.locales.length === 0) {
+      throw new Error("The `i18n.locales` option of the rust plugin is empty");
+    }
+    this.#options = options;
+  }
+
+  get defaultLocale(): string {
+    return this.#options.locales[0];
+  }
+
+  /** Loads the catalogs of all the locales, the missing messages of a locale fall back to the default locale. */
+  async load(): Promise<void> {
+    const { locales, dir = "./locales" } = this.#options;
+    const catalogs = await Promise.all(locales.map(async (locale) => {
+      const filename = path.join(getAppDir(), dir, locale + ".json");
+      try {
+        return flattenMessages(JSON.parse(await Deno.readTextFile(filename)));
+      } catch (err) {
+        if (err instanceof Deno.errors.NotFound) {
+          log.warn(`The catalog of the locale "${locale}" is not found: ${filename}`);
+          return {};
+        }
+        throw new Error(`Failed to load the catalog ${filename}: ${err.message}`);
+      }
+    }));
+    const defaultMessages = catalogs[0];
+    locales.forEach((locale, index) => {
+      const messages = catalogs[index];
+      const missing = Object.keys(defaultMessages).filter((key) => !(key in messages));
+      if (index > 0 && missing.length > 0) {
+        log.debug(`The catalog of "${locale}" misses ${missing.length} message(s): ${missing.join(", ")}`);
+      }
+      this.#catalogs.set(locale, { locale, messages: { ...defaultMessages, ...messages } });
+    });
+  }
+
+  /** Returns the catalog of the locale, or the default locale if it's not supported. */
+  get(locale: string): Catalog {
+    return this.#catalogs.get(locale) ?? this.#catalogs.get(this.defaultLocale) ??
+      { locale: this.defaultLocale, messages: {} };
+  }
+
+  /**
+   * Strips the prefix of a supported locale from the pathname case-insensitively, e.g. "/zh-cn/todos" returns
+   * `["zh-CN", "/todos"]`. Returns `null` if the first segment of the path is not a locale.
+   */
+  stripLocale(pathname: string): [locale: string, pathname: string] | null {
+    const [segment] = pathname.slice(1).split("/", 1);
+    const locale = segment && resolveLocale(this.#options.locales, segment);
+    if (!locale) {
+      return null;
+    }
+    return [locale, pathname.slice(segment.length + 1) || "/"];
+  }
+
+  /**
+   * Detects the locale of the request by the cookie and the `Accept-Language` header. The locale prefix of the path is
+   * stripped before the routing, see `stripLocale`.
+   */
+  detect(headers?: Headers): string {
+    const { locales, cookie = "locale" } = this.#options;
+    const cookieValue = headers?.get("cookie")?.split(";").map((part) => part.trim().split("=", 2))
+      .find(([name]) => name === cookie)?.[1];
+    const fromCookie = cookieValue && resolveLocale(locales, cookieValue);
+    if (fromCookie) {
+      return fromCookie;
+    }
+    for (const lang of parseAcceptLanguage(headers?.get("accept-language") ?? "")) {
+      // falls back to the locale of the same language, e.g. "zh-TW" matches "zh-CN"
+      const language = lang.split("-")[0].toLowerCase();
+      const locale = resolveLocale(locales, lang) ??
+        locales.find((locale) => locale.split("-")[0].toLowerCase() === language);
+      if (locale) {
+        return locale;
+      }
+    }
+    return this.defaultLocale;
+  }
+}
+
+/** Matches the language tag with the supported locales case-insensitively, e.g. "zh-cn" matches "zh-CN". */
+function resolveLocale(locales: string[], tag: string): string | undefined {
+  const lower = tag.toLowerCase();
+  return locales.find((locale) => locale.toLowerCase() === lower);
+}
+
+/** Parses the `Accept-Language` header, returns the languages ordered by the quality. */
+export function parseAcceptLanguage(header: string): string[] {
+  return header.split(",").map((part, index) => {
+    const [lang, ...params] = part.trim().split(";");
+    const q = params.map((p) => p.trim()).find((p) => p.startsWith("q="));
+    return { lang: lang.trim(), q: q ? Number(q.slice(2)) : 1, index };
+  }).filter(({ lang, q }) => lang && lang !== "*" && q > 0)
+    .sort((a, b) => b.q - a.q || a.index - b.index)
+    .map(({ lang }) => lang);
+}
+
+/** Flattens the nested messages, e.g. `{ "index": { "title": "..." } }` to `{ "index.title": "..." }`. */
+export function flattenMessages(messages: Record<string, unknown>, prefix = ""): Record<string, string> {
+  const flattened: Record<string, string> = {};
+  for (const [key, value] of Object.entries(messages)) {
+    if (isPlainObject(value)) {
+      Object.assign(flattened, flattenMessages(value, prefix + key + "."));
+    } else {
+      flattened[prefix + key] = String(value);
+    }
+  }
+  return flattened;
+}
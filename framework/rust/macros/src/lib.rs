@@ -0,0 +1,199 @@
+//! The macros of the Rust runtime for Aleph.js apps, re-exported by the `aleph` crate.
+
+mod routes;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Ident, LitStr};
+
+/// The manifest of the CSS modules written by `buildCSSModules()` of `aleph/plugins/rust`, relative to the crate root.
+/// The `aleph::build::css_modules()` helper runs it in the `build.rs` of the crate.
+const CSS_MODULES_MANIFEST: &str = "target/aleph/css_modules.json";
+
+/// Imports a `.module.css` file, the path is relative to the crate root.
+///
+/// The class names are hashed by the CSS pipeline of Aleph.js at compile time, and are available as fields:
+///
+/// ```ignore
+/// let styles = css_module!("src/components/header.module.css");
+/// html! { <header class={styles.header}></header> }
+/// ```
+#[proc_macro]
+pub fn css_module(input: TokenStream) -> TokenStream {
+  let lit = parse_macro_input!(input as LitStr);
+  match expand_css_module(&lit) {
+    Ok(tokens) => tokens.into(),
+    Err(message) => syn::Error::new(lit.span(), message).to_compile_error().into(),
+  }
+}
+
+fn expand_css_module(lit: &LitStr) -> Result<proc_macro2::TokenStream, String> {
+  let root = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").map_err(|err| err.to_string())?);
+  let manifest_path = root.join(CSS_MODULES_MANIFEST);
+  let manifest = std::fs::read_to_string(&manifest_path).map_err(|_| {
+    format!(
+      "can't find the CSS modules manifest `{}`, call `aleph::build::css_modules()` in the `build.rs` of the crate, or \
+       `buildCSSModules()` of `aleph/plugins/rust` before building",
+      manifest_path.display()
+    )
+  })?;
+  let manifest: serde_json::Value = serde_json::from_str(&manifest).map_err(|err| err.to_string())?;
+
+  let specifier = format!("./{}", lit.value().trim_start_matches("./"));
+  let module = manifest
+    .get(&specifier)
+    .ok_or_else(|| format!("CSS module `{}` not found in the manifest", specifier))?;
+  let css = module.get("css").and_then(|css| css.as_str()).unwrap_or_default();
+  let mut exports = module
+    .get("exports")
+    .and_then(|exports| exports.as_object())
+    .map(|exports| {
+      exports
+        .iter()
+        .map(|(name, value)| (name.clone(), value.as_str().unwrap_or_default().to_owned()))
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+  exports.sort();
+
+  let fields = field_idents(&specifier, &exports)?;
+  let values = exports.iter().map(|(_, value)| value);
+  let manifest_path = path_str(&manifest_path);
+  let css_path = path_str(&root.join(&specifier));
+
+  Ok(quote! {
+    {
+      // rebuild when the css or the manifest changes
+      const _: &[u8] = ::core::include_bytes!(#manifest_path);
+      const _: &[u8] = ::core::include_bytes!(#css_path);
+      #[allow(non_snake_case)]
+      struct CssModuleClasses {
+        #(#fields: &'static str,)*
+      }
+      ::aleph::CssModule::new(#specifier, #css, CssModuleClasses {
+        #(#fields: #values,)*
+      })
+    }
+  })
+}
+
+/// Generates the `Route` enum and the `switch` function of Yew from the files of the routes directory, default is
+/// `src/routes`. It follows the routing conventions of Aleph.js:
+///
+/// - `index.rs` matches the path of the directory, e.g. `routes/todos/index.rs` matches `/todos`.
+/// - `[id].rs` or `$id.rs` matches a segment, and `[...path].rs` matches the rest segments, e.g. `routes/todos/[id].rs`
+///   matches `/todos/:id`. The page module exports the `Params` that implements `Serialize` and `Deserialize`, it's
+///   parsed from the params of the route and passed to the `Page` as the `params` prop.
+/// - `_404.rs` is rendered for the unmatched urls.
+/// - `_app.rs` wraps all the pages, and `_layout.rs` wraps the pages of its directory and the nested directories,
+///   e.g. `routes/dashboard/_layout.rs` wraps `/dashboard/*`. Their `App` and `Layout` components render the page
+///   through the `<Outlet/>`, and load their data with `use_loader`. The macro generates Yew code only, Leptos apps
+///   nest the `<Route>`s of `leptos_router` and render the layouts through its `<Outlet/>` instead.
+///
+/// Every page module exports a `Page` component, or declares the `CHUNK` const to split the page into its own wasm
+/// module, e.g. `pub const CHUNK: &str = "/pkg/todos/todos.js";`. The file of a split page only declares the const,
+/// it's not compiled into the crate. The chunk is a crate exporting the `ssr_island` and `hydrate_island` functions,
+/// it's named by the route pattern and receives the params as the JSON props of strings, see the `RouteChunk` and the
+/// `ChunkParams` of `aleph::yew`. The chunks are Yew-only too, a Leptos app is compiled into one wasm module. Cargo
+/// doesn't rebuild the crate when a new page is added, so add `println!("cargo:rerun-if-changed=src/routes")` to the
+/// `build.rs` of the crate.
+///
+/// The route table is exported to the Aleph server as the `__aleph_routes` function of the wasm module, the server
+/// matches the routes with the same URL pattern engine of Aleph.js for the `<Switch>` of `aleph::yew`.
+///
+/// ```ignore
+/// // src/routes.rs
+/// aleph::yew::routes!();
+///
+/// // src/routes/todos/[id].rs
+/// #[derive(Clone, PartialEq, Serialize, Deserialize)]
+/// pub struct Params {
+///   pub id: u32,
+/// }
+///
+/// // src/app.rs
+/// html! { <BrowserRouter><Switch<Route> render={switch} /></BrowserRouter> }
+/// ```
+#[proc_macro]
+pub fn routes(input: TokenStream) -> TokenStream {
+  let lit = if input.is_empty() {
+    LitStr::new("src/routes", proc_macro2::Span::call_site())
+  } else {
+    parse_macro_input!(input as LitStr)
+  };
+  match routes::expand_routes(&lit) {
+    Ok(tokens) => tokens.into(),
+    Err(message) => syn::Error::new(lit.span(), message).to_compile_error().into(),
+  }
+}
+
+/// Converts the class names of the CSS module to the field names, the names converted to the same field are reported.
+fn field_idents(specifier: &str, exports: &[(String, String)]) -> Result<Vec<Ident>, String> {
+  let fields = exports
+    .iter()
+    .map(|(name, _)| field_ident(name))
+    .collect::<Result<Vec<_>, _>>()?;
+  // `a-b` and `a_b` are both converted to `a_b`
+  let mut names = HashMap::new();
+  for (field, (name, _)) in fields.iter().zip(exports) {
+    if let Some(other) = names.insert(field.to_string(), name) {
+      return Err(format!(
+        "the class names `{other}` and `{name}` of the CSS module `{specifier}` are both converted to the field \
+         `{field}`, rename one of them"
+      ));
+    }
+  }
+  Ok(fields)
+}
+
+/// Converts the class name to a field name, e.g. `header-wrapper` to `header_wrapper`. The keywords are converted to
+/// raw identifiers, e.g. `r#type`, except `self`, `Self`, `super` and `crate` that can't be a field name.
+fn field_ident(name: &str) -> Result<Ident, String> {
+  let field = name.replace('-', "_");
+  if let Ok(ident) = syn::parse_str::<Ident>(&field) {
+    return Ok(ident);
+  }
+  let is_raw = !matches!(field.as_str(), "self" | "Self" | "super" | "crate" | "_")
+    && syn::parse_str::<Ident>(&format!("r#{field}")).is_ok();
+  if is_raw {
+    Ok(Ident::new_raw(&field, proc_macro2::Span::call_site()))
+  } else {
+    Err(format!(
+      "the class name `{name}` of the CSS module can't be converted to a field, rename it"
+    ))
+  }
+}
+
+fn path_str(path: &Path) -> String {
+  path.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn field_names() {
+    assert_eq!(field_ident("header-wrapper").unwrap().to_string(), "header_wrapper");
+    assert_eq!(field_ident("type").unwrap().to_string(), "r#type");
+    for name in ["self", "Self", "super", "crate", "_", "1col"] {
+      assert!(field_ident(name).is_err(), "{name}");
+    }
+  }
+
+  #[test]
+  fn field_name_collisions() {
+    let exports = |names: &[&str]| {
+      names
+        .iter()
+        .map(|name| (name.to_string(), String::new()))
+        .collect::<Vec<_>>()
+    };
+    assert!(field_idents("./app.module.css", &exports(&["a-b", "b"])).is_ok());
+    let err = field_idents("./app.module.css", &exports(&["a-b", "a_b"])).unwrap_err();
+    assert!(err.contains("`a-b` and `a_b`"), "{err}");
+  }
+}
@@ -0,0 +1,419 @@
+//! The file-system routing of Yew apps, following the conventions of the routes directory of Aleph.js.
+
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Ident, LitStr};
+
+use crate::{field_ident, path_str};
+
+/// A page module of the routes directory.
+struct Page {
+  /// The path of the file relative to the routes directory, without the extension, e.g. `todos/[id]`.
+  specifier: String,
+  filename: PathBuf,
+  /// The URL pattern of the route, e.g. `/todos/:id`.
+  path: String,
+  /// The route has dynamic segments, the `Params` of the page module are passed to the `Page` component.
+  dynamic: bool,
+  module: Ident,
+  variant: Ident,
+  /// The wasm module of the `CHUNK` const, the page is split into it and rendered by the `RouteChunk` of `aleph::yew`.
+  chunk: Option<String>,
+}
+
+pub fn expand_routes(lit: &LitStr) -> Result<TokenStream, String> {
+  let root = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").map_err(|err| err.to_string())?);
+  expand_routes_dir(&root.join(lit.value().trim_start_matches("./")))
+}
+
+fn expand_routes_dir(dir: &Path) -> Result<TokenStream, String> {
+  let mut files = Vec::new();
+  walk(dir, &mut files).map_err(|_| format!("can't read the routes directory `{}`", dir.display()))?;
+  files.sort();
+
+  let mut app = None;
+  let mut not_found = None;
+  // the layouts by directory, e.g. `dashboard` for `dashboard/_layout.rs`, the root layout is ``
+  let mut layouts: Vec<(String, Ident)> = Vec::new();
+  let mut modules = Vec::new();
+  let mut pages: Vec<Page> = Vec::new();
+  for filename in files {
+    let specifier = filename
+      .strip_prefix(dir)
+      .unwrap()
+      .with_extension("")
+      .components()
+      .map(|c| c.as_os_str().to_string_lossy().into_owned())
+      .collect::<Vec<_>>()
+      .join("/");
+    match specifier.as_str() {
+      "_app" => app = Some(filename),
+      "_404" => not_found = Some(filename),
+      _ if specifier == "_layout" || specifier.ends_with("/_layout") => {
+        let dir = specifier.trim_end_matches("_layout").trim_end_matches('/').to_owned();
+        let module = if dir.is_empty() {
+          format_ident!("_layout")
+        } else {
+          let mut words = words(dir.split('/'));
+          words.push("layout");
+          ident(&words.join("_").to_ascii_lowercase(), "_")
+        };
+        modules.push(module_decl(&module, &filename));
+        layouts.push((dir, module));
+      }
+      // the other files starting with `_` are not pages, e.g. `_export.rs`
+      _ if specifier.split('/').any(|part| part.starts_with('_')) => {}
+      _ => {
+        let page = page(specifier, filename)?;
+        if let Some(dup) = pages.iter().find(|p| p.path == page.path || p.variant == page.variant) {
+          return Err(format!(
+            "the routes `{}` and `{}` are conflicting, rename one of them",
+            dup.specifier, page.specifier
+          ));
+        }
+        pages.push(page);
+      }
+    }
+  }
+
+  // the layouts are wrapped from the innermost one, the files are sorted by name so `dashboard/[team]/_layout.rs` comes
+  // before `dashboard/_layout.rs`
+  layouts.sort_by_key(|(dir, _)| dir.split('/').filter(|part| !part.is_empty()).count());
+
+  // the route table is matched in order by both the Rust router and the Aleph server, the static segments first
+  pages.sort_by_key(|page| priority(&page.path));
+
+  let mut variants = Vec::new();
+  let mut arms = Vec::new();
+  let mut from_path = Vec::new();
+  let mut to_path = Vec::new();
+  let mut patterns = Vec::new();
+  let mut chunks = Vec::new();
+  for Page {
+    specifier,
+    filename,
+    path,
+    dynamic,
+    module,
+    variant,
+    chunk,
+  } in &pages
+  {
+    match chunk {
+      // the page of a chunk is compiled into the chunk crate, only its `CHUNK` const is read
+      Some(_) => modules.push(track_file(filename)),
+      None => modules.push(module_decl(module, filename)),
+    }
+    patterns.push(path);
+    // wraps the page with the layouts of its directories, from the innermost one
+    let mut page = match (chunk, *dynamic) {
+      (Some(chunk), true) => quote! {
+        ::yew::html! {
+          <::aleph::yew::RouteChunk module={#chunk} name={#path} props={::aleph::yew::chunk_props(&params)} />
+        }
+      },
+      (Some(chunk), false) => quote! { ::yew::html! { <::aleph::yew::RouteChunk module={#chunk} name={#path} /> } },
+      (None, true) => quote! { ::yew::html! { <#module::Page {params} /> } },
+      (None, false) => quote! { ::yew::html! { <#module::Page /> } },
+    };
+    if let Some(chunk) = chunk {
+      chunks.push(quote! { (#path, #chunk) });
+    }
+    for (dir, layout) in layouts.iter().rev() {
+      if dir.is_empty() || specifier.starts_with(&format!("{}/", dir)) {
+        page = with_outlet(page, quote! { #layout::Layout });
+      }
+    }
+    if *dynamic {
+      // the params of a chunk are passed to the chunk crate as the JSON props
+      let params = match chunk {
+        Some(_) => quote! { ::aleph::yew::ChunkParams },
+        None => quote! { #module::Params },
+      };
+      variants.push(quote! { #variant(#params) });
+      arms.push(quote! { Route::#variant(params) => #page });
+      from_path.push(quote! { #path => ::aleph::yew::parse_params(params).map(Route::#variant) });
+      to_path.push(quote! { Route::#variant(params) => ::aleph::yew::format_path(#path, params) });
+    } else {
+      variants.push(quote! { #variant });
+      arms.push(quote! { Route::#variant => #page });
+      from_path.push(quote! { #path => Some(Route::#variant) });
+      to_path.push(quote! { Route::#variant => #path.to_owned() });
+    }
+  }
+  let not_found_route = match not_found {
+    Some(filename) => {
+      let module = format_ident!("_404");
+      modules.push(module_decl(&module, &filename));
+      variants.push(quote! { NotFound });
+      arms.push(quote! { Route::NotFound => ::yew::html! { <_404::Page /> } });
+      to_path.push(quote! { Route::NotFound => "/404".to_owned() });
+      quote! { Some(Route::NotFound) }
+    }
+    None => quote! { None },
+  };
+  let page = match app {
+    Some(filename) => {
+      let module = format_ident!("_app");
+      modules.push(module_decl(&module, &filename));
+      with_outlet(quote! { page }, quote! { _app::App })
+    }
+    None => quote! { page },
+  };
+
+  Ok(quote! {
+    #(#modules)*
+
+    /// The routes generated from the routes directory.
+    #[derive(Clone, PartialEq)]
+    pub enum Route {
+      #(#variants,)*
+    }
+
+    impl ::yew_router::Routable for Route {
+      fn from_path(path: &str, params: &::std::collections::HashMap<&str, &str>) -> Option<Self> {
+        match path {
+          #(#from_path,)*
+          _ => None,
+        }
+      }
+
+      fn to_path(&self) -> String {
+        match self {
+          #(#to_path,)*
+        }
+      }
+
+      fn routes() -> Vec<&'static str> {
+        vec![#(#patterns),*]
+      }
+
+      fn not_found_route() -> Option<Self> {
+        #not_found_route
+      }
+
+      fn recognize(pathname: &str) -> Option<Self> {
+        ::aleph::yew::recognize(pathname)
+      }
+    }
+
+    /// Renders the page of the route, use it as the `render` of the `<Switch<Route>>` of `aleph::yew`.
+    pub fn switch(route: Route) -> ::yew::Html {
+      ::aleph::yew::register_route_chunks(&[#(#chunks),*]);
+      let page = match route {
+        #(#arms,)*
+      };
+      ::yew::html! { <::yew::suspense::Suspense>{#page}</::yew::suspense::Suspense> }
+    }
+
+    /// Returns the route table for the Aleph server to match the routes in the server-side rendering.
+    #[::wasm_bindgen::prelude::wasm_bindgen]
+    pub fn __aleph_routes() -> String {
+      ::aleph::yew::routes_manifest::<Route>()
+    }
+  })
+}
+
+/// The priority of the route pattern per segment: the static segments first, then `:param`, then `:rest+`.
+fn priority(path: &str) -> Vec<u8> {
+  path
+    .split('/')
+    .filter(|segment| !segment.is_empty())
+    .map(|segment| match segment.strip_prefix(':') {
+      Some(name) if name.ends_with('+') => 2,
+      Some(_) => 1,
+      None => 0,
+    })
+    .collect()
+}
+
+fn module_decl(module: &Ident, filename: &Path) -> TokenStream {
+  let filename = path_str(filename);
+  quote! {
+    #[path = #filename]
+    pub mod #module;
+  }
+}
+
+/// Rebuilds the crate when the file changes, for the pages of the chunks that are not declared as modules.
+fn track_file(filename: &Path) -> TokenStream {
+  let filename = path_str(filename);
+  quote! {
+    const _: &[u8] = ::core::include_bytes!(#filename);
+  }
+}
+
+/// Renders the layout with the content of its `<Outlet/>`.
+fn with_outlet(content: TokenStream, layout: TokenStream) -> TokenStream {
+  quote! {
+    ::yew::html! {
+      <::yew::ContextProvider<::aleph::yew::OutletContext> context={::aleph::yew::OutletContext(#content)}>
+        <#layout />
+      </::yew::ContextProvider<::aleph::yew::OutletContext>>
+    }
+  }
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+  for entry in std::fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      walk(&path, files)?;
+    } else if path.extension().is_some_and(|ext| ext == "rs") {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+fn page(specifier: String, filename: PathBuf) -> Result<Page, String> {
+  let chunk = chunk_module(&filename)?;
+  let mut parts = specifier.split('/').collect::<Vec<_>>();
+  if parts.last() == Some(&"index") {
+    parts.pop();
+  }
+  let mut path = String::new();
+  let mut dynamic = false;
+  let mut segments = Vec::new();
+  for part in parts {
+    path.push('/');
+    if let Some(name) = part.strip_prefix("[...").and_then(|s| s.strip_suffix(']')) {
+      // `/blog/[...path]` matches `/blog/:path+`
+      path.push(':');
+      path.push_str(name);
+      path.push('+');
+      dynamic = true;
+      segments.push(name);
+    } else if let Some(name) = part
+      .strip_prefix('[')
+      .and_then(|s| s.strip_suffix(']'))
+      .or_else(|| part.strip_prefix('$'))
+    {
+      // `/blog/[id]` and `/blog/$id` match `/blog/:id`
+      path.push(':');
+      path.push_str(name);
+      dynamic = true;
+      segments.push(name);
+    } else {
+      path.push_str(part);
+      segments.push(part);
+    }
+  }
+  if path.is_empty() {
+    path.push('/');
+    segments.push("index");
+  }
+  let words = words(segments.into_iter());
+  let variant = words
+    .iter()
+    .map(|word| {
+      let mut chars = word.chars();
+      chars
+        .next()
+        .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+    })
+    .collect::<String>();
+  let module = words
+    .iter()
+    .map(|word| word.to_ascii_lowercase())
+    .collect::<Vec<_>>()
+    .join("_");
+  Ok(Page {
+    specifier,
+    filename,
+    path,
+    dynamic,
+    module: ident(&module, "_"),
+    variant: ident(&variant, "Page"),
+    chunk,
+  })
+}
+
+/// Reads the `CHUNK` const of the page module, e.g. `pub const CHUNK: &str = "/pkg/todos/todos.js";`.
+fn chunk_module(filename: &Path) -> Result<Option<String>, String> {
+  let source =
+    std::fs::read_to_string(filename).map_err(|_| format!("can't read the page `{}`", filename.display()))?;
+  // the syntax errors are reported by the compiler with the module
+  let file = match syn::parse_file(&source) {
+    Ok(file) => file,
+    Err(_) => return Ok(None),
+  };
+  let chunk = file.items.iter().find_map(|item| match item {
+    syn::Item::Const(item) if item.ident == "CHUNK" => Some(item),
+    _ => None,
+  });
+  match chunk.map(|item| &*item.expr) {
+    Some(syn::Expr::Lit(syn::ExprLit {
+      lit: syn::Lit::Str(lit),
+      ..
+    })) => Ok(Some(lit.value())),
+    Some(_) => Err(format!(
+      "the `CHUNK` const of the page `{}` must be a string literal",
+      filename.display()
+    )),
+    None => Ok(None),
+  }
+}
+
+/// Splits the path segments into the words of the idents, e.g. `[todo-id]` to `todo` and `id`.
+fn words<'a>(segments: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+  segments
+    .flat_map(|segment| segment.split(|c: char| !c.is_ascii_alphanumeric()))
+    .filter(|word| !word.is_empty())
+    .collect()
+}
+
+/// Creates the ident, the `prefix` is added if the name can't be an ident, e.g. the `/2023` or `/self` route.
+fn ident(name: &str, prefix: &str) -> Ident {
+  field_ident(name).unwrap_or_else(|_| format_ident!("{}{}", prefix, name))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn nest_the_layouts_by_depth() {
+    let dir = std::env::temp_dir().join(format!("aleph-routes-test-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("dashboard/[team]")).unwrap();
+    for file in [
+      "_layout.rs",
+      "dashboard/_layout.rs",
+      "dashboard/[team]/_layout.rs",
+      "dashboard/[team]/index.rs",
+    ] {
+      std::fs::write(dir.join(file), "").unwrap();
+    }
+    let tokens = expand_routes_dir(&dir).map(|tokens| tokens.to_string());
+    std::fs::remove_dir_all(&dir).unwrap();
+    let tokens = tokens.unwrap();
+    // the innermost layout is rendered in the `<Outlet/>` of the outer ones
+    let position = |layout: &str| tokens.find(&format!("< {layout} :: Layout / >")).unwrap();
+    assert!(position("dashboard_team_layout") < position("dashboard_layout"));
+    assert!(position("dashboard_layout") < position("_layout"));
+  }
+
+  #[test]
+  fn split_the_chunk_pages() {
+    let dir = std::env::temp_dir().join(format!("aleph-routes-chunk-test-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("todos")).unwrap();
+    std::fs::write(dir.join("index.rs"), "").unwrap();
+    std::fs::write(
+      dir.join("todos/[id].rs"),
+      r#"pub const CHUNK: &str = "/pkg/todos/todos.js";"#,
+    )
+    .unwrap();
+    let tokens = expand_routes_dir(&dir).map(|tokens| tokens.to_string());
+    std::fs::remove_dir_all(&dir).unwrap();
+    let tokens = tokens.unwrap();
+    // the page of the chunk is not compiled into the crate
+    assert!(tokens.contains("pub mod index ;"));
+    assert!(!tokens.contains("pub mod todos_id"));
+    assert!(tokens.contains("TodosId (:: aleph :: yew :: ChunkParams)"));
+    assert!(tokens.contains(r#"module = { "/pkg/todos/todos.js" }"#));
+    assert!(tokens.contains(r#"("/todos/:id" , "/pkg/todos/todos.js")"#));
+  }
+}
@@ -0,0 +1,21 @@
+// The client module of the Rust runtime, import it in the `main.ts` of the app to connect the Rust router to the
+// event bus of Aleph.js.
+export { default as events } from "../core/events.ts";
+export { redirect } from "../core/redirect.ts";
+
+/**
+ * Returns the url of the `*_bg.wasm` file versioned with the deployment id, it matches the preload link of the SSR
+ * output, so the wasm module isn't fetched twice.
+ *
+ * ```ts
+ * import init, { main } from "./pkg/yew_app.js";
+ * import { wasmURL } from "aleph/rust";
+ *
+ * init(wasmURL("./pkg/yew_app_bg.wasm")).then(main);
+ * ```
+ */
+export function wasmURL(specifier: string): string {
+  const v = document.body.getAttribute("data-deployment-id");
+  const pathname = "/" + specifier.replace(/^\.?\//, "");
+  return pathname + (v ? "?v=" + v : "");
+}
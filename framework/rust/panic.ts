@@ -0,0 +1,37 @@
+// The panic overlay of the Rust apps in development, the renderer of `aleph/plugins/rust` imports it in the SSR output
+// like the hydration check. The panics reported by the panic hook of the `aleph` crate are symbolized with the DWARF
+// info of the wasm modules loaded by the page, then logged to the console and shown in the error overlay.
+
+import { showRustPanic } from "../core/error.ts";
+import { type RustPanic, symbolizeStack, WasmSymbols } from "./symbolize.ts";
+
+// the debug info of the wasm modules by url, they're fetched once a panic is reported
+const symbols = new Map<string, Promise<WasmSymbols | null>>();
+
+/** Sets the hook for the panics of the Rust app, the hook of the crate logs them without symbolication otherwise. */
+export function watchPanics() {
+  Reflect.set(globalThis, "__ALEPH_RUST_PANIC_HOOK", async (panic: RustPanic) => {
+    const frames = panic.stack.split("\n").filter((line) => line.includes("wasm-function["));
+    const stack = symbolizeStack(frames.join("\n"), await loadSymbols());
+    console.error(`${panic.message}\n\nStack:\n\n${stack}`);
+    showRustPanic(panic.message, stack);
+  });
+}
+
+/** Loads the debug info of the wasm modules of the page, e.g. the preloaded `*_bg.wasm` and the route chunks. */
+async function loadSymbols(): Promise<WasmSymbols[]> {
+  for (const entry of performance.getEntriesByType("resource")) {
+    const url = entry.name;
+    if (new URL(url).pathname.endsWith(".wasm") && !symbols.has(url)) {
+      symbols.set(
+        url,
+        fetch(url)
+          .then((res) => res.arrayBuffer())
+          .then((buf) => WasmSymbols.parse(new Uint8Array(buf)))
+          .catch(() => null),
+      );
+    }
+  }
+  const list = await Promise.all(symbols.values());
+  return list.filter((s): s is WasmSymbols => s !== null);
+}
@@ -0,0 +1,922 @@
+import { bundleCSS } from "../../server/build.ts";
+import { brotliCompress, colors, concatBytes, ensureDir, HTMLRewriter, path } from "../../server/deps.ts";
+import {
+  existsFile,
+  getAlephConfig,
+  getAlephPkgUri,
+  getAppDir,
+  getDeploymentId,
+  getFiles,
+  toLocalPath,
+} from "../../server/helpers.ts";
+import log from "../../server/log.ts";
+import type { HTMLRewriterHandlers, Middleware, Plugin, SSRContext, SSROptions } from "../../server/types.ts";
+import { prettyBytes, utf8Dec, utf8Enc } from "../../shared/util.ts";
+import { FetchError } from "../core/error.ts";
+import { URLPatternCompat } from "../core/url_pattern.ts";
+import { type InvalidateOptions, PageCache, type PageCacheControl } from "./cache.ts";
+import { Catalogs, type I18nOptions } from "./i18n.ts";
+import { readVarUint32, type RustPanic, symbolizeStack, WasmSymbols } from "./symbolize.ts";
+
+/** The wasm module built by `wasm-pack build --target web` with the `aleph` crate. */
+export type WasmModule = {
+  default: (input: BufferSource) => Promise<unknown>;
+  ssr: (ctx: string) => Promise<string> | string;
+  __aleph_html_rewriter: (id: number, index: number, kind: keyof HTMLRewriterHandlers, node: unknown) => void;
+  __aleph_html_rewriter_free: (id: number) => void;
+  /** The route table generated by the `routes!` macro. */
+  __aleph_routes?: () => string;
+};
+
+/** The module that renders the pages, the wasm module or the native library loaded by `loadNativeModule`. */
+export type SSRModule = Omit<WasmModule, "default">;
+
+/** The route matched with the route table of the `routes!` macro. */
+export type RouteMatch = {
+  pattern: string;
+  params: Record<string, string>;
+};
+
+/** The JSON output of the `ssr` function. */
+export type SSROutput = {
+  html: string;
+  /** The error of the native SSR, the wasm module throws it instead. */
+  error?: string;
+  headCollection?: string[];
+  htmlRewriter?: {
+    id: number;
+    selectors: { selector: string; element: boolean; text: boolean; comments: boolean }[];
+  };
+  /** The cache control of the page, see `SsrContext::cache` of the crate. */
+  cache?: PageCacheControl;
+  /** The status of the first error rendered by the `ErrorBoundary` of the crate. */
+  status?: number;
+  /** The errors rendered by the `ErrorBoundary`, they are passed to the `onError` of the server. */
+  errors?: { status: number; message: string }[];
+  /** The redirect of the page, see `SsrContext::redirect` of the crate. */
+  redirect?: { location: string; status: number };
+};
+
+/** The wasm module that exports the `ssr_island` and `hydrate_island` functions, see `IslandHandle` of the crate. */
+export type IslandModule = {
+  default: (input: BufferSource) => Promise<unknown>;
+  ssr_island: (name: string, ctx: string, props: string) => Promise<string> | string;
+};
+
+export type PluginOptions = {
+  /** The wasm-bindgen module that exports the `ssr` function, e.g. "./pkg/yew_app.js". */
+  module: string;
+  /**
+   * The wasm-bindgen module imported by the `main.ts` in the browser, its js glue and the `*_bg.wasm` file are
+   * preloaded by the SSR output. Default is the `module`, set it to `false` to disable the preloading.
+   */
+  clientModule?: string | false;
+  /** The arguments of `wasm-opt` for the production build, default is `["-Oz"]`, set it to `false` to skip it. */
+  wasmOpt?: string[] | false;
+  /**
+   * The native library of the app crate built for the host target, without the `lib` prefix and the extension, e.g.
+   * "./target/release/yew_app". The crate exports the C ABI with the `export_native_ssr!` macro of the `aleph` crate,
+   * and it's loaded with `Deno.dlopen` (requires the `--allow-ffi` and `--unstable` flags). The SSR falls back to the
+   * wasm `module` if the library is not found.
+   */
+  native?: string;
+  /**
+   * The wasm-bindgen modules of the pages split by the `CHUNK` const of the routes, e.g. ["./pkg/todos/todos.js"].
+   * The route chunks are generated by the `routes!` macro of `aleph::yew`, the Leptos apps are not split.
+   */
+  chunks?: string[];
+  /**
+   * Caches the pages that call `SsrContext::cache` of the crate, default is `true`. The pages are written to the
+   * `.ssr-cache` directory of the build output dir, so they survive restarts. It's disabled in development mode. The
+   * pages reading the `data` of their route modules are not cached, and the nonce of the CSP is applied per request.
+   */
+  pageCache?: boolean;
+  /**
+   * Loads the message catalogs of the locales for the `I18n` of the crate. The locale of the request is detected by
+   * the path prefix, the cookie or the `Accept-Language` header, and only its messages are sent to the browser. The
+   * prefix is stripped before the routing, e.g. "/zh-CN/todos" is routed as "/todos", see `i18nMiddleware`.
+   */
+  i18n?: I18nOptions;
+  /** The options for SSR. */
+  ssr?: SSROptions;
+};
+
+// the route tables of the wasm modules
+const routeTables = new WeakMap<SSRModule, string[]>();
+
+// drops the handlers in the wasm memory once the rewriter is garbage collected
+const rewriterRegistry = new FinalizationRegistry<() => void>((free) => free());
+
+// the debug info of the wasm modules built in dev mode, the wasm frames of the logged errors are symbolized with it
+const wasmSymbols: WasmSymbols[] = [];
+
+/** The handle of a wasm module loaded by `loadModule`, the trapped instance is replaced by a new one after a panic. */
+type ModuleHandle = {
+  specifier: string;
+  current: Pick<WasmModule, "default">;
+  /** The memory of the current instance, the panic hook finds the trapped module with it. */
+  memory?: WebAssembly.Memory;
+  /** The count of the instances created, the js glue is imported with it to create a new instance. */
+  instances: number;
+  /** The creation of the new instance after a panic, the renders wait for it. */
+  reloading?: Promise<void>;
+};
+
+// the handles of the wasm modules keyed by their instances
+const moduleHandles = new WeakMap<object, ModuleHandle>();
+
+// the handles of the wasm modules keyed by the memory of their current instance
+const memoryHandles = new WeakMap<WebAssembly.Memory, ModuleHandle>();
+
+// the renders of the `ssr` functions in progress keyed by the render id sent in the ssr input
+const pendingRenders = new Map<number, { reject: (err: Error) => void; retry: (err: Error) => void }>();
+let lastRenderId = 0;
+
+// the trap following the panic reported by the hook
+let trapping = false;
+
+/**
+ * Loads the wasm module and initializes it with the `*_bg.wasm` file next to it. The panics of the module are handled
+ * by the panic hook of the plugin, and the DWARF info of the dev build is kept to symbolize the wasm frames.
+ */
+export async function loadModule<T extends Pick<WasmModule, "default"> = WasmModule>(specifier: string): Promise<T> {
+  const handle = { specifier, instances: 0 } as unknown as ModuleHandle;
+  await instantiate(handle);
+  setPanicHook();
+  return handle.current as T;
+}
+
+/**
+ * Creates a new instance of the wasm module. The js glue keeps the instance in its module scope, so the glue is
+ * imported again with the `instance` query for a new one, the trapped instance is not released.
+ */
+async function instantiate(handle: ModuleHandle): Promise<void> {
+  const filename = path.join(getAppDir(), handle.specifier);
+  const query = handle.instances > 0 ? `?instance=${handle.instances}` : "";
+  const mod: WasmModule = await import(path.toFileUrl(filename).href + query);
+  const wasm = await Deno.readFile(filename.replace(/\.js$/, "_bg.wasm"));
+  const exports = await mod.default(wasm) as { memory?: WebAssembly.Memory } | undefined;
+  if (handle.instances++ === 0) {
+    const symbols = WasmSymbols.parse(wasm);
+    if (symbols && wasmSymbols.push(symbols) === 1) {
+      log.addStackFormatter((stack) => symbolizeStack(stack, wasmSymbols, getAppDir()));
+    }
+  }
+  handle.current = mod;
+  handle.memory = exports?.memory;
+  moduleHandles.set(mod, handle);
+  if (handle.memory) {
+    memoryHandles.set(handle.memory, handle);
+  }
+}
+
+/** Replaces the trapped instance of the module, the islands and the route chunks are updated with the new one. */
+function reload(handle: ModuleHandle) {
+  const trapped = handle.current;
+  handle.reloading = instantiate(handle).then(() => {
+    const islands: Record<string, unknown> | undefined = Reflect.get(globalThis, "__ALEPH_RUST_ISLANDS");
+    for (const key in islands) {
+      if (islands[key] === trapped) {
+        islands[key] = handle.current;
+      }
+    }
+    log.warn(`The wasm module ${handle.specifier} is re-instantiated after a panic`);
+  }, (err) => {
+    log.error(`Failed to re-instantiate the wasm module ${handle.specifier}:`, err);
+  }).finally(() => {
+    handle.reloading = undefined;
+  });
+}
+
+/**
+ * Sets the `__ALEPH_RUST_PANIC_HOOK` called by the panic hook of the `aleph` crate. The panic rejects the render with
+ * the id reported by the hook, or it's logged if it's not in a render, e.g. a task spawned by the app. The trapped
+ * instance is replaced by a new one, and the other renders in progress are retried once since they may wait for the
+ * trapped instance forever, e.g. a page waiting for its route chunk.
+ */
+function setPanicHook() {
+  if (Reflect.has(globalThis, "__ALEPH_RUST_PANIC_HOOK")) {
+    return;
+  }
+  Reflect.set(globalThis, "__ALEPH_RUST_PANIC_HOOK", (panic: RustPanic) => {
+    const err = panicError(panic);
+    trapping = true;
+    const handle = panic.memory && memoryHandles.get(panic.memory);
+    if (handle && handle.memory === panic.memory) {
+      reload(handle);
+    }
+    const render = panic.renderId !== undefined ? pendingRenders.get(panic.renderId) : undefined;
+    if (render) {
+      render.reject(err);
+    } else {
+      log.error("Rust", err);
+    }
+    pendingRenders.forEach((pending) => pending !== render && pending.retry(err));
+  });
+  // the trap of an async render is thrown in a microtask of wasm-bindgen-futures, don't exit the process with it
+  const onTrap = (e: Event, err: unknown) => {
+    if (trapping && err instanceof WebAssembly.RuntimeError) {
+      trapping = false;
+      e.preventDefault();
+    }
+  };
+  addEventListener("error", (e) => onTrap(e, (e as ErrorEvent).error));
+  addEventListener("unhandledrejection", (e) => onTrap(e, (e as PromiseRejectionEvent).reason));
+}
+
+/** Creates the error of the panic, its stack is the wasm frames of the panic, symbolized by the logger. */
+export function panicError(panic: RustPanic): Error {
+  const err = new Error(panic.message);
+  err.name = "Panic";
+  const frames = panic.stack.split("\n").filter((line) => /^\s+at /.test(line));
+  err.stack = [`${err.name}: ${panic.message}`, ...frames].join("\n");
+  return err;
+}
+
+/**
+ * Calls the `ssr` function with the current instance of the module, resolves the output with the instance rendered
+ * it. The render id is sent to the wasm modules loaded by `loadModule`, a panic rejects the render with the id only,
+ * and a render retried after a panic is rejected by the next one.
+ */
+function callSSR(mod: SSRModule, input: Record<string, unknown>): Promise<[string, SSRModule]> {
+  const handle = moduleHandles.get(mod);
+  const renderId = ++lastRenderId;
+  const json = JSON.stringify(handle ? { ...input, renderId } : input);
+  return new Promise<[string, SSRModule]>((resolve, reject) => {
+    const call = async () => {
+      await handle?.reloading;
+      const instance = (handle?.current ?? mod) as SSRModule;
+      try {
+        resolve([await instance.ssr(json), instance]);
+      } catch (err) {
+        // the trap of a sync render is thrown by the `ssr` function itself
+        if (err instanceof WebAssembly.RuntimeError) {
+          trapping = false;
+        }
+        reject(err);
+      }
+    };
+    let retried = false;
+    pendingRenders.set(renderId, {
+      reject,
+      retry: (err) => {
+        if (retried) {
+          reject(err);
+        } else {
+          retried = true;
+          call();
+        }
+      },
+    });
+    call();
+  }).finally(() => pendingRenders.delete(renderId));
+}
+
+/**
+ * Loads the native library exporting the `aleph_ssr`, `aleph_routes` and `aleph_free` functions, returns `null` if
+ * the library is not found or the FFI is not available. The pages are rendered on the threads of the Deno runtime,
+ * not blocking the event loop.
+ */
+export async function loadNativeModule(specifier: string): Promise<SSRModule | null> {
+  const filename = path.join(getAppDir(), nativeLibName(specifier));
+  if (!(await existsFile(filename))) {
+    log.debug(`native SSR module ${filename} not found, fall back to the wasm module`);
+    return null;
+  }
+  if (typeof Deno.dlopen !== "function") {
+    log.warn(`Can't load ${filename} without the \`--unstable\` flag, fall back to the wasm module`);
+    return null;
+  }
+  let lib;
+  try {
+    lib = Deno.dlopen(filename, {
+      aleph_ssr: { parameters: ["buffer", "usize"], result: "pointer", nonblocking: true },
+      aleph_routes: { parameters: [], result: "pointer" },
+      aleph_free: { parameters: ["pointer"], result: "void" },
+    } as const);
+  } catch (err) {
+    log.warn(`Failed to load ${filename}, fall back to the wasm module:`, err.message);
+    return null;
+  }
+  const { symbols } = lib;
+  // takes the string returned by the library and frees it
+  const take = (ptr: Deno.PointerValue): string | null => {
+    if (!ptr) {
+      return null;
+    }
+    try {
+      return Deno.UnsafePointerView.getCString(ptr);
+    } finally {
+      symbols.aleph_free(ptr);
+    }
+  };
+  const routes = take(symbols.aleph_routes());
+  log.debug(`native SSR module ${filename} loaded`);
+  return {
+    async ssr(ctx: string) {
+      const input = utf8Enc.encode(ctx);
+      return take(await symbols.aleph_ssr(input, BigInt(input.length)))!;
+    },
+    __aleph_routes: routes !== null ? () => routes : undefined,
+    // the native SSR doesn't return the handlers of the `HTMLRewriter`
+    __aleph_html_rewriter() {},
+    __aleph_html_rewriter_free() {},
+  };
+}
+
+/** Returns the file name of the native library for the host os, e.g. "./target/release/libyew_app.so". */
+export function nativeLibName(specifier: string, os: typeof Deno.build.os = Deno.build.os): string {
+  const dir = path.dirname(specifier);
+  const name = path.basename(specifier);
+  switch (os) {
+    case "windows":
+      return path.join(dir, name + ".dll");
+    case "darwin":
+      return path.join(dir, "lib" + name + ".dylib");
+    default:
+      return path.join(dir, "lib" + name + ".so");
+  }
+}
+
+/**
+ * Matches the url with the route table in order, the first matched route wins. The Rust router matches the routes
+ * with the same rules, see the `recognize` function of the `aleph` crate.
+ */
+export function matchRoute(routes: string[], url: URL): RouteMatch | null {
+  for (const pattern of routes) {
+    let ret: ReturnType<typeof URLPatternCompat.execPathname> = null;
+    try {
+      ret = URLPatternCompat.execPathname(pattern, url.pathname);
+    } catch {
+      // malformed URI sequence
+    }
+    if (ret) {
+      return { pattern, params: ret.pathname.groups };
+    }
+  }
+  return null;
+}
+
+export type RenderOptions = {
+  /** The deployment id sent to the `ssr` function, the chunks of the routes are versioned with it. */
+  deploymentId?: string;
+  /** The cache of the pages that call `SsrContext::cache` of the crate, the pages with the `data` are not cached. */
+  cache?: PageCache<SSROutput>;
+  /** The message catalogs, the catalog of the detected locale is sent to the `ssr` function. */
+  catalogs?: Catalogs;
+  /**
+   * Compares the hydrated DOM with the server markup in the browser, the mismatches are shown in the error overlay.
+   * It's enabled in development mode.
+   */
+  hydrationCheck?: boolean;
+  /**
+   * Shows the panics of the app in the error overlay of the browser, with the wasm frames symbolized by the DWARF info
+   * of the dev build. It's enabled in development mode.
+   */
+  panicOverlay?: boolean;
+};
+
+/** Renders the page with the `ssr` function of the wasm module or the native library. */
+export async function render(mod: SSRModule, ctx: SSRContext, options: RenderOptions = {}): Promise<string> {
+  const { url, nonce } = ctx;
+  const { deploymentId, catalogs, hydrationCheck, panicOverlay } = options;
+  let route: RouteMatch | null | undefined = undefined;
+  if (typeof mod.__aleph_routes === "function") {
+    let routes = routeTables.get(mod);
+    if (!routes) {
+      routes = JSON.parse(mod.__aleph_routes()) as string[];
+      routeTables.set(mod, routes);
+    }
+    // the params of the route are sent in the ssr input, see `SsrContext::route` of the crate
+    route = matchRoute(routes, url);
+  }
+  // the data of the page loaded by the `data` of its route module, see `SsrContext::data` of the crate
+  const page = ctx.dataModules?.findLast((mod) => mod.withData);
+  const data = page && !(page.data instanceof Error) && typeof page.data !== "function" ? page.data : undefined;
+  // the error thrown by the `data` is rendered by the `ErrorBoundary` of the crate
+  const error = page?.data instanceof Error
+    ? { status: page.data instanceof FetchError ? page.data.status : 500, message: page.data.message }
+    : undefined;
+  if (page) {
+    // the data-only modules are not in the `ssr-data`, the page data is embedded for the hydration of `use_data`
+    const ssrPage = { url: page.url.pathname + page.url.search, withData: true, data, error };
+    ctx.headCollection.push(
+      `<script id="aleph-data" type="application/json">${JSON.stringify([ssrPage]).replaceAll("/", "\\/")}</script>`,
+    );
+  }
+  // the pages with the `data` are not cached, the data of the html is loaded for every request
+  const cache = page ? undefined : options.cache;
+  const locale = catalogs && (prefixedLocales.get(ctx.headers) ?? catalogs.detect(ctx.headers));
+  if (catalogs) {
+    // the html depends on the locale detected by the headers, e.g. for the CDN caches
+    ctx.setHeader("Vary", "Accept-Language, Cookie");
+  }
+  if (locale) {
+    ctx.htmlRewriter.on("html", {
+      element(el) {
+        el.setAttribute("lang", locale);
+      },
+    });
+  }
+  // the instance rendered the page, it's re-instantiated after a panic
+  let instance = mod;
+  const ssr = async (): Promise<[SSROutput, SSRModule]> => {
+    const i18n = locale ? catalogs?.get(locale) : undefined;
+    const input = { url: url.href, nonce, route, deploymentId, data, error, i18n };
+    const [json, rendered] = await callSSR(mod, input);
+    const output: SSROutput = JSON.parse(json);
+    if (output.error !== undefined) {
+      throw new Error(output.error);
+    }
+    return [output, rendered];
+  };
+  // the pages are cached per locale, the hash keeps the pathname of the key for the invalidation
+  const cacheKey = url.pathname + url.search + (locale ? "#" + locale : "");
+  const cached = cache?.get(cacheKey);
+  let output: SSROutput;
+  if (cache && cached) {
+    output = cached.output;
+    if (cache.isStale(cached)) {
+      cache.revalidate(cacheKey, async () => {
+        const [output, instance] = await ssr();
+        if (!output.cache) {
+          if (output.htmlRewriter) {
+            instance.__aleph_html_rewriter_free(output.htmlRewriter.id);
+          }
+          return null;
+        }
+        return [stripNonce(rewriteForCache(instance, output), nonce), output.cache];
+      });
+    }
+  } else {
+    [output, instance] = await ssr();
+  }
+  const { headCollection, htmlRewriter, status, errors, redirect } = output;
+  let { html } = output;
+
+  if (redirect) {
+    // the thrown response is sent by the server as is, like the redirect of the `data` of the route modules
+    throw new Response(null, { status: redirect.status, headers: { Location: redirect.location } });
+  }
+
+  if (status) {
+    ctx.setStatus(status);
+  }
+  if (errors) {
+    // the errors are rendered by the boundaries, the `onError` of the server may respond them instead
+    const onError = getAlephConfig()?.onError;
+    const req = new Request(url.href, { headers: ctx.headers });
+    for (const { status, message } of errors) {
+      const res = onError?.(new FetchError(status, message), "ssr", req);
+      if (res instanceof Response) {
+        throw res;
+      }
+    }
+  }
+  const handlers: [string, HTMLRewriterHandlers][] = [];
+
+  // the nonce of the request, the cached pages are stored without it
+  const nonceHandlers: [string, HTMLRewriterHandlers][] = nonce
+    ? [["script", {
+      element(el) {
+        const typeAttr = el.getAttribute("type");
+        if ((!typeAttr || typeAttr === "module") && !el.getAttribute("src")) {
+          el.setAttribute("nonce", nonce);
+        }
+      },
+    }], ["style", {
+      element(el) {
+        el.setAttribute("nonce", nonce);
+      },
+    }]]
+    : [];
+
+  if (headCollection) {
+    const tags = nonce ? headCollection.map((tag) => rewriteHTML(tag, nonceHandlers)) : headCollection;
+    ctx.headCollection.push(...tags);
+  }
+
+  if (hydrationCheck) {
+    // clones the server markup before the wasm module hydrates the app
+    const specifier = `${toLocalPath(getAlephPkgUri())}/framework/rust/hydration.ts`;
+    ctx.headCollection.push(
+      `<script type="module">import { watchHydration } from "${specifier}";watchHydration();</script>`,
+    );
+  }
+
+  if (panicOverlay) {
+    // sets the panic hook before the wasm module is loaded by the `main.ts`
+    const specifier = `${toLocalPath(getAlephPkgUri())}/framework/rust/panic.ts`;
+    ctx.headCollection.push(`<script type="module">import { watchPanics } from "${specifier}";watchPanics();</script>`);
+  }
+
+  if (htmlRewriter) {
+    // the handlers keep a reference to the `binding` object until the response is finished
+    const { id } = htmlRewriter;
+    const binding = { id };
+    const rustHandlers = createRewriterHandlers(instance, htmlRewriter, binding);
+    rustHandlers.forEach(([selector, h]) => ctx.htmlRewriter.on(selector, h));
+    rewriterRegistry.register(binding, () => instance.__aleph_html_rewriter_free(id));
+    if (cache && output.cache) {
+      // cache the html rewritten by the handlers of the app
+      html = rewriteHTML(html, rustHandlers);
+      await cache.set(cacheKey, stripNonce({ ...output, html, htmlRewriter: undefined }, nonce), output.cache);
+    } else {
+      handlers.push(...rustHandlers);
+    }
+  } else if (cache && output.cache && !cached) {
+    await cache.set(cacheKey, stripNonce(output, nonce), output.cache);
+  }
+
+  // apply the nonce to the inline scripts and styles emitted by the app
+  handlers.push(...nonceHandlers);
+
+  // collect the classes of the rendered page for the atomic CSS
+  const classes = new Set<string>();
+  const withAtomicCSS = !!getAlephConfig()?.atomicCSS;
+  if (withAtomicCSS) {
+    handlers.push(["[class]", {
+      element(el) {
+        el.getAttribute("class")?.split(/\s+/).forEach((name) => name && classes.add(name));
+      },
+    }]);
+  }
+
+  // the html output of the `ssr` function is inserted into the index.html without being parsed by the rewriter of
+  // the renderer, so rewrite it here.
+  html = rewriteHTML(html, handlers);
+  if (withAtomicCSS) {
+    ctx.setAtomicCSSClasses(classes);
+  }
+  return html;
+}
+
+function createRewriterHandlers(
+  mod: SSRModule,
+  htmlRewriter: NonNullable<SSROutput["htmlRewriter"]>,
+  binding: { id: number },
+): [string, HTMLRewriterHandlers][] {
+  return htmlRewriter.selectors.map(({ selector, ...kinds }, index) => {
+    const h: HTMLRewriterHandlers = {};
+    for (const kind of ["element", "text", "comments"] as const) {
+      if (kinds[kind]) {
+        h[kind] = (node: unknown) => mod.__aleph_html_rewriter(binding.id, index, kind, node);
+      }
+    }
+    return [selector, h];
+  });
+}
+
+/** Removes the nonce of the request from the output, the nonce of the next request is applied to the cached page. */
+function stripNonce(output: SSROutput, nonce: string | undefined): SSROutput {
+  if (!nonce) {
+    return output;
+  }
+  const attr = ` nonce="${nonce}"`;
+  return {
+    ...output,
+    html: output.html.replaceAll(attr, ""),
+    headCollection: output.headCollection?.map((tag) => tag.replaceAll(attr, "")),
+  };
+}
+
+/** Applies the handlers of the app to the html of a revalidated page, the cached page has no handlers. */
+function rewriteForCache(mod: SSRModule, output: SSROutput): SSROutput {
+  const { htmlRewriter } = output;
+  if (!htmlRewriter) {
+    return output;
+  }
+  try {
+    const html = rewriteHTML(output.html, createRewriterHandlers(mod, htmlRewriter, { id: htmlRewriter.id }));
+    return { ...output, html, htmlRewriter: undefined };
+  } finally {
+    mod.__aleph_html_rewriter_free(htmlRewriter.id);
+  }
+}
+
+function rewriteHTML(html: string, handlers: [string, HTMLRewriterHandlers][]): string {
+  if (handlers.length === 0) {
+    return html;
+  }
+  const chunks: Uint8Array[] = [];
+  const rewriter = new HTMLRewriter("utf8", (chunk: Uint8Array) => chunks.push(chunk));
+  handlers.forEach(([selector, h]) => rewriter.on(selector, h));
+  try {
+    rewriter.write(utf8Enc.encode(html));
+    rewriter.end();
+  } finally {
+    rewriter.free();
+  }
+  return utf8Dec.decode(concatBytes(...chunks));
+}
+
+/**
+ * Returns the `<link>` tags to preload the js glue and the `*_bg.wasm` file of a wasm-bindgen module, versioned with
+ * the deployment id like the route modules. The `wasmURL` function of `aleph/rust` loads the wasm file with the same
+ * url in the browser.
+ */
+export function preloadLinks(specifier: string): string[] {
+  const deploymentId = getDeploymentId();
+  const q = deploymentId ? `?v=${deploymentId}` : "";
+  const glue = "/" + specifier.replace(/^\.?\//, "");
+  const wasm = glue.replace(/\.js$/, "_bg.wasm");
+  return [
+    `<link rel="modulepreload" href="${glue}${q}" data-module-id="${glue}">`,
+    `<link rel="preload" href="${wasm}${q}" as="fetch" type="application/wasm" crossorigin>`,
+  ];
+}
+
+/** The manifest of the CSS modules read by the `css_module!` macro of the `aleph` crate. */
+export const cssModulesManifest = "target/aleph/css_modules.json";
+
+export type BuildCSSModulesOptions = {
+  /** The directory of the crate, default is the app directory. */
+  cwd?: string;
+  /** Minify the css, default is `true`. */
+  minify?: boolean;
+};
+
+/**
+ * Builds all the `.module.css` files of the crate with the CSS pipeline of Aleph.js, the hashed class names and the
+ * css are written to `target/aleph/css_modules.json` for the `css_module!` macro, call it before `wasm-pack build`.
+ */
+export async function buildCSSModules(options: BuildCSSModulesOptions = {}): Promise<void> {
+  const { cwd = getAppDir(), minify = true } = options;
+  const files = await getFiles(
+    cwd,
+    (filename) => filename.endsWith(".module.css") && !/^\.\/(target|pkg|output|node_modules)\//.test(filename),
+  );
+  const manifest: Record<string, { css: string; exports: Record<string, string> }> = {};
+  await Promise.all(files.map(async (specifier) => {
+    const source = await Deno.readTextFile(path.join(cwd, specifier));
+    const { code, cssModulesExports = {} } = await bundleCSS(specifier, source, {
+      targets: {
+        android: 95,
+        chrome: 95,
+        edge: 95,
+        firefox: 90,
+        safari: 14,
+      },
+      minify,
+      cssModules: true,
+    });
+    manifest[specifier] = { css: code, exports: cssModulesExports };
+  }));
+  const filename = path.join(cwd, cssModulesManifest);
+  const content = JSON.stringify(manifest, undefined, 2);
+  await ensureDir(path.dirname(filename));
+  // don't touch the manifest if nothing changed, otherwise cargo rebuilds the crate
+  const current = await Deno.readTextFile(filename).catch(() => null);
+  if (current !== content) {
+    await Deno.writeTextFile(filename, content);
+  }
+  log.debug(`${files.length} CSS module(s) built for Rust`);
+}
+
+export type OptimizeWasmOptions = {
+  /** The arguments of `wasm-opt`, default is `["-Oz"]`, set it to `false` to skip it. */
+  wasmOpt?: string[] | false;
+};
+
+/** The sizes of a wasm file optimized by the `optimizeWasm` function. */
+export type WasmSizeReport = {
+  pathname: string;
+  original: number;
+  optimized: number;
+  gzip: number;
+  brotli: number;
+};
+
+/**
+ * Optimizes the `*_bg.wasm` file of a wasm-bindgen module for production: runs `wasm-opt` if it's installed, strips
+ * the debug and producers sections, then writes it into the output directory with the precompressed `.br` and `.gz`
+ * files, which are served by the static file handler with the `Content-Encoding` header.
+ */
+export async function optimizeWasm(
+  specifier: string,
+  outputDir: string,
+  options: OptimizeWasmOptions = {},
+): Promise<WasmSizeReport> {
+  const { wasmOpt = ["-Oz"] } = options;
+  const pathname = "/" + specifier.replace(/^\.?\//, "").replace(/\.js$/, "_bg.wasm");
+  const filename = path.join(getAppDir(), pathname);
+  const outFile = path.join(outputDir, pathname);
+  const original = await Deno.readFile(filename);
+  await ensureDir(path.dirname(outFile));
+  let wasm = original;
+  if (wasmOpt && await runWasmOpt(filename, outFile, wasmOpt)) {
+    wasm = await Deno.readFile(outFile);
+  }
+  wasm = stripCustomSections(wasm);
+  const [gzip, brotli] = await Promise.all([gzipCompress(wasm), brotliCompress(wasm, 4096, 11)]);
+  await Promise.all([
+    Deno.writeFile(outFile, wasm),
+    Deno.writeFile(outFile + ".gz", gzip),
+    Deno.writeFile(outFile + ".br", brotli),
+  ]);
+  return { pathname, original: original.length, optimized: wasm.length, gzip: gzip.length, brotli: brotli.length };
+}
+
+/** Removes the custom sections that are not needed at runtime, e.g. `.debug_info`, `name` and `producers`. */
+export function stripCustomSections(wasm: Uint8Array): Uint8Array {
+  // the magic number and the version
+  const sections: Uint8Array[] = [wasm.subarray(0, 8)];
+  let offset = 8;
+  while (offset < wasm.length) {
+    const start = offset;
+    const id = wasm[offset++];
+    const [size, sizeLen] = readVarUint32(wasm, offset);
+    offset += sizeLen;
+    const end = offset + size;
+    if (id === 0) {
+      const [nameLen, nameLenLen] = readVarUint32(wasm, offset);
+      const nameStart = offset + nameLenLen;
+      const name = utf8Dec.decode(wasm.subarray(nameStart, nameStart + nameLen));
+      if (name === "name" || name === "producers" || name === "sourceMappingURL" || name.startsWith(".debug_")) {
+        offset = end;
+        continue;
+      }
+    }
+    sections.push(wasm.subarray(start, end));
+    offset = end;
+  }
+  return concatBytes(...sections);
+}
+
+/** Runs `wasm-opt`, returns `false` if it's not installed or failed. */
+async function runWasmOpt(input: string, output: string, args: string[]): Promise<boolean> {
+  let p: Deno.Process;
+  try {
+    p = Deno.run({ cmd: ["wasm-opt", input, "-o", output, ...args], stdout: "null", stderr: "inherit" });
+  } catch (err) {
+    if (err instanceof Deno.errors.NotFound) {
+      log.warn("`wasm-opt` not found, install binaryen to optimize the wasm modules");
+      return false;
+    }
+    throw err;
+  }
+  try {
+    const { success } = await p.status();
+    if (!success) {
+      log.warn(`Failed to optimize ${input} with \`wasm-opt\``);
+    }
+    return success;
+  } finally {
+    p.close();
+  }
+}
+
+async function gzipCompress(data: Uint8Array): Promise<Uint8Array> {
+  const stream = new Blob([data]).stream().pipeThrough(new CompressionStream("gzip"));
+  return new Uint8Array(await new Response(stream).arrayBuffer());
+}
+
+/** Optimizes the wasm modules in the build and prints the sizes. */
+async function buildWasmModules(specifiers: string[], outputDir: string, options: OptimizeWasmOptions) {
+  const reports = await Promise.all(specifiers.map((specifier) => optimizeWasm(specifier, outputDir, options)));
+  console.log(`${colors.green("Rust")} ${colors.bold(reports.length.toString())} wasm module(s) optimized.`);
+  console.log(
+    reports.map(({ pathname, original, optimized, gzip, brotli }, index) => {
+      const tab = index === reports.length - 1 ? "└─" : "├─";
+      return `${tab} ${pathname} ${prettyBytes(original)} → ${colors.bold(prettyBytes(optimized))} ${
+        colors.dim(`(gzip ${prettyBytes(gzip)}, brotli ${prettyBytes(brotli)})`)
+      }`;
+    }).join("\n"),
+  );
+}
+
+/** The locales stripped from the path by the `i18nMiddleware`, keyed by the headers of the rewritten request. */
+const prefixedLocales = new WeakMap<Headers, string>();
+
+/**
+ * Routes the requests prefixed with a locale as the path without the prefix, e.g. "/zh-CN/todos" as "/todos", so the
+ * route modules, their `data` and `mutation`, and the routes of the crate match the path. The stripped locale is used
+ * by the `render` instead of detecting it by the headers.
+ */
+export function i18nMiddleware(catalogs: Catalogs): Middleware {
+  return {
+    name: "rust-i18n",
+    fetch: (req, ctx) => {
+      const url = new URL(req.url);
+      const stripped = catalogs.stripLocale(url.pathname);
+      if (!stripped) {
+        return ctx.next();
+      }
+      url.pathname = stripped[1];
+      const rewritten = new Request(url.href, req);
+      prefixedLocales.set(rewritten.headers, stripped[0]);
+      return ctx.next(rewritten);
+    },
+  };
+}
+
+export default function RustPlugin(options: PluginOptions): Plugin {
+  return {
+    name: "rust",
+    async setup(aleph, env) {
+      const [mod] = await Promise.all([
+        loadSSRModule(options),
+        options.chunks && loadIslands(options.chunks),
+      ]);
+      const { clientModule = options.module, pageCache = true } = options;
+      const links = clientModule ? preloadLinks(clientModule) : [];
+      const deploymentId = getDeploymentId();
+      const cache = pageCache && !Deno.args.includes("--dev") ? await loadPageCache() : undefined;
+      const catalogs = options.i18n ? new Catalogs(options.i18n) : undefined;
+      await catalogs?.load();
+      if (catalogs) {
+        aleph.middlewares = [i18nMiddleware(catalogs), ...(aleph.middlewares ?? [])];
+      }
+      aleph.ssr = {
+        ...options.ssr,
+        render: (ctx) => {
+          // starts fetching the wasm module before the `main.ts` is evaluated
+          ctx.headCollection.push(...links);
+          const { isDev } = env;
+          return render(mod, ctx, { deploymentId, cache, catalogs, hydrationCheck: isDev, panicOverlay: isDev });
+        },
+      };
+    },
+    async build(outputDir) {
+      const { clientModule = options.module, chunks = [] } = options;
+      const modules = clientModule ? [clientModule, ...chunks] : chunks;
+      await buildWasmModules(modules, outputDir, options);
+    },
+  };
+}
+
+/** Loads the page cache of the Rust app, it's shared by the `invalidatePageCache` function. */
+async function loadPageCache(): Promise<PageCache<SSROutput>> {
+  let cache: PageCache<SSROutput> | undefined = Reflect.get(globalThis, "__ALEPH_RUST_PAGE_CACHE");
+  if (!cache) {
+    const outputDir = getAlephConfig()?.build?.outputDir ?? "output";
+    cache = new PageCache(path.join(getAppDir(), outputDir, ".ssr-cache"));
+    Reflect.set(globalThis, "__ALEPH_RUST_PAGE_CACHE", cache);
+    await cache.load();
+  }
+  return cache;
+}
+
+/**
+ * Invalidates the cached pages of the Rust app by the path or the tag, e.g. in the API route that updates the data of
+ * the page. Returns the count of the invalidated pages.
+ *
+ * ```ts
+ * await invalidatePageCache({ tag: "todos" });
+ * ```
+ */
+export function invalidatePageCache(options: InvalidateOptions): Promise<number> {
+  const cache: PageCache<SSROutput> | undefined = Reflect.get(globalThis, "__ALEPH_RUST_PAGE_CACHE");
+  return cache?.invalidate(options) ?? Promise.resolve(0);
+}
+
+/** Loads the native library if it's built, otherwise the wasm module. */
+async function loadSSRModule(options: PluginOptions): Promise<SSRModule> {
+  if (options.native) {
+    const mod = await loadNativeModule(options.native);
+    if (mod) {
+      return mod;
+    }
+  }
+  return loadModule(options.module);
+}
+
+export type IslandsPluginOptions = {
+  /** The wasm-bindgen modules of the islands used by the `<RustIsland>` component, e.g. ["./pkg/islands.js"]. */
+  modules: string[];
+  /** The arguments of `wasm-opt` for the production build, default is `["-Oz"]`, set it to `false` to skip it. */
+  wasmOpt?: string[] | false;
+};
+
+/**
+ * Loads the wasm modules of the Rust islands for the `<RustIsland>` component of `aleph/react`, use it with the
+ * react plugin to mount Yew/Leptos components in the React pages.
+ */
+export function RustIslandsPlugin(options: IslandsPluginOptions): Plugin {
+  return {
+    name: "rust-islands",
+    async setup() {
+      await loadIslands(options.modules);
+    },
+    async build(outputDir) {
+      await buildWasmModules(options.modules, outputDir, options);
+    },
+  };
+}
+
+/**
+ * Loads the wasm modules into the registry of the islands, shared by the `<RustIsland>` component of React and the
+ * route chunks of the `aleph` crate.
+ */
+async function loadIslands(specifiers: string[]): Promise<void> {
+  let islands: Record<string, IslandModule> | undefined = Reflect.get(globalThis, "__ALEPH_RUST_ISLANDS");
+  if (!islands) {
+    islands = {};
+    Reflect.set(globalThis, "__ALEPH_RUST_ISLANDS", islands);
+  }
+  const loaded = await Promise.all(specifiers.map((specifier) => loadModule<IslandModule>(specifier)));
+  specifiers.forEach((specifier, i) => {
+    // the same key as the `<RustIsland>` component
+    islands!["/" + specifier.replace(/^\.?\//, "")] = loaded[i];
+  });
+}
@@ -0,0 +1,274 @@
+//! The helpers for the `build.rs` of the app crate, enabled by the `build` feature:
+//!
+//! ```toml
+//! [build-dependencies]
+//! aleph = { path = "../aleph/framework/rust", features = ["build"] }
+//! ```
+//!
+//! The helpers run the scripts of Aleph.js next to the crate, e.g. `build_css_modules.ts` imports the `server` of the
+//! repository. So the crate must be a path or git dependency of the Aleph.js repository, the package published to
+//! crates.io doesn't include the scripts.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The manifest of the CSS modules read by the `css_module!` macro, relative to the crate root.
+const CSS_MODULES_MANIFEST: &str = "target/aleph/css_modules.json";
+
+/// Builds the CSS modules of the crate for the `css_module!` macro with `buildCSSModules()` of `aleph/plugins/rust`,
+/// so a plain `wasm-pack build` or `cargo build` finds the manifest. It runs `deno`, set the `DENO` env to use another
+/// executable. The build script reruns when a `.module.css` file, or a file of their directories, is changed.
+///
+/// ```ignore
+/// // build.rs
+/// fn main() {
+///   aleph::build::css_modules();
+/// }
+/// ```
+///
+/// Nothing runs if the crate has no CSS modules or the manifest is newer than them, and it's skipped if the
+/// `ALEPH_SKIP_CSS_MODULES` env is set, e.g. the manifest is built by the dev server. If `deno` is not found, e.g. a
+/// `cargo clippy` or the native build on a machine without deno, a warning is printed and a fallback manifest is
+/// written: the class names are not hashed and the CSS is not transformed. It's rebuilt once deno is found.
+///
+/// # Panics
+///
+/// Panics if `deno` fails to build the CSS modules, which fails the build.
+pub fn css_modules() {
+  println!("cargo:rerun-if-env-changed=ALEPH_SKIP_CSS_MODULES");
+  println!("cargo:rerun-if-env-changed=DENO");
+  if std::env::var_os("ALEPH_SKIP_CSS_MODULES").is_some() {
+    return;
+  }
+  let root = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").expect("`CARGO_MANIFEST_DIR` is not set"));
+  let mut files = Vec::new();
+  find_css_modules(&root, &root, &mut files);
+  let mut dirs = files.iter().filter_map(|file| file.parent()).collect::<Vec<_>>();
+  dirs.dedup();
+  for path in dirs.iter().copied().chain(files.iter().map(PathBuf::as_path)) {
+    println!("cargo:rerun-if-changed={}", path.display());
+  }
+  let manifest = root.join(CSS_MODULES_MANIFEST);
+  if files.is_empty()
+    || is_fresh(
+      &manifest,
+      dirs.iter().copied().chain(files.iter().map(PathBuf::as_path)),
+    )
+  {
+    return;
+  }
+
+  let script = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/build_css_modules.ts"));
+  if !script.exists() {
+    println!(
+      "cargo:warning=the CSS modules are not built: `{}` is not found, use the `aleph` crate of the Aleph.js repository",
+      script.display()
+    );
+    write_fallback_manifest(&root, &files, &manifest);
+    return;
+  }
+  let deno = std::env::var("DENO").unwrap_or_else(|_| "deno".to_owned());
+  let mut cmd = Command::new(&deno);
+  cmd
+    .args(["run", "-A", "--quiet"])
+    .arg(script)
+    .arg(&root)
+    .current_dir(&root);
+  if std::env::var("PROFILE").as_deref() == Ok("release") {
+    cmd.arg("--minify");
+  }
+  match cmd.status() {
+    Ok(status) if status.success() => {}
+    Ok(status) => panic!("failed to build the CSS modules: `{deno}` exited with {status}"),
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+      println!(
+        "cargo:warning=the CSS modules are not built: `{deno}` is not found, install deno or set the `DENO` env"
+      );
+      write_fallback_manifest(&root, &files, &manifest);
+    }
+    Err(err) => panic!("failed to build the CSS modules: can't run `{deno}`: {err}"),
+  }
+}
+
+/// Checks if the manifest is newer than the CSS modules and their directories, a removed or added file changes the
+/// modified time of its directory. The fallback manifest is never fresh.
+fn is_fresh<'a>(manifest: &Path, paths: impl IntoIterator<Item = &'a Path>) -> bool {
+  let modified = |path: &Path| path.metadata().and_then(|meta| meta.modified()).ok();
+  if std::fs::read_to_string(manifest).map_or(true, |content| content.contains(FALLBACK_KEY)) {
+    return false;
+  }
+  match modified(manifest) {
+    Some(built) => paths
+      .into_iter()
+      .all(|path| modified(path).is_some_and(|time| time <= built)),
+    None => false,
+  }
+}
+
+/// The key marking the fallback manifest, it's not a specifier so the `css_module!` macro ignores it.
+const FALLBACK_KEY: &str = "$fallback";
+
+/// Writes the manifest of the CSS modules without deno, the class names are exported as is.
+fn write_fallback_manifest(root: &Path, files: &[PathBuf], manifest: &Path) {
+  let mut modules = serde_json::Map::new();
+  modules.insert(FALLBACK_KEY.to_owned(), true.into());
+  for file in files {
+    let css = std::fs::read_to_string(file).unwrap_or_default();
+    let exports = class_names(&css)
+      .into_iter()
+      .map(|name| (name.clone(), name.into()))
+      .collect::<serde_json::Map<_, _>>();
+    let specifier = format!("./{}", file.strip_prefix(root).unwrap_or(file).display());
+    modules.insert(specifier, serde_json::json!({ "css": css, "exports": exports }));
+  }
+  let content = serde_json::Value::Object(modules).to_string();
+  if let Some(dir) = manifest.parent() {
+    let _ = std::fs::create_dir_all(dir);
+  }
+  if let Err(err) = std::fs::write(manifest, content) {
+    println!("cargo:warning=failed to write the CSS modules manifest: {err}");
+  }
+}
+
+/// Finds the class selectors of the CSS, e.g. `header` and `active` of `.header > a.active`. The comments and the
+/// declarations are skipped, the rules of the at-rules like `@media` are scanned.
+fn class_names(css: &str) -> BTreeSet<String> {
+  let mut names = BTreeSet::new();
+  let mut prelude = String::new();
+  let mut chars = css.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '/' if chars.peek() == Some(&'*') => {
+        let mut prev = ' ';
+        for c in chars.by_ref() {
+          if prev == '*' && c == '/' {
+            break;
+          }
+          prev = c;
+        }
+      }
+      '{' if prelude.trim_start().starts_with('@') => prelude.clear(),
+      '{' => {
+        selector_class_names(&prelude, &mut names);
+        prelude.clear();
+        let mut depth = 1;
+        for c in chars.by_ref() {
+          match c {
+            '{' => depth += 1,
+            '}' if depth == 1 => break,
+            '}' => depth -= 1,
+            _ => {}
+          }
+        }
+      }
+      '}' | ';' => prelude.clear(),
+      c => prelude.push(c),
+    }
+  }
+  names
+}
+
+fn selector_class_names(selector: &str, names: &mut BTreeSet<String>) {
+  let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-' || !c.is_ascii();
+  for (index, _) in selector.match_indices('.') {
+    let name = selector[index + 1..]
+      .split(|c| !is_name_char(c))
+      .next()
+      .unwrap_or_default();
+    if name.starts_with(|c: char| !c.is_ascii_digit()) {
+      names.insert(name.to_owned());
+    }
+  }
+}
+
+/// Finds the `.module.css` files like `buildCSSModules()`, the build directories are skipped.
+fn find_css_modules(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+  let entries = match std::fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(_) => return,
+  };
+  let mut entries = entries
+    .filter_map(Result::ok)
+    .map(|entry| entry.path())
+    .collect::<Vec<_>>();
+  entries.sort();
+  for path in entries {
+    if path.is_dir() {
+      let skipped = dir == root
+        && matches!(
+          path.file_name().and_then(|name| name.to_str()),
+          Some("target" | "pkg" | "output" | "node_modules")
+        );
+      if !skipped {
+        find_css_modules(root, &path, files);
+      }
+    } else if path.to_string_lossy().ends_with(".module.css") {
+      files.push(path);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn find_the_css_modules() {
+    let root = std::env::temp_dir().join(format!("aleph-build-test-{}", std::process::id()));
+    for dir in ["src/components", "target/aleph", "style"] {
+      std::fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    for file in [
+      "src/components/header.module.css",
+      "src/components/header.css",
+      "target/aleph/header.module.css",
+      "style/app.module.css",
+    ] {
+      std::fs::write(root.join(file), "").unwrap();
+    }
+    let mut files = Vec::new();
+    find_css_modules(&root, &root, &mut files);
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(
+      files,
+      [
+        root.join("src/components/header.module.css"),
+        root.join("style/app.module.css")
+      ]
+    );
+  }
+
+  #[test]
+  fn find_the_class_names() {
+    let css = "/* .comment */ .header > a.active, .nav-link:hover { margin: 0.5em; background: url(a.png) }\n\
+               @media (min-width: 640px) { .wrapper { padding: 1.5rem } }";
+    assert_eq!(
+      class_names(css).into_iter().collect::<Vec<_>>(),
+      ["active", "header", "nav-link", "wrapper"]
+    );
+  }
+
+  #[test]
+  fn check_the_manifest_is_fresh() {
+    let root = std::env::temp_dir().join(format!("aleph-build-fresh-test-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("target/aleph")).unwrap();
+    let css = root.join("app.module.css");
+    let manifest = root.join(CSS_MODULES_MANIFEST);
+    std::fs::write(&css, "").unwrap();
+    assert!(!is_fresh(&manifest, [css.as_path()]));
+    write_fallback_manifest(&root, std::slice::from_ref(&css), &manifest);
+    assert!(!is_fresh(&manifest, [css.as_path()]));
+    std::fs::write(&manifest, "{}").unwrap();
+    assert!(is_fresh(&manifest, [css.as_path()]));
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(10);
+    std::fs::File::options()
+      .write(true)
+      .open(&css)
+      .unwrap()
+      .set_modified(later)
+      .unwrap();
+    assert!(!is_fresh(&manifest, [css.as_path()]));
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
@@ -0,0 +1,353 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::error::PageError;
+use crate::i18n::{Catalog, I18n};
+use crate::rewriter::{self, HtmlRewriter, HtmlRewriterHandlers, HtmlRewriterManifest};
+
+/// The SSR context of a request, created from the JSON sent by the Aleph server.
+#[derive(Clone)]
+pub struct SsrContext(Rc<Inner>);
+
+struct Inner {
+  url: String,
+  nonce: Option<String>,
+  route: Option<Option<RouteMatch>>,
+  deployment_id: Option<String>,
+  data: Option<serde_json::Value>,
+  error: Option<PageError>,
+  i18n: Option<I18n>,
+  render_id: Option<u32>,
+  errors: RefCell<Vec<PageError>>,
+  redirect: RefCell<Option<Redirect>>,
+  head_collection: RefCell<Vec<String>>,
+  cache: RefCell<Option<PageCache>>,
+  css_modules: RefCell<HashSet<&'static str>>,
+  html_rewriter: RefCell<Vec<(String, HtmlRewriterHandlers)>>,
+}
+
+/// The route matched by the Aleph server with the route table of the `routes!` macro.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RouteMatch {
+  /// The URL pattern of the route, e.g. `/todos/:id`.
+  pub pattern: String,
+  /// The params of the dynamic segments, e.g. `{"id": "1"}`.
+  pub params: HashMap<String, String>,
+}
+
+/// The cache control of the rendered page, see `SsrContext::cache`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PageCache {
+  /// The seconds the page is fresh, the stale page is served while it's regenerated in the background.
+  pub ttl: u32,
+  /// The tags to invalidate the page with `invalidatePageCache` of `aleph/plugins/rust`.
+  pub tags: Vec<String>,
+}
+
+/// The redirect of the page, the Aleph server responds it instead of the rendered html.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+struct Redirect {
+  location: String,
+  status: u16,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsrInput {
+  url: String,
+  #[serde(default)]
+  nonce: Option<String>,
+  // `null` if no route matched, or missing if the app doesn't use the `routes!` macro
+  #[serde(default, deserialize_with = "deserialize_some")]
+  route: Option<Option<RouteMatch>>,
+  #[serde(default)]
+  deployment_id: Option<String>,
+  #[serde(default)]
+  data: Option<serde_json::Value>,
+  #[serde(default)]
+  error: Option<PageError>,
+  #[serde(default)]
+  i18n: Option<Catalog>,
+  // the id of the render sent by the Aleph server, see the `panic` module
+  #[serde(default)]
+  render_id: Option<u32>,
+}
+
+fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: Deserialize<'de>,
+{
+  T::deserialize(deserializer).map(Some)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SsrOutput {
+  html: String,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  head_collection: Vec<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  html_rewriter: Option<HtmlRewriterManifest>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  cache: Option<PageCache>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  status: Option<u16>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  errors: Vec<PageError>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  redirect: Option<Redirect>,
+}
+
+impl SsrContext {
+  /// Creates a new context from the JSON sent by the Aleph server.
+  pub fn from_json(input: &str) -> serde_json::Result<Self> {
+    let SsrInput {
+      url,
+      nonce,
+      route,
+      deployment_id,
+      data,
+      error,
+      i18n,
+      render_id,
+    } = serde_json::from_str(input)?;
+    Ok(Self(Rc::new(Inner {
+      url,
+      nonce,
+      route,
+      deployment_id,
+      data,
+      error,
+      i18n: i18n.map(I18n::new),
+      render_id,
+      errors: RefCell::new(Vec::new()),
+      redirect: RefCell::new(None),
+      head_collection: RefCell::new(Vec::new()),
+      cache: RefCell::new(None),
+      css_modules: RefCell::new(HashSet::new()),
+      html_rewriter: RefCell::new(Vec::new()),
+    })))
+  }
+
+  /// The full url of the request.
+  pub fn url(&self) -> &str {
+    &self.0.url
+  }
+
+  /// The nonce of the content-security-policy, it's only available when the `CSP.nonce` option is enabled.
+  ///
+  /// The nonce is applied to the inline `<script>` and `<style>` tags of the html output automatically.
+  pub fn nonce(&self) -> Option<&str> {
+    self.0.nonce.as_deref()
+  }
+
+  /// The route matched by the Aleph server, returns `Some(None)` if no route matched, or `None` if the app doesn't
+  /// use the `routes!` macro.
+  pub fn route(&self) -> Option<Option<&RouteMatch>> {
+    self.0.route.as_ref().map(Option::as_ref)
+  }
+
+  /// The deployment id of the Aleph server, the urls of the static files are versioned with it, e.g. `?v=<id>`.
+  pub fn deployment_id(&self) -> Option<&str> {
+    self.0.deployment_id.as_deref()
+  }
+
+  /// The id of the render sent by the Aleph server, the panics of the render are reported with it, so only the render
+  /// that panicked is rejected.
+  pub fn render_id(&self) -> Option<u32> {
+    self.0.render_id
+  }
+
+  /// The data of the page loaded by the `data` of its route module, e.g. `routes/todos.ts`. Returns `None` if the
+  /// route has no data, or it can't be deserialized to `T`.
+  pub fn data<T: DeserializeOwned>(&self) -> Option<T> {
+    serde_json::from_value(self.0.data.clone()?).ok()
+  }
+
+  /// The error thrown by the `data` of the route module, e.g. a `FetchError`, it's rendered by the `ErrorBoundary`.
+  pub fn error(&self) -> Option<&PageError> {
+    self.0.error.as_ref()
+  }
+
+  /// Reports the error rendered by the `ErrorBoundary`, the status of the response is set to the status of the first
+  /// error and the errors are passed to the `onError` of the Aleph server. The page with errors is never cached.
+  pub fn report_error(&self, error: &PageError) {
+    let mut errors = self.0.errors.borrow_mut();
+    if !errors.contains(error) {
+      errors.push(error.clone());
+    }
+  }
+
+  /// Redirects to the url instead of rendering the page, the Aleph server responds the `Location` header with the
+  /// `status`, which is one of `301`, `302`, `303`, `307` and `308`, others fall back to `302`. The first redirect
+  /// wins, e.g. the one of the layout, and the rendered html is dropped. See `use_redirect` to redirect in the browser
+  /// as well.
+  pub fn redirect(&self, to: impl Into<String>, status: u16) {
+    let mut redirect = self.0.redirect.borrow_mut();
+    if redirect.is_none() {
+      let status = if matches!(status, 301 | 302 | 303 | 307 | 308) {
+        status
+      } else {
+        302
+      };
+      *redirect = Some(Redirect {
+        location: to.into(),
+        status,
+      });
+    }
+  }
+
+  /// Checks if the page is redirected by `redirect`, the components can skip the rendering and the loaders.
+  pub fn redirected(&self) -> bool {
+    self.0.redirect.borrow().is_some()
+  }
+
+  /// The messages of the locale detected by the Aleph server, it's only available when the `i18n` option of
+  /// `aleph/plugins/rust` is set.
+  pub fn i18n(&self) -> Option<&I18n> {
+    self.0.i18n.as_ref()
+  }
+
+  /// Appends the html to the `<head>` of the page.
+  pub fn append_head(&self, html: impl Into<String>) {
+    self.0.head_collection.borrow_mut().push(html.into());
+  }
+
+  /// Caches the rendered page for `ttl` seconds, the Aleph server serves the page from the cache without calling the
+  /// `ssr` function. When the page is stale it's served once more while it's regenerated in the background, and it
+  /// can be invalidated on demand by the path or the `tags` with `invalidatePageCache` of `aleph/plugins/rust`.
+  ///
+  /// If it's called more than once, e.g. by the layouts and the page, the shortest `ttl` wins and the tags are merged.
+  /// Don't cache the pages rendered for a particular user. The handlers of the `html_rewriter` are applied to the page
+  /// before it's cached, but not to the `index.html` when the page is served from the cache. The pages reading the
+  /// `data` of their route modules are not cached, and the `nonce` is removed from the cached page and applied to the
+  /// `<script>` and `<style>` tags of every request.
+  pub fn cache(&self, ttl: u32, tags: &[&str]) {
+    let mut cache = self.0.cache.borrow_mut();
+    let cache = cache.get_or_insert_with(|| PageCache { ttl, tags: Vec::new() });
+    cache.ttl = cache.ttl.min(ttl);
+    for tag in tags {
+      if !cache.tags.iter().any(|t| t == tag) {
+        cache.tags.push(tag.to_string());
+      }
+    }
+  }
+
+  /// Inlines the css of a CSS module into the `<head>`, once per module.
+  pub(crate) fn apply_css(&self, id: &'static str, css: &str) {
+    if self.0.css_modules.borrow_mut().insert(id) {
+      let nonce = self
+        .nonce()
+        .map(|nonce| format!(" nonce=\"{}\"", nonce))
+        .unwrap_or_default();
+      self.append_head(format!("<style data-module-id=\"{}\"{}>{}</style>", id, nonce, css));
+    }
+  }
+
+  /// Embeds the data of a loader into the `<head>` for the hydration, see `use_loader`.
+  #[cfg(feature = "yew")]
+  pub(crate) fn embed_loader_data(&self, key: &str, json: &str) {
+    self.append_head(format!(
+      "<script type=\"application/json\" data-aleph-loader=\"{}\">{}</script>",
+      key.replace('&', "&amp;").replace('"', "&quot;"),
+      json.replace("</", "<\\/")
+    ));
+  }
+
+  /// Returns the `HtmlRewriter` to rewrite the html output.
+  pub fn html_rewriter(&self) -> HtmlRewriter<'_> {
+    HtmlRewriter::new(&self.0.html_rewriter)
+  }
+
+  /// Finishes the rendering and returns the JSON output for the Aleph server.
+  pub fn finish(&self, html: String) -> String {
+    if let Some(redirect) = self.0.redirect.take() {
+      // nothing of the page is sent to the browser
+      self.0.head_collection.take();
+      self.0.html_rewriter.take();
+      self.0.cache.take();
+      self.0.errors.take();
+      return serde_json::to_string(&SsrOutput {
+        html: String::new(),
+        head_collection: Vec::new(),
+        html_rewriter: None,
+        cache: None,
+        status: None,
+        errors: Vec::new(),
+        redirect: Some(redirect),
+      })
+      .unwrap();
+    }
+    if let Some(route) = &self.0.route {
+      // the client router uses the route matched by the server for the hydration
+      let json = serde_json::json!({ "url": self.0.url, "route": route }).to_string();
+      self.append_head(format!(
+        "<script id=\"aleph-route\" type=\"application/json\">{}</script>",
+        json.replace("</", "<\\/")
+      ));
+    }
+    if let Some(i18n) = &self.0.i18n {
+      // the browser reads the messages of the locale for the hydration
+      self.append_head(i18n.to_script());
+    }
+    let head_collection = self.0.head_collection.take();
+    let html_rewriter = rewriter::register(supported_handlers(self.0.html_rewriter.take()));
+    let errors = self.0.errors.take();
+    let cache = self.0.cache.take().filter(|_| errors.is_empty());
+    serde_json::to_string(&SsrOutput {
+      html,
+      head_collection,
+      html_rewriter,
+      cache,
+      status: errors.first().map(|error| error.status),
+      errors,
+      redirect: None,
+    })
+    .unwrap()
+  }
+
+  /// Finishes the rendering of an island and returns the JSON output for the `<RustIsland>` component of React.
+  /// The `HtmlRewriter` is not supported in islands since the page is rendered by React.
+  pub fn finish_island(&self, html: String) -> String {
+    let head_collection = self.0.head_collection.take();
+    self.0.html_rewriter.take();
+    serde_json::to_string(&SsrOutput {
+      html,
+      head_collection,
+      html_rewriter: None,
+      cache: None,
+      status: None,
+      errors: Vec::new(),
+      redirect: None,
+    })
+    .unwrap()
+  }
+}
+
+/// The handlers are called back by the JS runtime, so they are dropped by the native SSR.
+#[cfg(not(target_arch = "wasm32"))]
+fn supported_handlers(handlers: Vec<(String, HtmlRewriterHandlers)>) -> Vec<(String, HtmlRewriterHandlers)> {
+  static WARN: std::sync::Once = std::sync::Once::new();
+  if !handlers.is_empty() {
+    WARN.call_once(|| {
+      eprintln!("[aleph] The `HtmlRewriter` is not supported by the native SSR, the handlers are ignored")
+    });
+  }
+  Vec::new()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn supported_handlers(handlers: Vec<(String, HtmlRewriterHandlers)>) -> Vec<(String, HtmlRewriterHandlers)> {
+  handlers
+}
+
+impl PartialEq for SsrContext {
+  fn eq(&self, other: &Self) -> bool {
+    Rc::ptr_eq(&self.0, &other.0)
+  }
+}
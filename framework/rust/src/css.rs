@@ -0,0 +1,69 @@
+use std::ops::Deref;
+
+use wasm_bindgen::JsCast;
+
+use crate::SsrContext;
+
+/// A CSS module imported by the `css_module!` macro, derefs to the hashed class names.
+pub struct CssModule<C> {
+  id: &'static str,
+  css: &'static str,
+  classes: C,
+}
+
+impl<C> CssModule<C> {
+  #[doc(hidden)]
+  pub const fn new(id: &'static str, css: &'static str, classes: C) -> Self {
+    Self { id, css, classes }
+  }
+
+  /// The specifier of the css file, e.g. `./src/components/header.module.css`.
+  pub fn id(&self) -> &'static str {
+    self.id
+  }
+
+  /// The compiled css.
+  pub fn css(&self) -> &'static str {
+    self.css
+  }
+
+  /// Inlines the css into the `headCollection` during SSR, or applies it to the document in the browser.
+  pub fn apply(&self, ctx: Option<&SsrContext>) {
+    match ctx {
+      Some(ctx) => ctx.apply_css(self.id, self.css),
+      None => apply_css(self.id, self.css),
+    }
+  }
+}
+
+impl<C> Deref for CssModule<C> {
+  type Target = C;
+
+  fn deref(&self) -> &C {
+    &self.classes
+  }
+}
+
+/// Appends a `<style>` element to the document head if the css is not applied yet.
+fn apply_css(id: &str, css: &str) {
+  let document = match web_sys::window().and_then(|window| window.document()) {
+    Some(document) => document,
+    None => return,
+  };
+  let head = match document.head() {
+    Some(head) => head,
+    None => return,
+  };
+  let applied = head
+    .query_selector(&format!("style[data-module-id=\"{}\"]", id))
+    .ok()
+    .flatten()
+    .is_some();
+  if !applied {
+    if let Ok(el) = document.create_element("style") {
+      let _ = el.set_attribute("data-module-id", id);
+      el.set_text_content(Some(css));
+      let _ = head.append_child(el.unchecked_ref());
+    }
+  }
+}
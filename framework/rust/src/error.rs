@@ -0,0 +1,39 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// An error of the page rendered by the `ErrorBoundary`, e.g. the `FetchError` thrown by the `data` of the route
+/// module, or the error reported by a component with `use_error_boundary`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PageError {
+  /// The status code of the response, e.g. `404`.
+  #[serde(default = "default_status")]
+  pub status: u16,
+  pub message: String,
+}
+
+fn default_status() -> u16 {
+  500
+}
+
+impl PageError {
+  pub fn new(status: u16, message: impl Into<String>) -> Self {
+    Self {
+      status,
+      message: message.into(),
+    }
+  }
+
+  /// The `404 Not Found` error, e.g. the todo of the page doesn't exist.
+  pub fn not_found() -> Self {
+    Self::new(404, "Not Found")
+  }
+}
+
+impl fmt::Display for PageError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} - {}", self.status, self.message)
+  }
+}
+
+impl std::error::Error for PageError {}
@@ -0,0 +1,222 @@
+//! The interop with the event bus of Aleph.js, imported by `aleph/rust` in the `main.ts` of the app. The Rust routers
+//! follow the `popstate` events emitted by `redirect()`, and emit the same events on their own navigations, so the
+//! Rust app, the React router and the TS modules agree on the current URL.
+
+use std::cell::RefCell;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[wasm_bindgen]
+extern "C" {
+  /// The `mitt` emitter exposed as `globalThis.__ALEPH_EVENTS`.
+  type Emitter;
+
+  #[wasm_bindgen(method)]
+  fn on(this: &Emitter, ty: &str, handler: &js_sys::Function);
+  #[wasm_bindgen(method)]
+  fn off(this: &Emitter, ty: &str, handler: &js_sys::Function);
+  #[wasm_bindgen(method)]
+  fn emit(this: &Emitter, ty: &str, event: &JsValue);
+}
+
+/// A listener of the event bus, it's removed once dropped.
+pub struct EventListener {
+  ty: String,
+  handler: Closure<dyn FnMut(JsValue)>,
+}
+
+impl Drop for EventListener {
+  fn drop(&mut self) {
+    if let Some(emitter) = emitter() {
+      emitter.off(&self.ty, self.handler.as_ref().unchecked_ref());
+    }
+  }
+}
+
+struct Connection {
+  _popstate: EventListener,
+  _window_popstate: Closure<dyn FnMut()>,
+  _navigatesuccess: Closure<dyn FnMut()>,
+}
+
+thread_local! {
+  static SYNCED_URL: RefCell<String> = const { RefCell::new(String::new()) };
+  static CONNECTION: RefCell<Option<Connection>> = const { RefCell::new(None) };
+}
+
+/// Listens to the event of the event bus, returns `None` if `aleph/rust` is not imported.
+pub fn on(ty: impl Into<String>, handler: impl FnMut(JsValue) + 'static) -> Option<EventListener> {
+  let emitter = emitter()?;
+  let listener = EventListener {
+    ty: ty.into(),
+    handler: Closure::new(handler),
+  };
+  emitter.on(&listener.ty, listener.handler.as_ref().unchecked_ref());
+  Some(listener)
+}
+
+/// Emits the event to the event bus, the `type` field of the event is set to `ty`.
+pub fn emit(ty: &str, event: js_sys::Object) {
+  if let Some(emitter) = emitter() {
+    let _ = js_sys::Reflect::set(&event, &"type".into(), &ty.into());
+    emitter.emit(ty, &event);
+  }
+}
+
+/// Connects the Rust router to the event bus, called by the `hydrate` functions.
+pub(crate) fn connect() {
+  if CONNECTION.with(|connection| connection.borrow().is_some()) {
+    return;
+  }
+  let window = match web_sys::window() {
+    Some(window) => window,
+    None => return,
+  };
+  SYNCED_URL.with(|url| *url.borrow_mut() = location_href());
+
+  // the `popstate` event of the window is handled by the Rust router itself
+  let window_popstate = Closure::<dyn FnMut()>::new(|| {
+    SYNCED_URL.with(|url| *url.borrow_mut() = location_href());
+  });
+  let _ = window.add_event_listener_with_callback("popstate", window_popstate.as_ref().unchecked_ref());
+
+  // `redirect()` doesn't emit the `popstate` event with the Navigation API
+  let navigatesuccess = Closure::<dyn FnMut()>::new(sync_router);
+  if let Ok(navigation) = js_sys::Reflect::get(&window, &"navigation".into()) {
+    if let Some(navigation) = navigation.dyn_ref::<web_sys::EventTarget>() {
+      let _ = navigation.add_event_listener_with_callback("navigatesuccess", navigatesuccess.as_ref().unchecked_ref());
+    }
+  }
+
+  let popstate = match on("popstate", |e: JsValue| {
+    let from = js_sys::Reflect::get(&e, &"from".into())
+      .ok()
+      .and_then(|from| from.as_string());
+    if from.as_deref() != Some("rust") {
+      sync_router();
+    }
+  }) {
+    Some(listener) => listener,
+    None => return,
+  };
+  CONNECTION.with(|connection| {
+    *connection.borrow_mut() = Some(Connection {
+      _popstate: popstate,
+      _window_popstate: window_popstate,
+      _navigatesuccess: navigatesuccess,
+    })
+  });
+
+  let event = js_sys::Object::new();
+  let _ = js_sys::Reflect::set(&event, &"framework".into(), &"rust".into());
+  emit("router", event);
+}
+
+/// Emits the `popstate` event if the URL is changed by the Rust router, called on every location change of it.
+pub(crate) fn notify_navigation() {
+  let href = location_href();
+  if !update_synced_url(&href) {
+    return;
+  }
+  let has_navigation = web_sys::window()
+    .map(|window| js_sys::Reflect::has(&window, &"navigation".into()).unwrap_or(false))
+    .unwrap_or(false);
+  // with the Navigation API, the React router listens to the `navigate` event instead
+  if has_navigation {
+    return;
+  }
+  if let Ok(url) = web_sys::Url::new(&href) {
+    let event = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&event, &"url".into(), &url);
+    let _ = js_sys::Reflect::set(&event, &"from".into(), &"rust".into());
+    emit("popstate", event);
+  }
+}
+
+/// Emits the `hydrated` event once the app is hydrated, the hydration check of `aleph/plugins/rust` compares the
+/// hydrated DOM with the server markup then in development.
+#[cfg(any(feature = "yew", all(feature = "leptos", feature = "hydrate")))]
+pub(crate) fn notify_hydrated() {
+  // the check that starts after the hydration is skipped
+  let _ = js_sys::Reflect::set(&js_sys::global(), &"__ALEPH_RUST_HYDRATED".into(), &JsValue::TRUE);
+  let event = js_sys::Object::new();
+  let _ = js_sys::Reflect::set(&event, &"framework".into(), &"rust".into());
+  emit("hydrated", event);
+}
+
+/// Navigates to the url like the `redirect()` of Aleph.js, e.g. the page redirected by a mutation. With `replace` the
+/// current history entry is replaced instead of pushing a new one.
+#[cfg(any(feature = "yew", feature = "leptos"))]
+pub(crate) fn navigate(href: &str, replace: bool) {
+  let window = match web_sys::window() {
+    Some(window) => window,
+    None => return,
+  };
+  let url = match web_sys::Url::new_with_base(href, &location_href()) {
+    Ok(url) => url,
+    Err(_) => return,
+  };
+  if url.origin() != window.location().origin().unwrap_or_default() {
+    let _ = if replace {
+      window.location().replace(&url.href())
+    } else {
+      window.location().set_href(&url.href())
+    };
+    return;
+  }
+  if let Ok(history) = window.history() {
+    let _ = if replace {
+      history.replace_state_with_url(&JsValue::NULL, "", Some(&url.href()))
+    } else {
+      history.push_state_with_url(&JsValue::NULL, "", Some(&url.href()))
+    };
+  }
+  notify_navigation();
+  dispatch_popstate(&window);
+}
+
+/// Notifies the Rust router if the URL is changed by others, e.g. `redirect()`.
+fn sync_router() {
+  if !update_synced_url(&location_href()) {
+    return;
+  }
+  if let Some(window) = web_sys::window() {
+    dispatch_popstate(&window);
+  }
+}
+
+/// Dispatches the `popstate` event to the window, the Rust routers re-render with the current location.
+fn dispatch_popstate(window: &web_sys::Window) {
+  let init = js_sys::Object::new();
+  if let Ok(state) = window.history().and_then(|history| history.state()) {
+    let _ = js_sys::Reflect::set(&init, &"state".into(), &state);
+  }
+  if let Ok(event) = web_sys::PopStateEvent::new_with_event_init_dict("popstate", init.unchecked_ref()) {
+    let _ = window.dispatch_event(&event);
+  }
+}
+
+fn update_synced_url(href: &str) -> bool {
+  SYNCED_URL.with(|url| {
+    let mut url = url.borrow_mut();
+    if url.as_str() == href {
+      return false;
+    }
+    *url = href.to_owned();
+    true
+  })
+}
+
+fn emitter() -> Option<Emitter> {
+  js_sys::Reflect::get(&js_sys::global(), &"__ALEPH_EVENTS".into())
+    .ok()
+    .filter(|emitter| emitter.is_object())
+    .map(JsCast::unchecked_into)
+}
+
+fn location_href() -> String {
+  web_sys::window()
+    .and_then(|window| window.location().href().ok())
+    .unwrap_or_default()
+}
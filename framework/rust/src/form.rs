@@ -0,0 +1,91 @@
+//! The submission of the `<Form>` components, it posts the form to the `mutation` of the route module once the wasm
+//! is loaded. Before that, the browser submits the form itself and the Aleph server redirects back to the page.
+
+use std::fmt;
+
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{FormData, Headers, HtmlFormElement, RequestInit, UrlSearchParams};
+
+use crate::events;
+
+/// The error of a form submission, e.g. the mutation responds `400 Bad Request`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormError {
+  /// The status code of the response, it's `0` if the request failed.
+  pub status: u16,
+  /// The `message` of the JSON error thrown by the mutation, or the text of the response.
+  pub message: String,
+}
+
+impl fmt::Display for FormError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {}", self.status, self.message)
+  }
+}
+
+impl std::error::Error for FormError {}
+
+impl From<JsValue> for FormError {
+  fn from(err: JsValue) -> Self {
+    let message = js_sys::Reflect::get(&err, &"message".into())
+      .ok()
+      .and_then(|message| message.as_string())
+      .or_else(|| err.as_string())
+      .unwrap_or_else(|| "Failed to submit the form".to_owned());
+    Self { status: 0, message }
+  }
+}
+
+/// Posts the form to its `action` with the same body as the browser does, and returns the JSON responded by the
+/// mutation. If the mutation redirects to another page, the app navigates to it and `null` is returned.
+pub(crate) async fn submit(
+  form: &HtmlFormElement,
+  submitter: Option<web_sys::HtmlElement>,
+) -> Result<serde_json::Value, FormError> {
+  let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window"))?;
+  let data = FormData::new_with_form(form)?;
+  // the name and the value of the submit button, e.g. `<button name="intent" value="delete">`
+  if let Some(submitter) = submitter {
+    if let Some(name) = submitter.get_attribute("name").filter(|name| !name.is_empty()) {
+      data.append_with_str(&name, &submitter.get_attribute("value").unwrap_or_default())?;
+    }
+  }
+  let body: JsValue = if form.enctype() == "multipart/form-data" {
+    data.into()
+  } else {
+    UrlSearchParams::new_with_str_sequence_sequence(&data)?.into()
+  };
+  let headers = Headers::new()?;
+  headers.set("Accept", "application/json")?;
+  let mut init = RequestInit::new();
+  // the setters replacing the builder methods are missing before web-sys 0.3.70
+  #[allow(deprecated)]
+  init.method("POST").headers(&headers).body(Some(&body));
+
+  let res: web_sys::Response = JsFuture::from(window.fetch_with_str_and_init(&form.action(), &init))
+    .await?
+    .dyn_into()?;
+  if res.redirected() && res.ok() {
+    events::navigate(&res.url(), false);
+    return Ok(serde_json::Value::Null);
+  }
+  let text = JsFuture::from(res.text()?).await?.as_string().unwrap_or_default();
+  if !res.ok() {
+    let message = serde_json::from_str::<serde_json::Value>(&text)
+      .ok()
+      .and_then(|err| err.get("message")?.as_str().map(ToOwned::to_owned))
+      .unwrap_or(text);
+    return Err(FormError {
+      status: res.status(),
+      message,
+    });
+  }
+  if text.is_empty() {
+    return Ok(serde_json::Value::Null);
+  }
+  serde_json::from_str(&text).map_err(|err| FormError {
+    status: res.status(),
+    message: err.to_string(),
+  })
+}
@@ -0,0 +1,203 @@
+#[cfg(any(feature = "yew", feature = "leptos"))]
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+/// The messages of a locale, loaded from the catalogs by the `i18n` option of `aleph/plugins/rust`.
+#[derive(Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Catalog {
+  locale: String,
+  messages: HashMap<String, String>,
+}
+
+/// The messages of the locale detected by the Aleph server, see `use_i18n`.
+///
+/// The server sends only the catalog of the locale to the `ssr` function, and embeds it into the page for the
+/// hydration. The numbers and the dates are formatted by the crate instead of the `Intl` of the browser, so the
+/// output of the server and the client always matches. The special messages of the catalog define the formats:
+///
+/// ```json
+/// {
+///   "$number": { "decimal": ",", "group": "." },
+///   "$date": { "short": "DD.MM.YYYY", "months": "Jan,Feb,Mar,Apr,May,Jun,Jul,Aug,Sep,Oct,Nov,Dec" }
+/// }
+/// ```
+#[derive(Clone, Default, PartialEq)]
+pub struct I18n(Rc<Catalog>);
+
+#[cfg(any(feature = "yew", feature = "leptos"))]
+thread_local! {
+  static DOCUMENT_I18N: RefCell<Option<I18n>> = const { RefCell::new(None) };
+}
+
+impl I18n {
+  pub(crate) fn new(catalog: Catalog) -> Self {
+    Self(Rc::new(catalog))
+  }
+
+  /// Reads the catalog embedded into the page by the SSR, it's empty if the `i18n` option is not set.
+  #[cfg(any(feature = "yew", feature = "leptos"))]
+  pub(crate) fn from_document() -> Self {
+    DOCUMENT_I18N.with(|cell| {
+      cell
+        .borrow_mut()
+        .get_or_insert_with(|| {
+          web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id("aleph-i18n"))
+            .and_then(|el| el.text_content())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .map(Self::new)
+            .unwrap_or_default()
+        })
+        .clone()
+    })
+  }
+
+  /// The `<script>` that embeds the catalog into the `<head>` of the page.
+  pub(crate) fn to_script(&self) -> String {
+    let json = serde_json::to_string(&*self.0).unwrap_or_default();
+    format!(
+      "<script id=\"aleph-i18n\" type=\"application/json\">{}</script>",
+      json.replace("</", "<\\/")
+    )
+  }
+
+  /// The locale of the request, e.g. `zh-CN`.
+  pub fn locale(&self) -> &str {
+    &self.0.locale
+  }
+
+  /// Returns the message of the key, or the key itself if the message is missing.
+  pub fn t(&self, key: &str) -> String {
+    self.t_with(key, &[])
+  }
+
+  /// Returns the message of the key with the `{name}` placeholders replaced by the arguments, e.g.
+  /// `i18n.t_with("todos.left", &[("count", &3)])`.
+  pub fn t_with(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let message = match self.message(key) {
+      Some(message) => message,
+      None => return key.to_owned(),
+    };
+    let mut output = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+      output.push_str(&rest[..start]);
+      rest = &rest[start..];
+      let end = match rest.find('}') {
+        Some(end) => end,
+        None => break,
+      };
+      match args.iter().find(|(name, _)| *name == &rest[1..end]) {
+        Some((_, value)) => output.push_str(&value.to_string()),
+        None => output.push_str(&rest[..=end]),
+      }
+      rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    output
+  }
+
+  fn message(&self, key: &str) -> Option<&str> {
+    self.0.messages.get(key).map(String::as_str)
+  }
+
+  /// Formats the number with the `$number.decimal` and `$number.group` separators of the catalog, default is `.`
+  /// and `,`.
+  pub fn number(&self, value: f64, fraction_digits: usize) -> String {
+    if !value.is_finite() {
+      return value.to_string();
+    }
+    let decimal = self.message("$number.decimal").unwrap_or(".");
+    let group = self.message("$number.group").unwrap_or(",");
+    let formatted = format!("{:.*}", fraction_digits, value.abs());
+    let (int, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+    let mut output = String::new();
+    if value.is_sign_negative() && formatted.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+      output.push('-');
+    }
+    for (i, c) in int.chars().enumerate() {
+      if i > 0 && (int.len() - i) % 3 == 0 {
+        output.push_str(group);
+      }
+      output.push(c);
+    }
+    if !fraction.is_empty() {
+      output.push_str(decimal);
+      output.push_str(fraction);
+    }
+    output
+  }
+
+  /// Formats the timestamp in milliseconds as UTC with the `$date.<format>` pattern of the catalog, or the `format`
+  /// itself as the pattern, e.g. `i18n.date(ts, "short")` or `i18n.date(ts, "YYYY-MM-DD")`.
+  ///
+  /// The tokens are `YYYY`, `MMMM` (the names of `$date.months`), `MM`, `M`, `DD`, `D`, `HH`, `mm` and `ss`, the text
+  /// in square brackets is kept as is, e.g. `[at] HH:mm`.
+  pub fn date(&self, timestamp: f64, format: &str) -> String {
+    let pattern = self.message(&format!("$date.{}", format)).unwrap_or(format);
+    let ms = timestamp as i64;
+    let days = ms.div_euclid(86_400_000);
+    let secs = ms.rem_euclid(86_400_000) / 1000;
+    let (year, month, day) = civil_from_days(days);
+    let month_name = self
+      .message("$date.months")
+      .and_then(|months| months.split(',').nth(month as usize - 1))
+      .map(|name| name.trim().to_owned())
+      .unwrap_or_else(|| month.to_string());
+
+    let tokens: [(&str, String); 9] = [
+      ("YYYY", format!("{:04}", year)),
+      ("MMMM", month_name),
+      ("MM", format!("{:02}", month)),
+      ("M", month.to_string()),
+      ("DD", format!("{:02}", day)),
+      ("D", day.to_string()),
+      ("HH", format!("{:02}", secs / 3600)),
+      ("mm", format!("{:02}", secs % 3600 / 60)),
+      ("ss", format!("{:02}", secs % 60)),
+    ];
+
+    let mut output = String::new();
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+      if c == '[' {
+        if let Some(end) = rest.find(']') {
+          output.push_str(&rest[1..end]);
+          rest = &rest[end + 1..];
+          continue;
+        }
+      }
+      match tokens.iter().find(|(token, _)| rest.starts_with(token)) {
+        Some((token, value)) => {
+          output.push_str(value);
+          rest = &rest[token.len()..];
+        }
+        None => {
+          output.push(c);
+          rest = &rest[c.len_utf8()..];
+        }
+      }
+    }
+    output
+  }
+}
+
+/// Converts the days since the unix epoch to the date of the proleptic Gregorian calendar, see
+/// <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z.rem_euclid(146_097);
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+  let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+  let year = yoe + era * 400 + i64::from(month <= 2);
+  (year, month, day)
+}
@@ -0,0 +1,52 @@
+//! Rust components mounted in the pages of React as islands.
+
+use wasm_bindgen::prelude::*;
+
+/// The handle of an island mounted by the `<RustIsland>` component of `aleph/react`, it's destroyed when the
+/// component is unmounted or the props are changed.
+///
+/// The app crate exports the `ssr_island` and `hydrate_island` functions that dispatch the island by name:
+///
+/// ```ignore
+/// #[wasm_bindgen]
+/// pub async fn ssr_island(name: String, ctx: String, props: String) -> Result<JsValue, JsValue> {
+///   match name.as_str() {
+///     "counter" => aleph::yew::render_island::<Counter>(ctx, props).await,
+///     _ => Err(JsValue::from_str("unknown island")),
+///   }
+/// }
+///
+/// #[wasm_bindgen]
+/// pub fn hydrate_island(name: String, root: web_sys::Element, props: String) -> Result<IslandHandle, JsValue> {
+///   match name.as_str() {
+///     "counter" => aleph::yew::hydrate_island::<Counter>(root, props),
+///     _ => Err(JsValue::from_str("unknown island")),
+///   }
+/// }
+/// ```
+///
+/// The Leptos islands use the `render_island` and `hydrate_island` functions of `aleph::leptos` the same way, but
+/// they're rendered on the client instead of being hydrated, see the limitations of `aleph::leptos::hydrate_island`.
+#[wasm_bindgen]
+pub struct IslandHandle {
+  destroy: Option<Box<dyn FnOnce()>>,
+}
+
+impl IslandHandle {
+  #[cfg(any(feature = "yew", all(feature = "leptos", not(feature = "ssr"))))]
+  pub(crate) fn new(destroy: impl FnOnce() + 'static) -> Self {
+    Self {
+      destroy: Some(Box::new(destroy)),
+    }
+  }
+}
+
+#[wasm_bindgen]
+impl IslandHandle {
+  /// Unmounts the island and clears the root element.
+  pub fn destroy(&mut self) {
+    if let Some(destroy) = self.destroy.take() {
+      destroy();
+    }
+  }
+}
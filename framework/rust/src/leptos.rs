@@ -0,0 +1,496 @@
+//! The [Leptos](https://leptos.dev) integration.
+//!
+//! The routes are declared with the `<Routes>` of `leptos_router`, the nested `<Route>`s render the layouts through
+//! its `<Outlet/>`. The file system routes of the `routes!` macro, with their `_app.rs` and `_layout.rs` files and
+//! the route chunks split by the `CHUNK` const, are Yew-only, so the app is compiled into one wasm module. The locale
+//! prefix of the path, e.g. `/zh-CN/todos`, is stripped by the Aleph server but not by `leptos_router` in the browser,
+//! so the Leptos apps don't link the prefixed paths, the locale is detected by the cookie or the `Accept-Language`
+//! header instead.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use ::leptos::*;
+use leptos_router::use_location;
+use serde::de::DeserializeOwned;
+use wasm_bindgen::{JsCast, JsValue};
+
+#[cfg(not(feature = "ssr"))]
+use crate::IslandHandle;
+use crate::{events, form, panic, prefetch, router, CssModule, FormError, I18n, PageError, Redirector, SsrContext};
+
+/// Returns the SSR context of current request, or `None` in the browser.
+pub fn use_ssr_context(cx: Scope) -> Option<SsrContext> {
+  use_context::<SsrContext>(cx)
+}
+
+/// Returns the nonce of the content-security-policy for the inline `<script>` and `<style>` tags.
+pub fn use_nonce(cx: Scope) -> Option<String> {
+  use_ssr_context(cx).and_then(|ctx| ctx.nonce().map(ToOwned::to_owned))
+}
+
+/// Returns the messages of the locale detected by the Aleph server, see `I18n`.
+pub fn use_i18n(cx: Scope) -> I18n {
+  match use_ssr_context(cx) {
+    Some(ctx) => ctx.i18n().cloned().unwrap_or_default(),
+    None => I18n::from_document(),
+  }
+}
+
+/// Caches the rendered page for `ttl` seconds, see `SsrContext::cache`.
+pub fn use_page_cache(cx: Scope, ttl: u32, tags: &[&str]) {
+  if let Some(ctx) = use_ssr_context(cx) {
+    ctx.cache(ttl, tags);
+  }
+}
+
+/// Returns the `Redirector` to redirect to another page, e.g. the page requires the login. In the server-side rendering
+/// the Aleph server responds the redirect instead of the page; in the browser it navigates through the router.
+pub fn use_redirect(cx: Scope) -> Redirector {
+  Redirector::new(use_ssr_context(cx))
+}
+
+/// Applies the CSS module imported by the `css_module!` macro, returns it for the class names.
+pub fn use_css_module<C>(cx: Scope, module: CssModule<C>) -> CssModule<C> {
+  module.apply(use_ssr_context(cx).as_ref());
+  module
+}
+
+/// The `<Link>` component to link between pages, it prefetches the data and the wasm chunks of the page on hover.
+/// The navigation is handled by the `<Router>` of `leptos_router`.
+#[component]
+pub fn Link(
+  cx: Scope,
+  /// The url to link to.
+  #[prop(into)]
+  href: String,
+  /// Replaces the current history entry instead of pushing a new one.
+  #[prop(optional)]
+  replace: bool,
+  /// Prefetches the page once the link enters the viewport, otherwise the page is prefetched on hover.
+  #[prop(optional)]
+  prefetch: bool,
+  #[prop(optional, into)] class: Option<MaybeSignal<String>>,
+  /// The `aria-current` attribute, default is `page` for the links of the app.
+  #[prop(optional, into)]
+  aria_current: Option<String>,
+  children: Box<dyn FnOnce(Scope) -> Fragment>,
+) -> impl IntoView {
+  // the `prop:` attributes are not rendered on the server
+  #[cfg(feature = "ssr")]
+  let _ = replace;
+  let aria_current = aria_current.or_else(|| href.starts_with('/').then(|| "page".to_owned()));
+  let anchor = NodeRef::<HtmlElement<html::A>>::new(cx);
+  let timer = Rc::new(Cell::new(None::<i32>));
+
+  if prefetch {
+    let observed = Rc::new(RefCell::new(None::<web_sys::Element>));
+    create_effect(cx, {
+      let observed = observed.clone();
+      move |_| {
+        if let Some(el) = anchor.get() {
+          let el: &web_sys::Element = &el;
+          prefetch::observe(el);
+          observed.replace(Some(el.clone()));
+        }
+      }
+    });
+    on_cleanup(cx, move || {
+      if let Some(el) = observed.take() {
+        prefetch::unobserve(&el);
+      }
+    });
+  }
+  on_cleanup(cx, {
+    let timer = timer.clone();
+    move || {
+      if let Some(timer) = timer.take() {
+        prefetch::cancel_hover(timer);
+      }
+    }
+  });
+
+  let on_mouseenter = {
+    let href = href.clone();
+    let timer = timer.clone();
+    move |_| {
+      if timer.get().is_none() {
+        timer.set(prefetch::prefetch_on_hover(href.clone()));
+      }
+    }
+  };
+  let on_mouseleave = move |_| {
+    if let Some(timer) = timer.take() {
+      prefetch::cancel_hover(timer);
+    }
+  };
+
+  view! {
+    cx,
+    <a
+      href=href
+      class=move || class.as_ref().map(|class| class.get())
+      aria-current=aria_current
+      prop:replace=replace
+      _ref=anchor
+      on:mouseenter=on_mouseenter
+      on:mouseleave=on_mouseleave
+    >
+      {children(cx)}
+    </a>
+  }
+}
+
+/// A special version of the `<Link>` that adds the `active_class` when it matches the current location, it must be
+/// used in the `<Router>` of `leptos_router`.
+#[component]
+pub fn NavLink(
+  cx: Scope,
+  /// The url to link to.
+  #[prop(into)]
+  href: String,
+  /// Marks the link active only if the current location matches the url exactly, otherwise the nested routes of
+  /// the url activate the link as well.
+  #[prop(optional)]
+  exact: bool,
+  /// The class added to the link when it's active.
+  #[prop(optional, into)]
+  active_class: Option<String>,
+  #[prop(optional)] replace: bool,
+  #[prop(optional)] prefetch: bool,
+  #[prop(optional, into)] class: Option<String>,
+  children: Box<dyn FnOnce(Scope) -> Fragment>,
+) -> impl IntoView {
+  let location = use_location(cx);
+  let is_active = create_memo(cx, {
+    let href = href.clone();
+    move |_| router::is_active(&href, &location.pathname.get(), &location.search.get(), exact)
+  });
+  let class = MaybeSignal::derive(cx, move || {
+    let active_class = active_class.as_ref().filter(|_| is_active.get());
+    [class.as_ref(), active_class]
+      .into_iter()
+      .flatten()
+      .cloned()
+      .collect::<Vec<_>>()
+      .join(" ")
+  });
+
+  view! {
+    cx,
+    <Link href=href replace=replace prefetch=prefetch class=class>
+      {children(cx)}
+    </Link>
+  }
+}
+
+/// The error of the nearest `ErrorBoundary`.
+#[derive(Clone, Copy)]
+struct ErrorBoundaryContext(RwSignal<Option<PageError>>);
+
+/// Returns the function that reports the error to the nearest `ErrorBoundary`, e.g. the error of a resource. The
+/// error is logged to the console if there is no boundary.
+pub fn use_error_boundary(cx: Scope) -> Rc<dyn Fn(PageError)> {
+  let ctx = use_ssr_context(cx);
+  match use_context::<ErrorBoundaryContext>(cx) {
+    Some(ErrorBoundaryContext(caught)) => Rc::new(move |error| caught.set(Some(error))),
+    None => Rc::new(move |error| match &ctx {
+      Some(ctx) => ctx.report_error(&error),
+      None => web_sys::console::error_1(&error.to_string().into()),
+    }),
+  }
+}
+
+/// The `fallback` of the `<ErrorBoundary>`.
+pub type ErrorFallback = Rc<dyn Fn(Scope, PageError) -> View>;
+
+/// Catches the error thrown by the `data` of the route module, the errors reported by `use_error_boundary` and the
+/// `Err` of the `Result` views, and renders the `fallback` instead of the children like the `ErrorBoundary` of the
+/// React runtime. In the server-side rendering the status of the response is set to the status of the error, and the
+/// error is passed to the `onError` of the Aleph server. The error is cleared after navigating to another page, it
+/// must be used in the `<Router>` of `leptos_router`.
+///
+/// ```ignore
+/// view! { cx, <Router><Header/><ErrorBoundary><Routes>...</Routes></ErrorBoundary></Router> }
+/// ```
+#[component(transparent)]
+pub fn ErrorBoundary(
+  cx: Scope,
+  /// Renders the caught error, default is the `<ErrorPage>`.
+  #[prop(optional)]
+  fallback: Option<ErrorFallback>,
+  children: Box<dyn FnOnce(Scope) -> Fragment>,
+) -> impl IntoView {
+  let ctx = use_ssr_context(cx);
+  let location = use_location(cx);
+  let caught = create_rw_signal(
+    cx,
+    match &ctx {
+      Some(ctx) => ctx.error().cloned(),
+      None => router::ssr_page_error(&router::page_key()),
+    },
+  );
+  let errors = create_rw_signal(cx, Errors::default());
+  provide_context(cx, ErrorBoundaryContext(caught));
+  provide_context(cx, errors);
+  create_effect(cx, move |prev: Option<(String, String)>| {
+    let page = (location.pathname.get(), location.search.get());
+    if prev.is_some_and(|prev| prev != page) {
+      caught.set(None);
+    }
+    page
+  });
+
+  // runs the children first, so the errors they report are rendered
+  let children = children(cx);
+  move || {
+    let error = caught.get().or_else(|| {
+      errors.with(|errors| {
+        let error = errors.0.values().next()?;
+        Some(PageError::new(500, error.to_string()))
+      })
+    });
+    match (error, &fallback) {
+      (None, _) => children.clone().into_view(cx),
+      (Some(error), Some(fallback)) => {
+        if let Some(ctx) = &ctx {
+          ctx.report_error(&error);
+        }
+        fallback(cx, error)
+      }
+      (Some(error), None) => view! { cx, <ErrorPage error=error/> }.into_view(cx),
+    }
+  }
+}
+
+/// Renders the error like the `Err` component of the React runtime, the error is reported to the Aleph server in the
+/// server-side rendering, see `SsrContext::report_error`.
+#[component]
+pub fn ErrorPage(cx: Scope, error: PageError) -> impl IntoView {
+  if let Some(ctx) = use_ssr_context(cx) {
+    ctx.report_error(&error);
+  }
+  let PageError { status, message } = error;
+
+  view! {
+    cx,
+    <div class="aleph--error" style="margin:0;padding:1.5rem 2rem;color:red;font-size:18px">
+      <strong style="font-weight:600">{status}</strong>
+      <small style="opacity:0.5;padding:0 6px">"-"</small>
+      {message}
+    </div>
+  }
+}
+
+/// The `on_result` callback of the `<Form>`.
+pub type FormResultCallback = Rc<dyn Fn(Result<serde_json::Value, FormError>)>;
+
+/// The `<form>` that posts to the `mutation` of the route module, it works before the wasm is loaded: the browser
+/// submits the form and the Aleph server redirects back to the page after the mutation. Once the app is hydrated, the
+/// submission is sent by `fetch` and the result is passed to the `on_result` callback, the form is `aria-busy` until
+/// the mutation responds.
+///
+/// ```ignore
+/// view! { cx,
+///   <Form on_result=Rc::new(move |result| if let Ok(todos) = result { set_todos(todos) })>
+///     <input type="text" name="title"/>
+///   </Form>
+/// }
+/// ```
+#[component]
+pub fn Form(
+  cx: Scope,
+  /// The url that the form posts to, default is the current page.
+  #[prop(optional, into)]
+  action: Option<String>,
+  /// Use `multipart/form-data` to upload files, default is `application/x-www-form-urlencoded`.
+  #[prop(optional, into)]
+  enctype: Option<String>,
+  #[prop(optional, into)] class: Option<String>,
+  /// Resets the form after a successful submission.
+  #[prop(default = true)]
+  reset: bool,
+  /// The signal incremented after a successful submission, e.g. to refetch a resource.
+  #[prop(optional)]
+  version: Option<RwSignal<usize>>,
+  /// Receives the JSON responded by the `mutation` of the route module, or the error.
+  #[prop(optional)]
+  on_result: Option<FormResultCallback>,
+  children: Box<dyn FnOnce(Scope) -> Fragment>,
+) -> impl IntoView {
+  let (busy, set_busy) = create_signal(cx, false);
+  let on_submit = move |e: web_sys::SubmitEvent| {
+    if e.default_prevented() {
+      return;
+    }
+    e.prevent_default();
+    let form = match e
+      .target()
+      .and_then(|target| target.dyn_into::<web_sys::HtmlFormElement>().ok())
+    {
+      Some(form) if !busy.get() => form,
+      _ => return,
+    };
+    let submitter = e.submitter().and_then(|el| el.dyn_into::<web_sys::HtmlElement>().ok());
+    let on_result = on_result.clone();
+    set_busy.set(true);
+    spawn_local(async move {
+      let result = form::submit(&form, submitter).await;
+      if result.is_ok() {
+        if reset {
+          form.reset();
+        }
+        if let Some(version) = version {
+          version.update(|n| *n += 1);
+        }
+      }
+      set_busy.set(false);
+      if let Some(on_result) = on_result {
+        on_result(result);
+      }
+    });
+  };
+
+  view! {
+    cx,
+    <form
+      method="post"
+      action=action
+      enctype=enctype
+      class=class
+      aria-busy=move || busy.get().then_some("true")
+      on:submit=on_submit
+    >
+      {children(cx)}
+    </form>
+  }
+}
+
+/// Connects the `<Router>` of `leptos_router` to the event bus of Aleph.js, put it inside the router:
+///
+/// ```ignore
+/// view! { cx, <Router><RouterEvents/><Routes>...</Routes></Router> }
+/// ```
+#[component]
+pub fn RouterEvents(cx: Scope) -> impl IntoView {
+  let location = use_location(cx);
+  create_effect(cx, move |_| {
+    let _ = (location.pathname.get(), location.search.get());
+    events::notify_navigation();
+  });
+}
+
+/// Renders the app with the SSR context sent by the Aleph server.
+#[cfg(feature = "ssr")]
+pub fn render<F, N>(ctx: String, view: F) -> Result<JsValue, JsValue>
+where
+  F: FnOnce(Scope) -> N + 'static,
+  N: IntoView,
+{
+  panic::set_hook();
+  render_html(ctx, view)
+    .map(|output| JsValue::from_str(&output))
+    .map_err(|err| JsValue::from_str(&err))
+}
+
+/// Renders the app like the `render` function but returns the JSON output as a string, it's used by the native SSR,
+/// see `export_native_ssr!`.
+#[cfg(feature = "ssr")]
+pub fn render_html<F, N>(ctx: String, view: F) -> Result<String, String>
+where
+  F: FnOnce(Scope) -> N + 'static,
+  N: IntoView,
+{
+  let ctx = SsrContext::from_json(&ctx).map_err(|err| err.to_string())?;
+  let html = panic::with_render_id(ctx.render_id(), || {
+    render_to_string({
+      let ctx = ctx.clone();
+      move |cx| {
+        provide_context(cx, ctx);
+        view(cx)
+      }
+    })
+  });
+  Ok(ctx.finish(html))
+}
+
+/// Hydrates the app rendered by the `render` function, see `RouterEvents` to connect the router to the event
+/// bus of Aleph.js.
+#[cfg(feature = "hydrate")]
+pub fn hydrate<F, N>(view: F)
+where
+  F: FnOnce(Scope) -> N + 'static,
+  N: IntoView,
+{
+  panic::set_hook();
+  events::connect();
+  mount_to_body(view);
+  events::notify_hydrated();
+}
+
+/// Renders the view as an island of a React page, with the SSR context and the JSON props sent by the `<RustIsland>`
+/// component.
+#[cfg(feature = "ssr")]
+pub fn render_island<F, N, P>(ctx: String, props: String, view: F) -> Result<JsValue, JsValue>
+where
+  F: FnOnce(Scope, P) -> N + 'static,
+  N: IntoView,
+  P: DeserializeOwned + 'static,
+{
+  panic::set_hook();
+  let ctx = SsrContext::from_json(&ctx).map_err(|err| JsValue::from_str(&err.to_string()))?;
+  let props = serde_json::from_str(&props).map_err(|err| JsValue::from_str(&err.to_string()))?;
+  let html = panic::with_render_id(ctx.render_id(), || {
+    render_to_string({
+      let ctx = ctx.clone();
+      move |cx| {
+        provide_context(cx, ctx);
+        view(cx, props)
+      }
+    })
+  });
+  Ok(JsValue::from_str(&ctx.finish_island(html)))
+}
+
+/// Mounts the island rendered by the `render_island` function in the root element, it's named like the
+/// `hydrate_island` of `aleph::yew` for the `hydrate_island` export of the crate.
+///
+/// # Limitations
+///
+/// Leptos 0.1 hydrates the whole document only, its hydration keys of an island conflict with the other islands and
+/// the app. So the server html is replaced by a client-side render instead of being hydrated: the island flashes
+/// once the wasm is loaded, and its state isn't restored from the server html. Build the islands with the `csr`
+/// feature of leptos instead of `hydrate`. A warning is logged in the debug build.
+#[cfg(not(feature = "ssr"))]
+pub fn hydrate_island<F, N, P>(root: web_sys::Element, props: String, view: F) -> Result<IslandHandle, JsValue>
+where
+  F: FnOnce(Scope, P) -> N + 'static,
+  N: IntoView,
+  P: DeserializeOwned + 'static,
+{
+  panic::set_hook();
+  if cfg!(debug_assertions) {
+    web_sys::console::warn_1(
+      &"[aleph] The Leptos island is rendered on the client instead of being hydrated, see `aleph::leptos::hydrate_island`"
+        .into(),
+    );
+  }
+  let props = serde_json::from_str(&props).map_err(|err| JsValue::from_str(&err.to_string()))?;
+  let scope = Rc::new(Cell::new(None::<Scope>));
+  root.set_inner_html("");
+  mount_to(root.clone().unchecked_into(), {
+    let scope = scope.clone();
+    move |cx| {
+      scope.set(Some(cx));
+      view(cx, props)
+    }
+  });
+  Ok(IslandHandle::new(move || {
+    if let Some(cx) = scope.take() {
+      let runtime = cx.runtime;
+      cx.dispose();
+      runtime.dispose();
+    }
+    root.set_inner_html("");
+  }))
+}
@@ -0,0 +1,61 @@
+//! The Rust runtime for [Aleph.js](https://alephjs.org) apps.
+//!
+//! The `ssr` function exported by the app crate receives the SSR context from the Aleph server as JSON and returns
+//! the rendered html together with everything else the renderer needs to finish the response:
+//!
+//! ```ignore
+//! #[wasm_bindgen]
+//! pub async fn ssr(ctx: String) -> Result<JsValue, JsValue> {
+//!   aleph::yew::render::<app::App>(ctx).await
+//! }
+//! ```
+//!
+//! The app can also be compiled for the host target and rendered natively, see the `native` module.
+
+mod context;
+mod css;
+mod error;
+#[cfg(any(feature = "yew", feature = "leptos"))]
+mod form;
+mod i18n;
+mod island;
+#[cfg(any(feature = "yew", feature = "leptos"))]
+mod panic;
+#[cfg(any(feature = "yew", feature = "leptos"))]
+mod redirect;
+mod rewriter;
+#[cfg(any(feature = "yew", feature = "leptos"))]
+mod router;
+#[cfg(any(feature = "yew", feature = "native"))]
+#[cfg_attr(not(feature = "yew"), allow(dead_code))]
+mod routes;
+
+#[cfg(feature = "build")]
+pub mod build;
+#[cfg_attr(
+  not(any(feature = "yew", all(feature = "leptos", feature = "hydrate"))),
+  allow(dead_code)
+)]
+pub mod events;
+pub mod prefetch;
+
+#[cfg(feature = "leptos")]
+pub mod leptos;
+#[cfg(feature = "native")]
+pub mod native;
+#[cfg(feature = "yew")]
+pub mod yew;
+
+pub use aleph_macros::css_module;
+pub use context::{PageCache, RouteMatch, SsrContext};
+pub use css::CssModule;
+pub use error::PageError;
+#[cfg(any(feature = "yew", feature = "leptos"))]
+pub use form::FormError;
+pub use i18n::I18n;
+pub use island::IslandHandle;
+#[cfg(any(feature = "yew", feature = "leptos"))]
+pub use redirect::Redirector;
+pub use rewriter::{Comment, ContentType, Element, HtmlRewriter, HtmlRewriterHandlers, TextChunk};
+#[cfg(any(feature = "yew", feature = "native"))]
+pub use routes::match_route;
@@ -0,0 +1,167 @@
+//! The native SSR of the app crate, the app is compiled for the host target and loaded by the Aleph server with
+//! `Deno.dlopen` instead of running the wasm module in V8.
+//!
+//! The app crate exports the C ABI with the `export_native_ssr!` macro, and enables the `native` feature of the
+//! `aleph` crate for the host target only:
+//!
+//! ```ignore
+//! // src/lib.rs
+//! #[cfg(not(target_arch = "wasm32"))]
+//! aleph::export_native_ssr!(aleph::yew::render_html::<app::App>, routes = routes::__aleph_routes);
+//!
+//! // Cargo.toml
+//! [target.'cfg(not(target_arch = "wasm32"))'.dependencies]
+//! aleph = { version = "*", features = ["yew", "native"] }
+//! ```
+//!
+//! The `HtmlRewriter` handlers and the route chunks run in the JS runtime, they are not supported by the native SSR.
+//! A panic of the render is returned as an error, so don't build the native library with `panic = "abort"`, e.g. with
+//! a custom profile:
+//!
+//! ```ignore
+//! [profile.native]
+//! inherits = "release"
+//! panic = "unwind"
+//! ```
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct NativeError {
+  error: String,
+}
+
+thread_local! {
+  /// The single-threaded runtime of the renders on the current thread, it's reused by the next render.
+  static RUNTIME: RefCell<Option<tokio::runtime::Runtime>> = const { RefCell::new(None) };
+}
+
+/// Renders the page with the SSR context JSON, returns the JSON output of the render function, or `{"error"}` if it
+/// failed. The Aleph server calls it on the worker threads, see `render`.
+///
+/// # Safety
+///
+/// The `input` must point to `len` bytes of valid memory.
+pub unsafe fn ssr<F, Fut>(input: *const u8, len: usize, render: F) -> *mut c_char
+where
+  F: FnOnce(String) -> Fut,
+  Fut: Future<Output = Result<String, String>>,
+{
+  let ctx = String::from_utf8_lossy(std::slice::from_raw_parts(input, len)).into_owned();
+  let output = self::render(ctx, render);
+  into_raw(output.unwrap_or_else(|error| serde_json::to_string(&NativeError { error }).unwrap()))
+}
+
+/// Runs the render function on the single-threaded runtime of the current thread and blocks the thread until it's
+/// done, the panic of the render is returned as an error. The runtime is created by the first render of the thread and
+/// reused by the next ones, a runtime whose render panicked is dropped.
+pub fn render<F, Fut>(ctx: String, render: F) -> Result<String, String>
+where
+  F: FnOnce(String) -> Fut,
+  Fut: Future<Output = Result<String, String>>,
+{
+  // a panic can't unwind across the C ABI, it would abort the Aleph server
+  panic::catch_unwind(AssertUnwindSafe(|| {
+    let rt = match RUNTIME.with(|rt| rt.borrow_mut().take()) {
+      Some(rt) => rt,
+      None => tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|err| err.to_string())?,
+    };
+    // the tasks spawned by the render are dropped with the `LocalSet`
+    let output = rt.block_on(tokio::task::LocalSet::new().run_until(render(ctx)));
+    RUNTIME.with(|cell| *cell.borrow_mut() = Some(rt));
+    output
+  }))
+  .unwrap_or_else(|err| {
+    let message = err
+      .downcast_ref::<&str>()
+      .map(|s| s.to_string())
+      .or_else(|| err.downcast_ref::<String>().cloned())
+      .unwrap_or_default();
+    Err(format!("panicked: {}", message))
+  })
+}
+
+/// Returns the route table of the `routes!` macro, or a null pointer if the app doesn't use it.
+pub fn routes(routes: Option<fn() -> String>) -> *mut c_char {
+  match routes {
+    Some(routes) => into_raw(routes()),
+    None => std::ptr::null_mut(),
+  }
+}
+
+/// Frees the string returned by `ssr` or `routes`.
+///
+/// # Safety
+///
+/// The `ptr` must be returned by `ssr` or `routes`, and it's freed only once.
+pub unsafe fn free(ptr: *mut c_char) {
+  if !ptr.is_null() {
+    drop(CString::from_raw(ptr));
+  }
+}
+
+fn into_raw(output: String) -> *mut c_char {
+  // the output is JSON, the nul bytes are escaped
+  CString::new(output).unwrap_or_default().into_raw()
+}
+
+/// Exports the `aleph_ssr`, `aleph_routes` and `aleph_free` functions of the C ABI for the native SSR, see the
+/// `native` module.
+#[macro_export]
+macro_rules! export_native_ssr {
+  ($render:expr) => {
+    $crate::export_native_ssr!(@export $render, None);
+  };
+  ($render:expr, routes = $routes:path) => {
+    $crate::export_native_ssr!(@export $render, Some($routes));
+  };
+  (@export $render:expr, $routes:expr) => {
+    /// # Safety
+    ///
+    /// The `input` must point to `len` bytes of valid memory.
+    #[no_mangle]
+    pub unsafe extern "C" fn aleph_ssr(input: *const u8, len: usize) -> *mut ::std::ffi::c_char {
+      $crate::native::ssr(input, len, $render)
+    }
+
+    #[no_mangle]
+    pub extern "C" fn aleph_routes() -> *mut ::std::ffi::c_char {
+      $crate::native::routes($routes)
+    }
+
+    /// # Safety
+    ///
+    /// The `ptr` must be returned by `aleph_ssr` or `aleph_routes`, and it's freed only once.
+    #[no_mangle]
+    pub unsafe extern "C" fn aleph_free(ptr: *mut ::std::ffi::c_char) {
+      $crate::native::free(ptr)
+    }
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reuse_the_runtime() {
+    let has_runtime = || RUNTIME.with(|rt| rt.borrow().is_some());
+    assert_eq!(render("a".to_owned(), |ctx| async move { Ok(ctx) }), Ok("a".to_owned()));
+    assert!(has_runtime());
+    assert_eq!(
+      render("b".to_owned(), |_| async move { panic!("boom") }),
+      Err::<String, _>("panicked: boom".to_owned())
+    );
+    assert!(!has_runtime());
+    assert_eq!(render("c".to_owned(), |ctx| async move { Ok(ctx) }), Ok("c".to_owned()));
+    assert!(has_runtime());
+  }
+}
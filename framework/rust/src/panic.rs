@@ -0,0 +1,119 @@
+//! The panic hook of the Rust apps, it's set by the `render` and `hydrate` functions. The panic message with its
+//! location, e.g. `panicked at src/routes/todos.rs:42:5`, and the JS stack of the wasm frames are passed to the
+//! `__ALEPH_RUST_PANIC_HOOK` of Aleph.js: the server rejects the render with them and logs the frames symbolized with
+//! the DWARF info of the dev build, and the browser shows them in the error overlay in development. Without the hook,
+//! e.g. in production, they're logged to the console like `console_error_panic_hook` does.
+//!
+//! The panic is reported with the id of the render polled, so the server rejects the render that panicked only, and
+//! the memory of the wasm instance, so the server replaces the trapped instance with a new one.
+
+use std::cell::Cell;
+#[cfg(feature = "yew")]
+use std::future::Future;
+#[cfg(feature = "yew")]
+use std::pin::Pin;
+use std::sync::Once;
+#[cfg(feature = "yew")]
+use std::task::{Context, Poll};
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// Sets the panic hook once, it replaces the hook set by the app before. The hook runs with `panic = 'abort'` too,
+/// the wasm instance traps after it.
+pub(crate) fn set_hook() {
+  static SET_HOOK: Once = Once::new();
+  // the native SSR returns the panics as errors, see the `native` module
+  if !cfg!(target_arch = "wasm32") {
+    return;
+  }
+  SET_HOOK.call_once(|| std::panic::set_hook(Box::new(|info| report(&info.to_string()))));
+}
+
+thread_local! {
+  // the id of the render in progress, it's sent by the Aleph server in the SSR context
+  static RENDER_ID: Cell<Option<u32>> = const { Cell::new(None) };
+}
+
+/// Runs the render with its id, the panics of the render are reported with the id.
+#[cfg(any(feature = "yew", all(feature = "leptos", feature = "ssr")))]
+pub(crate) fn with_render_id<R>(id: Option<u32>, render: impl FnOnce() -> R) -> R {
+  let prev = RENDER_ID.with(|cell| cell.replace(id));
+  let ret = render();
+  RENDER_ID.with(|cell| cell.set(prev));
+  ret
+}
+
+/// The future of an async render, it's polled with the id of the render like `with_render_id`. The tasks spawned by
+/// the render are polled without the id, their panics can't be attributed to the render.
+#[cfg(feature = "yew")]
+pub(crate) struct Render<F: Future> {
+  id: Option<u32>,
+  future: Pin<Box<F>>,
+}
+
+#[cfg(feature = "yew")]
+impl<F: Future> Render<F> {
+  pub fn new(id: Option<u32>, future: F) -> Self {
+    Self {
+      id,
+      future: Box::pin(future),
+    }
+  }
+}
+
+#[cfg(feature = "yew")]
+impl<F: Future> Future for Render<F> {
+  type Output = F::Output;
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let id = self.id;
+    with_render_id(id, || self.future.as_mut().poll(cx))
+  }
+}
+
+fn report(message: &str) {
+  // the stack of the error created in the hook, its wasm frames are the frames of the panic. The frames of std come
+  // first, so the stack limit of V8 is raised to keep the frames of the app
+  let error_class = js_sys::Reflect::get(&js_sys::global(), &"Error".into()).unwrap_or_default();
+  let limit_key = JsValue::from_str("stackTraceLimit");
+  let limit = js_sys::Reflect::get(&error_class, &limit_key).unwrap_or_default();
+  let _ = js_sys::Reflect::set(&error_class, &limit_key, &100.into());
+  let stack = js_sys::Reflect::get(&js_sys::Error::new(""), &"stack".into())
+    .ok()
+    .and_then(|stack| stack.as_string())
+    .unwrap_or_default();
+  let _ = js_sys::Reflect::set(&error_class, &limit_key, &limit);
+  let hook = js_sys::Reflect::get(&js_sys::global(), &"__ALEPH_RUST_PANIC_HOOK".into())
+    .ok()
+    .and_then(|hook| hook.dyn_into::<js_sys::Function>().ok());
+  match hook {
+    Some(hook) => {
+      let panic = js_sys::Object::new();
+      let _ = js_sys::Reflect::set(&panic, &"message".into(), &message.into());
+      let _ = js_sys::Reflect::set(&panic, &"stack".into(), &stack.into());
+      let _ = js_sys::Reflect::set(&panic, &"memory".into(), &wasm_bindgen::memory());
+      if let Some(id) = RENDER_ID.with(Cell::get) {
+        let _ = js_sys::Reflect::set(&panic, &"renderId".into(), &id.into());
+      }
+      let _ = hook.call1(&JsValue::UNDEFINED, &panic);
+    }
+    None => web_sys::console::error_1(&format!("{message}\n\nStack:\n\n{stack}").into()),
+  }
+}
+
+#[cfg(all(test, feature = "yew"))]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn poll_with_the_render_id() {
+    let mut render = Render::new(
+      Some(7),
+      std::future::poll_fn(|_| Poll::Ready(RENDER_ID.with(Cell::get))),
+    );
+    let mut cx = Context::from_waker(std::task::Waker::noop());
+    assert_eq!(Pin::new(&mut render).poll(&mut cx), Poll::Ready(Some(7)));
+    assert_eq!(RENDER_ID.with(Cell::get), None);
+  }
+}
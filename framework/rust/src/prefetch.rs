@@ -0,0 +1,260 @@
+//! Prefetching the data and the wasm chunks of a page before navigating to it.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+#[cfg(any(feature = "yew", feature = "leptos"))]
+use web_sys::{IntersectionObserver, IntersectionObserverEntry};
+
+#[cfg(feature = "yew")]
+use crate::PageError;
+
+#[cfg(any(feature = "yew", feature = "leptos"))]
+type ObserverCallback = Closure<dyn FnMut(js_sys::Array, IntersectionObserver)>;
+
+thread_local! {
+  static PREFETCHED: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+  static DATA: RefCell<HashMap<String, js_sys::Promise>> = RefCell::new(HashMap::new());
+  static CHUNKS: RefCell<Vec<(ChunkPath, String)>> = const { RefCell::new(Vec::new()) };
+  #[cfg(any(feature = "yew", feature = "leptos"))]
+  static OBSERVER: RefCell<Option<(IntersectionObserver, ObserverCallback)>> = const { RefCell::new(None) };
+}
+
+/// The pages of a chunk, matched by the pathname of the prefetched link.
+#[derive(Clone, PartialEq)]
+enum ChunkPath {
+  Prefix(String),
+  Exact(String),
+}
+
+impl ChunkPath {
+  fn matches(&self, pathname: &str) -> bool {
+    match self {
+      ChunkPath::Prefix(prefix) => pathname.starts_with(prefix.as_str()),
+      ChunkPath::Exact(path) => pathname == path,
+    }
+  }
+}
+
+/// Registers a lazily-split wasm chunk (the `.wasm` file or its js glue) of the pages under the path prefix, the
+/// chunk is preloaded when a link to these pages is prefetched.
+pub fn register_chunk(path_prefix: impl Into<String>, url: impl Into<String>) {
+  push_chunk(ChunkPath::Prefix(path_prefix.into()), url.into());
+}
+
+/// Registers the wasm chunk of a route split by the `routes!` macro, the js glue and the `_bg.wasm` file are
+/// preloaded when a link matching the static segments of the pattern is prefetched, e.g. `/todos/` of `/todos/:id`.
+pub fn register_route_chunk(pattern: &str, module: &str) {
+  let path = match pattern.find(':') {
+    Some(index) => ChunkPath::Prefix(pattern[..index].to_owned()),
+    None => ChunkPath::Exact(pattern.to_owned()),
+  };
+  let wasm = format!("{}_bg.wasm", module.trim_end_matches(".js"));
+  for url in [module.to_owned(), wasm] {
+    let exists = CHUNKS.with(|chunks| chunks.borrow().iter().any(|(p, u)| *p == path && *u == url));
+    if !exists {
+      push_chunk(path.clone(), url);
+    }
+  }
+}
+
+fn push_chunk(path: ChunkPath, url: String) {
+  CHUNKS.with(|chunks| chunks.borrow_mut().push((path, url)));
+}
+
+/// Prefetches the `?_data_` payload and the wasm chunks of the page, like the `prefetchModule` of the React runtime.
+/// Does nothing on the server or for external links.
+pub fn prefetch(href: &str) {
+  let (pathname, search) = match resolve(href) {
+    Some(url) => url,
+    None => return,
+  };
+  let key = format!("{}{}", pathname, search);
+  if !PREFETCHED.with(|prefetched| prefetched.borrow_mut().insert(key.clone())) {
+    return;
+  }
+
+  let data_url = data_url(&pathname, &search);
+  let promise = future_to_promise(async move {
+    // resolves `undefined` if the prefetching failed, the page fetches the data again
+    Ok(fetch_json(&data_url).await.unwrap_or(JsValue::UNDEFINED))
+  });
+  DATA.with(|data| data.borrow_mut().insert(key, promise));
+
+  let document = match web_sys::window().and_then(|window| window.document()) {
+    Some(document) => document,
+    None => return,
+  };
+  CHUNKS.with(|chunks| {
+    for (path, url) in chunks.borrow().iter() {
+      if path.matches(&pathname) {
+        preload_chunk(&document, url);
+      }
+    }
+  });
+}
+
+/// Takes the prefetched `?_data_` payload of the page, the promise resolves `undefined` if the prefetching failed.
+pub fn take_prefetched_data(href: &str) -> Option<js_sys::Promise> {
+  let (pathname, search) = resolve(href)?;
+  let key = format!("{}{}", pathname, search);
+  PREFETCHED.with(|prefetched| prefetched.borrow_mut().remove(&key));
+  DATA.with(|data| data.borrow_mut().remove(&key))
+}
+
+/// Loads the `?_data_` payload of the page for the `use_data` hook, the prefetched one is used if it exists. The
+/// error responded by the `data` of the route module is parsed like the `FetchError.fromResponse` of Aleph.js.
+#[cfg(feature = "yew")]
+pub(crate) async fn load_data(href: &str) -> Result<JsValue, PageError> {
+  if let Some(promise) = take_prefetched_data(href) {
+    if let Ok(data) = JsFuture::from(promise).await {
+      if !data.is_undefined() {
+        return Ok(data);
+      }
+    }
+  }
+  let failed = |_| PageError::new(500, "Failed to load the data of the page");
+  let (pathname, search) = resolve(href).ok_or_else(|| failed(JsValue::UNDEFINED))?;
+  let window = web_sys::window().ok_or_else(|| failed(JsValue::UNDEFINED))?;
+  let res: web_sys::Response = JsFuture::from(window.fetch_with_str(&data_url(&pathname, &search)))
+    .await
+    .and_then(|res| res.dyn_into())
+    .map_err(failed)?;
+  if res.ok() {
+    return JsFuture::from(res.json().map_err(failed)?).await.map_err(failed);
+  }
+  let text = JsFuture::from(res.text().map_err(failed)?)
+    .await
+    .ok()
+    .and_then(|text| text.as_string())
+    .unwrap_or_default();
+  let json = serde_json::from_str::<serde_json::Value>(&text).ok();
+  let field = |name: &str| json.as_ref().and_then(|json| json.get(name));
+  Err(PageError::new(
+    field("status")
+      .and_then(|status| status.as_u64())
+      .map_or(res.status(), |status| status as u16),
+    field("message")
+      .and_then(|message| message.as_str())
+      .map_or(text.clone(), ToOwned::to_owned),
+  ))
+}
+
+/// Prefetches the page of the link once it enters the viewport.
+#[cfg(any(feature = "yew", feature = "leptos"))]
+pub(crate) fn observe(el: &web_sys::Element) {
+  OBSERVER.with(|observer| {
+    let mut observer = observer.borrow_mut();
+    if observer.is_none() {
+      let callback: ObserverCallback = Closure::new(|entries: js_sys::Array, observer: IntersectionObserver| {
+        for entry in entries.iter() {
+          let entry: IntersectionObserverEntry = entry.unchecked_into();
+          if entry.is_intersecting() {
+            let target = entry.target();
+            observer.unobserve(&target);
+            if let Some(href) = target.get_attribute("href") {
+              prefetch(&href);
+            }
+          }
+        }
+      });
+      match IntersectionObserver::new(callback.as_ref().unchecked_ref()) {
+        Ok(io) => *observer = Some((io, callback)),
+        Err(_) => return,
+      }
+    }
+    if let Some((io, _)) = observer.as_ref() {
+      io.observe(el);
+    }
+  })
+}
+
+#[cfg(any(feature = "yew", feature = "leptos"))]
+pub(crate) fn unobserve(el: &web_sys::Element) {
+  OBSERVER.with(|observer| {
+    if let Some((io, _)) = observer.borrow().as_ref() {
+      io.unobserve(el);
+    }
+  })
+}
+
+/// Prefetches the page after hovering over the link for 150ms, returns the timer to cancel it.
+#[cfg(any(feature = "yew", feature = "leptos"))]
+pub(crate) fn prefetch_on_hover(href: String) -> Option<i32> {
+  let window = web_sys::window()?;
+  let callback = Closure::once_into_js(move || prefetch(&href));
+  window
+    .set_timeout_with_callback_and_timeout_and_arguments_0(callback.unchecked_ref(), 150)
+    .ok()
+}
+
+#[cfg(any(feature = "yew", feature = "leptos"))]
+pub(crate) fn cancel_hover(timer: i32) {
+  if let Some(window) = web_sys::window() {
+    window.clear_timeout_with_handle(timer);
+  }
+}
+
+/// Resolves the href with the current location, returns `None` for external links.
+fn resolve(href: &str) -> Option<(String, String)> {
+  let location = web_sys::window()?.location();
+  let url = web_sys::Url::new_with_base(href, &location.href().ok()?).ok()?;
+  if url.origin() != location.origin().ok()? {
+    return None;
+  }
+  Some((url.pathname(), url.search()))
+}
+
+fn data_url(pathname: &str, search: &str) -> String {
+  format!(
+    "{}{}{}_data_",
+    pathname,
+    search,
+    if search.is_empty() { "?" } else { "&" }
+  )
+}
+
+async fn fetch_json(url: &str) -> Result<JsValue, JsValue> {
+  let window = web_sys::window().ok_or(JsValue::UNDEFINED)?;
+  let res: web_sys::Response = JsFuture::from(window.fetch_with_str(url)).await?.dyn_into()?;
+  if !res.ok() {
+    return Err(JsValue::from(res.status()));
+  }
+  JsFuture::from(res.json()?).await
+}
+
+/// Appends the deployment id of the page to the url of a static file, like the `wasmURL` function of `aleph/rust`.
+pub(crate) fn versioned_url(url: &str) -> String {
+  let deployment_id = web_sys::window()
+    .and_then(|window| window.document())
+    .and_then(|document| document.body())
+    .and_then(|body| body.get_attribute("data-deployment-id"));
+  match deployment_id {
+    Some(v) => format!("{}?v={}", url, v),
+    None => url.to_owned(),
+  }
+}
+
+fn preload_chunk(document: &web_sys::Document, url: &str) {
+  let selector = format!("link[data-module-id=\"{}\"]", url);
+  if document.query_selector(&selector).ok().flatten().is_some() {
+    return;
+  }
+  let (head, link) = match (document.head(), document.create_element("link")) {
+    (Some(head), Ok(link)) => (head, link),
+    _ => return,
+  };
+  if url.ends_with(".js") {
+    let _ = link.set_attribute("rel", "modulepreload");
+  } else {
+    let _ = link.set_attribute("rel", "prefetch");
+    let _ = link.set_attribute("as", "fetch");
+    let _ = link.set_attribute("crossorigin", "");
+  }
+  let _ = link.set_attribute("href", &versioned_url(url));
+  let _ = link.set_attribute("data-module-id", url);
+  let _ = head.append_child(&link);
+}
@@ -0,0 +1,27 @@
+//! The redirects of the pages and the loaders, see `use_redirect` of the `yew` and `leptos` modules.
+
+use crate::{events, SsrContext};
+
+/// Redirects to another page, it's returned by `use_redirect` and can be moved into the loaders. In the server-side
+/// rendering the Aleph server responds the redirect instead of the page, see `SsrContext::redirect`. In the browser it
+/// navigates through the router, replacing the current history entry.
+#[derive(Clone)]
+pub struct Redirector(Option<SsrContext>);
+
+impl Redirector {
+  pub(crate) fn new(ctx: Option<SsrContext>) -> Self {
+    Self(ctx)
+  }
+
+  /// Redirects to the url with the `status`, e.g. `303` to the login page. The `status` is only used by the server.
+  pub fn redirect(&self, to: &str, status: u16) {
+    match &self.0 {
+      Some(ctx) => ctx.redirect(to, status),
+      None => {
+        // the router can't be updated during the rendering
+        let to = to.to_owned();
+        wasm_bindgen_futures::spawn_local(async move { events::navigate(&to, true) });
+      }
+    }
+  }
+}
@@ -0,0 +1,279 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[wasm_bindgen]
+extern "C" {
+  /// An element of the html output, see https://github.com/cloudflare/lol-html.
+  pub type Element;
+
+  #[wasm_bindgen(method, getter, js_name = tagName)]
+  pub fn tag_name(this: &Element) -> String;
+  #[wasm_bindgen(method, getter)]
+  pub fn removed(this: &Element) -> bool;
+  #[wasm_bindgen(method, js_name = getAttribute)]
+  pub fn get_attribute(this: &Element, name: &str) -> Option<String>;
+  #[wasm_bindgen(method, js_name = hasAttribute)]
+  pub fn has_attribute(this: &Element, name: &str) -> bool;
+  #[wasm_bindgen(method, js_name = setAttribute)]
+  pub fn set_attribute(this: &Element, name: &str, value: &str);
+  #[wasm_bindgen(method, js_name = removeAttribute)]
+  pub fn remove_attribute(this: &Element, name: &str);
+  #[wasm_bindgen(method, js_name = before)]
+  fn before_content(this: &Element, content: &str, options: &JsValue);
+  #[wasm_bindgen(method, js_name = after)]
+  fn after_content(this: &Element, content: &str, options: &JsValue);
+  #[wasm_bindgen(method, js_name = prepend)]
+  fn prepend_content(this: &Element, content: &str, options: &JsValue);
+  #[wasm_bindgen(method, js_name = append)]
+  fn append_content(this: &Element, content: &str, options: &JsValue);
+  #[wasm_bindgen(method, js_name = replace)]
+  fn replace_content(this: &Element, content: &str, options: &JsValue);
+  #[wasm_bindgen(method, js_name = setInnerContent)]
+  fn set_inner_content_raw(this: &Element, content: &str, options: &JsValue);
+  #[wasm_bindgen(method)]
+  pub fn remove(this: &Element);
+  #[wasm_bindgen(method, js_name = removeAndKeepContent)]
+  pub fn remove_and_keep_content(this: &Element);
+
+  /// A text chunk of the html output.
+  pub type TextChunk;
+
+  #[wasm_bindgen(method, getter)]
+  pub fn text(this: &TextChunk) -> String;
+  #[wasm_bindgen(method, getter, js_name = lastInTextNode)]
+  pub fn last_in_text_node(this: &TextChunk) -> bool;
+  #[wasm_bindgen(method, getter)]
+  pub fn removed(this: &TextChunk) -> bool;
+  #[wasm_bindgen(method, js_name = before)]
+  fn before_content(this: &TextChunk, content: &str, options: &JsValue);
+  #[wasm_bindgen(method, js_name = after)]
+  fn after_content(this: &TextChunk, content: &str, options: &JsValue);
+  #[wasm_bindgen(method, js_name = replace)]
+  fn replace_content(this: &TextChunk, content: &str, options: &JsValue);
+  #[wasm_bindgen(method)]
+  pub fn remove(this: &TextChunk);
+
+  /// A comment of the html output.
+  pub type Comment;
+
+  #[wasm_bindgen(method, getter)]
+  pub fn text(this: &Comment) -> String;
+  #[wasm_bindgen(method, setter)]
+  pub fn set_text(this: &Comment, text: &str);
+  #[wasm_bindgen(method, getter)]
+  pub fn removed(this: &Comment) -> bool;
+  #[wasm_bindgen(method, js_name = before)]
+  fn before_content(this: &Comment, content: &str, options: &JsValue);
+  #[wasm_bindgen(method, js_name = after)]
+  fn after_content(this: &Comment, content: &str, options: &JsValue);
+  #[wasm_bindgen(method, js_name = replace)]
+  fn replace_content(this: &Comment, content: &str, options: &JsValue);
+  #[wasm_bindgen(method)]
+  pub fn remove(this: &Comment);
+}
+
+/// The type of the content inserted by the rewriter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContentType {
+  /// The content is escaped as text.
+  Text,
+  /// The content is inserted as raw html.
+  Html,
+}
+
+impl ContentType {
+  fn options(self) -> JsValue {
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"html".into(), &(self == ContentType::Html).into()).unwrap();
+    options.into()
+  }
+}
+
+impl Element {
+  pub fn before(&self, content: &str, content_type: ContentType) {
+    self.before_content(content, &content_type.options())
+  }
+
+  pub fn after(&self, content: &str, content_type: ContentType) {
+    self.after_content(content, &content_type.options())
+  }
+
+  pub fn prepend(&self, content: &str, content_type: ContentType) {
+    self.prepend_content(content, &content_type.options())
+  }
+
+  pub fn append(&self, content: &str, content_type: ContentType) {
+    self.append_content(content, &content_type.options())
+  }
+
+  pub fn replace(&self, content: &str, content_type: ContentType) {
+    self.replace_content(content, &content_type.options())
+  }
+
+  pub fn set_inner_content(&self, content: &str, content_type: ContentType) {
+    self.set_inner_content_raw(content, &content_type.options())
+  }
+}
+
+impl TextChunk {
+  pub fn before(&self, content: &str, content_type: ContentType) {
+    self.before_content(content, &content_type.options())
+  }
+
+  pub fn after(&self, content: &str, content_type: ContentType) {
+    self.after_content(content, &content_type.options())
+  }
+
+  pub fn replace(&self, content: &str, content_type: ContentType) {
+    self.replace_content(content, &content_type.options())
+  }
+}
+
+impl Comment {
+  pub fn before(&self, content: &str, content_type: ContentType) {
+    self.before_content(content, &content_type.options())
+  }
+
+  pub fn after(&self, content: &str, content_type: ContentType) {
+    self.after_content(content, &content_type.options())
+  }
+
+  pub fn replace(&self, content: &str, content_type: ContentType) {
+    self.replace_content(content, &content_type.options())
+  }
+}
+
+type Handler<T> = Option<Box<dyn Fn(&T)>>;
+
+/// The handlers of a selector, mirrors the `HTMLRewriterHandlers` of the Aleph server.
+#[derive(Default)]
+pub struct HtmlRewriterHandlers {
+  element: Handler<Element>,
+  text: Handler<TextChunk>,
+  comments: Handler<Comment>,
+}
+
+impl HtmlRewriterHandlers {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn element(mut self, handler: impl Fn(&Element) + 'static) -> Self {
+    self.element = Some(Box::new(handler));
+    self
+  }
+
+  pub fn text(mut self, handler: impl Fn(&TextChunk) + 'static) -> Self {
+    self.text = Some(Box::new(handler));
+    self
+  }
+
+  pub fn comments(mut self, handler: impl Fn(&Comment) + 'static) -> Self {
+    self.comments = Some(Box::new(handler));
+    self
+  }
+}
+
+/// The HtmlRewriter to rewrite the html output of current request, including the index.html shell.
+///
+/// ```ignore
+/// ctx.html_rewriter().on(
+///   "a[target=_blank]",
+///   HtmlRewriterHandlers::new().element(|el| el.set_attribute("rel", "noopener noreferrer")),
+/// );
+/// ```
+pub struct HtmlRewriter<'a> {
+  handlers: &'a RefCell<Vec<(String, HtmlRewriterHandlers)>>,
+}
+
+impl<'a> HtmlRewriter<'a> {
+  pub(crate) fn new(handlers: &'a RefCell<Vec<(String, HtmlRewriterHandlers)>>) -> Self {
+    Self { handlers }
+  }
+
+  pub fn on(&self, selector: impl Into<String>, handlers: HtmlRewriterHandlers) {
+    self.handlers.borrow_mut().push((selector.into(), handlers));
+  }
+}
+
+/// The manifest of the registered handlers, the Aleph server calls back into the wasm module with the `id` and the
+/// index of the selector.
+#[derive(Serialize)]
+pub(crate) struct HtmlRewriterManifest {
+  id: u32,
+  selectors: Vec<SelectorManifest>,
+}
+
+#[derive(Serialize)]
+struct SelectorManifest {
+  selector: String,
+  element: bool,
+  text: bool,
+  comments: bool,
+}
+
+thread_local! {
+  static HANDLERS: RefCell<HashMap<u32, Vec<HtmlRewriterHandlers>>> = RefCell::new(HashMap::new());
+  static NEXT_ID: Cell<u32> = const { Cell::new(1) };
+}
+
+pub(crate) fn register(handlers: Vec<(String, HtmlRewriterHandlers)>) -> Option<HtmlRewriterManifest> {
+  if handlers.is_empty() {
+    return None;
+  }
+  let id = NEXT_ID.with(|next| next.replace(next.get().wrapping_add(1)));
+  let (selectors, handlers): (Vec<_>, Vec<_>) = handlers
+    .into_iter()
+    .map(|(selector, handlers)| {
+      let manifest = SelectorManifest {
+        selector,
+        element: handlers.element.is_some(),
+        text: handlers.text.is_some(),
+        comments: handlers.comments.is_some(),
+      };
+      (manifest, handlers)
+    })
+    .unzip();
+  HANDLERS.with(|map| map.borrow_mut().insert(id, handlers));
+  Some(HtmlRewriterManifest { id, selectors })
+}
+
+/// Calls the handler registered by the `ssr` function, invoked by the Aleph server for every matched node.
+#[wasm_bindgen(js_name = "__aleph_html_rewriter")]
+pub fn dispatch(id: u32, index: usize, kind: &str, node: JsValue) {
+  HANDLERS.with(|map| {
+    let map = map.borrow();
+    let handlers = match map.get(&id).and_then(|handlers| handlers.get(index)) {
+      Some(handlers) => handlers,
+      None => return,
+    };
+    match kind {
+      "element" => {
+        if let Some(handler) = &handlers.element {
+          handler(node.unchecked_ref())
+        }
+      }
+      "text" => {
+        if let Some(handler) = &handlers.text {
+          handler(node.unchecked_ref())
+        }
+      }
+      "comments" => {
+        if let Some(handler) = &handlers.comments {
+          handler(node.unchecked_ref())
+        }
+      }
+      _ => {}
+    }
+  })
+}
+
+/// Drops the handlers once the response is finished.
+#[wasm_bindgen(js_name = "__aleph_html_rewriter_free")]
+pub fn free(id: u32) {
+  HANDLERS.with(|map| map.borrow_mut().remove(&id));
+}
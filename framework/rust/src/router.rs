@@ -0,0 +1,89 @@
+//! The helpers shared by the router integrations.
+
+use std::cell::RefCell;
+
+use serde::Deserialize;
+
+use crate::PageError;
+
+thread_local! {
+  // the page of the `aleph-data` script, it's dropped after navigating to another page
+  static SSR_PAGE: RefCell<Option<Option<SsrPage>>> = const { RefCell::new(None) };
+}
+
+/// The route module with data of the `aleph-data` script embedded by the rust plugin, the data-only route modules are
+/// not in the `ssr-data` of the Aleph server.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsrPage {
+  url: String,
+  #[serde(default)]
+  with_data: bool,
+  #[cfg(feature = "yew")]
+  #[serde(default)]
+  data: Option<serde_json::Value>,
+  #[serde(default)]
+  error: Option<PageError>,
+}
+
+/// The pathname and the query of the current location, e.g. `/todos?page=2`.
+pub(crate) fn page_key() -> String {
+  web_sys::window()
+    .map(|window| window.location())
+    .and_then(|location| Some(location.pathname().ok()? + &location.search().ok()?))
+    .unwrap_or_default()
+}
+
+/// Returns the page data sent by the server if the page is still the one rendered by the server.
+#[cfg(feature = "yew")]
+pub(crate) fn ssr_page_data(key: &str) -> Option<serde_json::Value> {
+  with_ssr_page(key, |page| page.data.clone())
+}
+
+/// Returns the error thrown by the `data` of the page rendered by the server, e.g. a `FetchError`.
+pub(crate) fn ssr_page_error(key: &str) -> Option<PageError> {
+  with_ssr_page(key, |page| page.error.clone())
+}
+
+fn with_ssr_page<T>(key: &str, f: impl FnOnce(&SsrPage) -> Option<T>) -> Option<T> {
+  SSR_PAGE.with(|cell| {
+    let mut cell = cell.borrow_mut();
+    let page = cell.get_or_insert_with(|| {
+      let json = web_sys::window()?
+        .document()?
+        .get_element_by_id("aleph-data")?
+        .text_content()?;
+      let pages: Vec<SsrPage> = serde_json::from_str(&json).ok()?;
+      pages.into_iter().rev().find(|page| page.with_data)
+    });
+    match page {
+      Some(page) if page.url == key => f(page),
+      _ => {
+        // navigated to another page, the data of the server is stale even if coming back
+        *page = None;
+        None
+      }
+    }
+  })
+}
+
+/// Checks if the link is active for the current location like the `NavLink` of the React runtime: the link is
+/// active if the current pathname is the pathname of the link or is nested in it, with `exact` both the pathname and
+/// the query must match.
+pub(crate) fn is_active(href: &str, pathname: &str, search: &str, exact: bool) -> bool {
+  let href = href.split('#').next().unwrap_or_default();
+  let (path, query) = href.split_once('?').unwrap_or((href, ""));
+  let path = trim_slash(path);
+  let current = trim_slash(pathname);
+  if exact {
+    return path == current && query == search.trim_start_matches('?');
+  }
+  path == current || (path != "/" && current.starts_with(path) && current[path.len()..].starts_with('/'))
+}
+
+fn trim_slash(path: &str) -> &str {
+  match path.trim_end_matches('/') {
+    "" => "/",
+    path => path,
+  }
+}
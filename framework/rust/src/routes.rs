@@ -0,0 +1,160 @@
+//! The matching of the route table generated by the `routes!` macro.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Deserialize;
+
+use crate::RouteMatch;
+
+/// Matches the pathname with the URL pattern, it's a port of `URLPatternCompat.execPathname` of the router of
+/// Aleph.js, so the Rust router and the Aleph server always agree on the matched route.
+pub(crate) fn exec_pathname(pattern: &str, pathname: &str) -> Option<HashMap<String, String>> {
+  let pattern_segments = split_path(pattern);
+  let segments = split_path(pathname);
+  let mut groups = HashMap::new();
+  for i in 0..pattern_segments.len().max(segments.len()) {
+    let (pattern_segment, segment) = (pattern_segments.get(i)?, segments.get(i)?);
+    if pattern_segment.starts_with(':') && pattern_segment.len() > 1 {
+      if pattern_segment.ends_with('+') && pattern_segment.len() > 2 && i == pattern_segments.len() - 1 {
+        let rest = segments[i..].iter().map(|s| decode(s)).collect::<Option<Vec<_>>>()?;
+        groups.insert(pattern_segment[1..pattern_segment.len() - 1].to_owned(), rest.join("/"));
+        break;
+      }
+      groups.insert(pattern_segment[1..].to_owned(), decode(segment)?);
+    } else if pattern_segment != segment {
+      return None;
+    }
+  }
+  Some(groups)
+}
+
+/// Matches the pathname with the route table in order, the first matched route wins. It's the `matchRoute` function
+/// of `aleph/plugins/rust`, e.g. to match the route table of the `routes!` macro in a native server.
+pub fn match_route<'a>(routes: impl IntoIterator<Item = &'a str>, pathname: &str) -> Option<RouteMatch> {
+  routes.into_iter().find_map(|pattern| {
+    exec_pathname(pattern, pathname).map(|params| RouteMatch {
+      pattern: pattern.to_owned(),
+      params,
+    })
+  })
+}
+
+/// The `splitPath` function of Aleph.js.
+fn split_path(path: &str) -> Vec<&str> {
+  let mut segments = Vec::new();
+  for segment in path.split(['/', '\\']) {
+    match segment {
+      "" | "." => {}
+      ".." => {
+        segments.pop();
+      }
+      segment => segments.push(segment),
+    }
+  }
+  segments
+}
+
+/// Decodes the segment like `decodeURIComponent`, returns `None` for the malformed one.
+fn decode(segment: &str) -> Option<String> {
+  percent_encoding::percent_decode_str(segment)
+    .decode_utf8()
+    .ok()
+    .map(Cow::into_owned)
+}
+
+/// The characters escaped by `encodeURIComponent`.
+const URI_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'_')
+  .remove(b'.')
+  .remove(b'!')
+  .remove(b'~')
+  .remove(b'*')
+  .remove(b'\'')
+  .remove(b'(')
+  .remove(b')');
+
+/// Formats the path of the URL pattern with the params, the rest param (`:path+`) keeps the slashes.
+pub(crate) fn format_pattern(pattern: &str, params: &HashMap<String, String>) -> String {
+  let path = split_path(pattern)
+    .into_iter()
+    .map(|segment| match segment.strip_prefix(':') {
+      Some(name) if name.len() > 1 && name.ends_with('+') => params
+        .get(&name[..name.len() - 1])
+        .map(|value| {
+          value
+            .split('/')
+            .map(|s| utf8_percent_encode(s, URI_COMPONENT).to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+        })
+        .unwrap_or_default(),
+      Some(name) if !name.is_empty() => params
+        .get(name)
+        .map(|value| utf8_percent_encode(value, URI_COMPONENT).to_string())
+        .unwrap_or_default(),
+      _ => segment.to_owned(),
+    })
+    .collect::<Vec<_>>()
+    .join("/");
+  format!("/{}", path)
+}
+
+/// Strips the prefix of the locale from the pathname case-insensitively, e.g. `/zh-CN/todos` to `/todos`. The Aleph
+/// server routes the prefixed paths without the prefix, see the `i18n` option of `aleph/plugins/rust`.
+pub(crate) fn strip_locale<'a>(pathname: &'a str, locale: &str) -> &'a str {
+  let segment = pathname[1.min(pathname.len())..].split('/').next().unwrap_or_default();
+  if locale.is_empty() || !segment.eq_ignore_ascii_case(locale) {
+    return pathname;
+  }
+  match &pathname[segment.len() + 1..] {
+    "" => "/",
+    rest => rest,
+  }
+}
+
+/// Returns the route matched by the server if the pathname is the url of the server-side rendering.
+pub(crate) fn server_route(pathname: &str) -> Option<Option<RouteMatch>> {
+  thread_local! {
+    static SERVER_ROUTE: Option<(String, Option<RouteMatch>)> = read_server_route();
+  }
+  SERVER_ROUTE.with(|server_route| match server_route {
+    Some((server_pathname, route)) if server_pathname == pathname => Some(route.clone()),
+    _ => None,
+  })
+}
+
+fn read_server_route() -> Option<(String, Option<RouteMatch>)> {
+  #[derive(Deserialize)]
+  struct ServerRoute {
+    url: String,
+    route: Option<RouteMatch>,
+  }
+
+  // there is no document in the native SSR
+  if cfg!(not(target_arch = "wasm32")) {
+    return None;
+  }
+  let script = web_sys::window()?.document()?.get_element_by_id("aleph-route")?;
+  let ServerRoute { url, route } = serde_json::from_str(&script.text_content()?).ok()?;
+  Some((web_sys::Url::new(&url).ok()?.pathname(), route))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn strip_the_locale() {
+    assert_eq!(strip_locale("/zh-CN/todos", "zh-CN"), "/todos");
+    assert_eq!(strip_locale("/zh-cn/todos/1", "zh-CN"), "/todos/1");
+    assert_eq!(strip_locale("/zh-CN", "zh-CN"), "/");
+    assert_eq!(strip_locale("/zh-CN/", "zh-CN"), "/");
+    assert_eq!(strip_locale("/zh-CNx/todos", "zh-CN"), "/zh-CNx/todos");
+    assert_eq!(strip_locale("/todos", "zh-CN"), "/todos");
+    assert_eq!(strip_locale("/todos", ""), "/todos");
+    assert_eq!(strip_locale("", "en"), "");
+  }
+}
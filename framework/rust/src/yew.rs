@@ -0,0 +1,1092 @@
+//! The [Yew](https://yew.rs) integration.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::rc::Rc;
+
+use ::yew::prelude::*;
+use ::yew::suspense::{Suspension, SuspensionResult};
+use ::yew::virtual_dom::AttrValue;
+use ::yew::{BaseComponent, LocalServerRenderer, Renderer};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+use yew_router::history::{BrowserHistory, History};
+use yew_router::prelude::*;
+
+use crate::{
+  events, form, panic, prefetch, router, routes, ContentType, CssModule, FormError, HtmlRewriterHandlers, I18n,
+  IslandHandle, PageError, Redirector, RouteMatch, SsrContext,
+};
+
+pub use aleph_macros::routes;
+
+/// The properties of the `Root` component.
+#[derive(Properties)]
+pub struct RootProps<C: BaseComponent> {
+  pub ctx: Option<SsrContext>,
+  pub props: C::Properties,
+}
+
+impl<C: BaseComponent> PartialEq for RootProps<C> {
+  fn eq(&self, other: &Self) -> bool {
+    self.ctx == other.ctx && self.props == other.props
+  }
+}
+
+/// The root component wraps the app with the SSR context, it must be used in both the server and the client to
+/// match the hydration.
+#[function_component]
+pub fn Root<C>(props: &RootProps<C>) -> Html
+where
+  C: BaseComponent,
+  C::Properties: Clone,
+{
+  let app_props = props.props.clone();
+  html! {
+    <ContextProvider<Option<SsrContext>> context={props.ctx.clone()}>
+      <C ..app_props />
+    </ContextProvider<Option<SsrContext>>>
+  }
+}
+
+/// Returns the SSR context of current request, or `None` in the browser.
+#[hook]
+pub fn use_ssr_context() -> Option<SsrContext> {
+  use_context::<Option<SsrContext>>().flatten()
+}
+
+/// Returns the nonce of the content-security-policy for the inline `<script>` and `<style>` tags.
+#[hook]
+pub fn use_nonce() -> Option<String> {
+  use_ssr_context().and_then(|ctx| ctx.nonce().map(ToOwned::to_owned))
+}
+
+/// Returns the messages of the locale detected by the Aleph server, see `I18n`.
+///
+/// ```ignore
+/// let i18n = use_i18n();
+/// html! { <h1>{i18n.t("index.title")}</h1> }
+/// ```
+#[hook]
+pub fn use_i18n() -> I18n {
+  match use_ssr_context() {
+    Some(ctx) => ctx.i18n().cloned().unwrap_or_default(),
+    None => I18n::from_document(),
+  }
+}
+
+/// Caches the rendered page for `ttl` seconds, see `SsrContext::cache`.
+///
+/// ```ignore
+/// use_page_cache(60, &["todos"]);
+/// ```
+#[hook]
+pub fn use_page_cache(ttl: u32, tags: &[&str]) {
+  if let Some(ctx) = use_ssr_context() {
+    ctx.cache(ttl, tags);
+  }
+}
+
+/// Returns the `Redirector` to redirect to another page, e.g. the page requires the login. In the server-side rendering
+/// the Aleph server responds the redirect instead of the page, and the `<Outlet>` of the layouts renders nothing after
+/// the redirect; in the browser it navigates through the router.
+///
+/// ```ignore
+/// let redirector = use_redirect();
+/// let user = use_loader("user", || async { fetch_user().await })?;
+/// if user.is_none() {
+///   redirector.redirect("/login", 303);
+///   return Ok(Html::default());
+/// }
+/// ```
+#[hook]
+pub fn use_redirect() -> Redirector {
+  Redirector::new(use_ssr_context())
+}
+
+/// Applies the CSS module imported by the `css_module!` macro, returns it for the class names.
+///
+/// ```ignore
+/// let styles = use_css_module(css_module!("src/components/header.module.css"));
+/// ```
+#[hook]
+pub fn use_css_module<C>(module: CssModule<C>) -> CssModule<C> {
+  module.apply(use_ssr_context().as_ref());
+  module
+}
+
+/// The content rendered by the `<Outlet/>`, it's provided by the `switch` function of the `routes!` macro.
+#[derive(Clone, PartialEq)]
+pub struct OutletContext(pub Html);
+
+/// Renders the nested page of a layout (`_app.rs` or `_layout.rs`) of the routes directory.
+///
+/// ```ignore
+/// #[function_component]
+/// pub fn Layout() -> Html {
+///   html! { <div class="dashboard"><Sidebar/><Outlet/></div> }
+/// }
+/// ```
+#[function_component]
+pub fn Outlet() -> Html {
+  // the nested page is not rendered if the layout redirects
+  if use_ssr_context().is_some_and(|ctx| ctx.redirected()) {
+    return Html::default();
+  }
+  match use_context::<OutletContext>() {
+    // the nested page may suspend on its own loader, without hiding the layout
+    Some(OutletContext(content)) => html! { <Suspense>{content}</Suspense> },
+    None => Html::default(),
+  }
+}
+
+/// Loads the data of a layout or a page, suspends the component until the data is loaded. The loader runs on the
+/// server for the first render, the data is embedded in the html for the hydration; after that, it runs in the browser
+/// when the `key` changes, e.g. navigating to another page.
+///
+/// ```ignore
+/// #[function_component]
+/// pub fn Layout() -> HtmlResult {
+///   let user = use_loader("dashboard/user", || async { fetch_user().await })?;
+///   Ok(html! { <div class="dashboard"><h2>{&user.name}</h2><Outlet/></div> })
+/// }
+/// ```
+#[hook]
+pub fn use_loader<T, F, Fut>(key: impl Into<String>, loader: F) -> SuspensionResult<Rc<T>>
+where
+  T: Serialize + DeserializeOwned + 'static,
+  F: FnOnce() -> Fut,
+  Fut: Future<Output = T> + 'static,
+{
+  let key = key.into();
+  let ctx = use_ssr_context();
+  let data = use_mut_ref(|| None::<(String, Rc<T>)>);
+  let pending = use_mut_ref(|| None::<(String, Suspension)>);
+
+  if let Some((data_key, data)) = &*data.borrow() {
+    if *data_key == key {
+      return Ok(data.clone());
+    }
+  }
+  if ctx.is_none() {
+    if let Some(embedded) = take_embedded_loader_data::<T>(&key) {
+      let embedded = Rc::new(embedded);
+      *data.borrow_mut() = Some((key, embedded.clone()));
+      return Ok(embedded);
+    }
+  }
+  if let Some((pending_key, suspension)) = &*pending.borrow() {
+    if *pending_key == key && !suspension.resumed() {
+      return Err(suspension.clone());
+    }
+  }
+
+  let (suspension, handle) = Suspension::new();
+  let future = loader();
+  *pending.borrow_mut() = Some((key.clone(), suspension.clone()));
+  ::yew::platform::spawn_local(async move {
+    let output = future.await;
+    if let Some(ctx) = ctx {
+      if let Ok(json) = serde_json::to_string(&output) {
+        ctx.embed_loader_data(&key, &json);
+      }
+    }
+    *data.borrow_mut() = Some((key, Rc::new(output)));
+    handle.resume();
+  });
+  Err(suspension)
+}
+
+/// Takes the data embedded by the `use_loader` on the server, it's used only once for the hydration.
+fn take_embedded_loader_data<T: DeserializeOwned>(key: &str) -> Option<T> {
+  let document = web_sys::window()?.document()?;
+  let scripts = document.query_selector_all("script[data-aleph-loader]").ok()?;
+  for i in 0..scripts.length() {
+    let script = scripts.item(i)?.dyn_into::<web_sys::Element>().ok()?;
+    if script.get_attribute("data-aleph-loader").as_deref() == Some(key) {
+      script.remove();
+      return serde_json::from_str(&script.text_content()?).ok();
+    }
+  }
+  None
+}
+
+/// Returns the data of the page loaded by the `data` of its route module, e.g. `routes/todos.ts`, suspends the
+/// component while the data of the next page is loading. The data is sent by the Aleph server for the first render,
+/// then it's fetched by the `?_data_` query after navigating, or taken from the prefetched payload of the `<Link>`.
+///
+/// ```ignore
+/// #[function_component]
+/// pub fn Page() -> HtmlResult {
+///   let todos = use_data::<Vec<Todo>>()?.unwrap_or_default();
+///   Ok(html! { <ul>{ for todos.iter().map(|todo| html! { <li>{&todo.title}</li> }) }</ul> })
+/// }
+/// ```
+#[hook]
+pub fn use_data<T>() -> SuspensionResult<Option<Rc<T>>>
+where
+  T: DeserializeOwned + 'static,
+{
+  // re-renders on history changes
+  let _ = use_location();
+  let ctx = use_ssr_context();
+  let report_error = use_error_boundary();
+  let data = use_mut_ref(|| None::<(String, Option<Rc<T>>)>);
+  let pending = use_mut_ref(|| None::<(String, Suspension)>);
+
+  if let Some(ctx) = ctx {
+    if let Some(error) = ctx.error() {
+      report_error.emit(error.clone());
+    }
+    return Ok(ctx.data().map(Rc::new));
+  }
+  let key = router::page_key();
+  if let Some((data_key, data)) = &*data.borrow() {
+    if *data_key == key {
+      return Ok(data.clone());
+    }
+  }
+  // the error of the server is rendered by the `ErrorBoundary`
+  if router::ssr_page_error(&key).is_some() {
+    *data.borrow_mut() = Some((key, None));
+    return Ok(None);
+  }
+  if let Some(ssr_data) = router::ssr_page_data(&key) {
+    let ssr_data = serde_json::from_value(ssr_data).ok().map(Rc::new);
+    *data.borrow_mut() = Some((key, ssr_data.clone()));
+    return Ok(ssr_data);
+  }
+  if let Some((pending_key, suspension)) = &*pending.borrow() {
+    if *pending_key == key && !suspension.resumed() {
+      return Err(suspension.clone());
+    }
+  }
+
+  let (suspension, handle) = Suspension::new();
+  *pending.borrow_mut() = Some((key.clone(), suspension.clone()));
+  ::yew::platform::spawn_local(async move {
+    let output = match prefetch::load_data(&key).await {
+      Ok(value) => js_sys::JSON::stringify(&value)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json.as_string()?).ok())
+        .map(Rc::new),
+      Err(error) => {
+        report_error.emit(error);
+        None
+      }
+    };
+    *data.borrow_mut() = Some((key, output));
+    handle.resume();
+  });
+  Err(suspension)
+}
+
+/// The reporter of the nearest `ErrorBoundary`.
+#[derive(Clone, PartialEq)]
+struct ErrorBoundaryContext(Callback<PageError>);
+
+/// Returns the callback that reports the error to the nearest `ErrorBoundary`, e.g. the error of a loader. In the
+/// server-side rendering the component is rendered before the boundary sees the error, so the component should render
+/// the `<ErrorPage>` itself. The error is logged to the console if there is no boundary.
+///
+/// ```ignore
+/// let report_error = use_error_boundary();
+/// report_error.emit(PageError::not_found());
+/// ```
+#[hook]
+pub fn use_error_boundary() -> Callback<PageError> {
+  let ctx = use_ssr_context();
+  match use_context::<ErrorBoundaryContext>() {
+    Some(ErrorBoundaryContext(report)) => report,
+    None => Callback::from(move |error: PageError| match &ctx {
+      Some(ctx) => ctx.report_error(&error),
+      None => web_sys::console::error_1(&error.to_string().into()),
+    }),
+  }
+}
+
+/// The properties of the `ErrorBoundary` component.
+#[derive(Properties, PartialEq)]
+pub struct ErrorBoundaryProps {
+  /// Renders the caught error, default is the `<ErrorPage>`.
+  #[prop_or_default]
+  pub fallback: Option<Callback<PageError, Html>>,
+  #[prop_or_default]
+  pub children: Children,
+}
+
+/// Catches the error thrown by the `data` of the route module and the errors reported by `use_error_boundary`, and
+/// renders the `fallback` instead of the children like the `ErrorBoundary` of the React runtime. In the server-side
+/// rendering the status of the response is set to the status of the error, and the error is passed to the `onError`
+/// of the Aleph server. The error is cleared after navigating to another page.
+///
+/// ```ignore
+/// html! { <><Header/><ErrorBoundary><Outlet/></ErrorBoundary></> }
+/// ```
+///
+/// # Panics
+///
+/// The panics of the components are not caught: a panic fails the server-side rendering, and in the browser it aborts
+/// the wasm module until the page is reloaded. Report a `PageError` with `use_error_boundary` instead of panicking,
+/// e.g. for an invalid param of the route.
+#[function_component]
+pub fn ErrorBoundary(props: &ErrorBoundaryProps) -> Html {
+  // re-renders on history changes
+  let _ = use_location();
+  let ctx = use_ssr_context();
+  let caught = use_state(|| None::<(String, PageError)>);
+  let context = {
+    let caught = caught.clone();
+    use_memo(
+      move |_| {
+        ErrorBoundaryContext(Callback::from(move |error| {
+          caught.set(Some((router::page_key(), error)))
+        }))
+      },
+      (),
+    )
+  };
+
+  let error = match &ctx {
+    Some(ctx) => ctx.error().cloned(),
+    None => {
+      let key = router::page_key();
+      match &*caught {
+        Some((caught_key, error)) if *caught_key == key => Some(error.clone()),
+        _ => router::ssr_page_error(&key),
+      }
+    }
+  };
+  match error {
+    Some(error) => match &props.fallback {
+      Some(fallback) => {
+        if let Some(ctx) = &ctx {
+          ctx.report_error(&error);
+        }
+        fallback.emit(error)
+      }
+      None => html! { <ErrorPage {error} /> },
+    },
+    None => html! {
+      <ContextProvider<ErrorBoundaryContext> context={(*context).clone()}>
+        { props.children.clone() }
+      </ContextProvider<ErrorBoundaryContext>>
+    },
+  }
+}
+
+/// The properties of the `ErrorPage` component.
+#[derive(Properties, PartialEq)]
+pub struct ErrorPageProps {
+  pub error: PageError,
+}
+
+/// Renders the error like the `Err` component of the React runtime, the error is reported to the Aleph server in the
+/// server-side rendering, see `SsrContext::report_error`.
+#[function_component]
+pub fn ErrorPage(props: &ErrorPageProps) -> Html {
+  let PageError { status, message } = &props.error;
+  if let Some(ctx) = use_ssr_context() {
+    ctx.report_error(&props.error);
+  }
+
+  html! {
+    <div class="aleph--error" style="margin:0;padding:1.5rem 2rem;color:red;font-size:18px">
+      <strong style="font-weight:600">{status}</strong>
+      <small style="opacity:0.5;padding:0 6px">{"-"}</small>
+      {message}
+    </div>
+  }
+}
+
+/// The properties of the `Form` component.
+#[derive(Properties, PartialEq)]
+pub struct FormProps {
+  /// The url that the form posts to, default is the current page.
+  #[prop_or_default]
+  pub action: Option<AttrValue>,
+  /// Use `multipart/form-data` to upload files, default is `application/x-www-form-urlencoded`.
+  #[prop_or_default]
+  pub enctype: Option<AttrValue>,
+  #[prop_or_default]
+  pub classes: Classes,
+  /// Resets the form after a successful submission.
+  #[prop_or(true)]
+  pub reset: bool,
+  /// Receives the JSON responded by the `mutation` of the route module, or the error.
+  #[prop_or_default]
+  pub onresult: Callback<Result<serde_json::Value, FormError>>,
+  #[prop_or_default]
+  pub children: Children,
+}
+
+/// The `<form>` that posts to the `mutation` of the route module, it works before the wasm is loaded: the browser
+/// submits the form and the Aleph server redirects back to the page after the mutation. Once the app is hydrated, the
+/// submission is sent by `fetch` and the result is passed to the `onresult` callback, the form is `aria-busy` until
+/// the mutation responds.
+///
+/// ```ignore
+/// html! {
+///   <Form onresult={onresult}>
+///     <input type="text" name="title" />
+///     <button name="intent" value="add">{"Add"}</button>
+///   </Form>
+/// }
+/// ```
+#[function_component]
+pub fn Form(props: &FormProps) -> Html {
+  let busy = use_state(|| false);
+  let onsubmit = {
+    let busy = busy.clone();
+    let reset = props.reset;
+    let onresult = props.onresult.clone();
+    Callback::from(move |e: SubmitEvent| {
+      if e.default_prevented() {
+        return;
+      }
+      e.prevent_default();
+      let form = match e.target_dyn_into::<web_sys::HtmlFormElement>() {
+        Some(form) if !*busy => form,
+        _ => return,
+      };
+      let submitter = e.submitter().and_then(|el| el.dyn_into::<web_sys::HtmlElement>().ok());
+      let busy = busy.clone();
+      let onresult = onresult.clone();
+      busy.set(true);
+      ::yew::platform::spawn_local(async move {
+        let result = form::submit(&form, submitter).await;
+        if reset && result.is_ok() {
+          form.reset();
+        }
+        busy.set(false);
+        onresult.emit(result);
+      });
+    })
+  };
+
+  html! {
+    <form
+      method="post"
+      action={props.action.clone()}
+      enctype={props.enctype.clone()}
+      class={props.classes.clone()}
+      aria-busy={busy.then_some("true")}
+      {onsubmit}
+    >
+      { props.children.clone() }
+    </form>
+  }
+}
+
+/// The properties of the `Switch` component.
+#[derive(Properties, PartialEq)]
+pub struct SwitchProps<R: Routable> {
+  /// Renders the page of the route, e.g. the `switch` function generated by the `routes!` macro.
+  pub render: Callback<R, Html>,
+}
+
+/// Renders the page of the current route like the `<Switch>` of yew-router, but the route is matched by the Aleph
+/// server in the server-side rendering, so the server and the client never disagree on the route.
+///
+/// ```ignore
+/// html! { <BrowserRouter><Switch<Route> render={switch} /></BrowserRouter> }
+/// ```
+#[function_component]
+pub fn Switch<R: Routable + 'static>(props: &SwitchProps<R>) -> Html {
+  let route = use_route::<R>();
+  let route = match use_ssr_context().and_then(|ctx| ctx.route().map(|route| route.cloned())) {
+    Some(matched) => matched
+      .and_then(|matched| from_match(&matched))
+      .or_else(R::not_found_route),
+    None => route,
+  };
+  route.map(|route| props.render.emit(route)).unwrap_or_default()
+}
+
+/// Recognizes the route with the route table of the `routes!` macro, used by its `Routable` implementation.
+///
+/// The first pattern of `R::routes()` matching the pathname wins, then the route is not found if the params can't be
+/// parsed. The route matched by the server is used for the hydration. The prefix of the locale detected by the server
+/// is stripped in the browser, e.g. `/zh-CN/todos` is recognized as `/todos` like the Aleph server does.
+pub fn recognize<R: Routable>(pathname: &str) -> Option<R> {
+  let pathname = routes::strip_locale(pathname, I18n::from_document().locale());
+  let matched = routes::server_route(pathname).unwrap_or_else(|| routes::match_route(R::routes(), pathname));
+  matched
+    .and_then(|matched| from_match(&matched))
+    .or_else(R::not_found_route)
+}
+
+/// Parses the params of a route into the `Params` of the page, used by the `routes!` macro.
+pub fn parse_params<P: DeserializeOwned>(params: &HashMap<&str, &str>) -> Option<P> {
+  serde_urlencoded::from_str(&serde_urlencoded::to_string(params).ok()?).ok()
+}
+
+/// Formats the path of a route with the `Params` of the page, used by the `routes!` macro.
+pub fn format_path<P: Serialize>(pattern: &str, params: &P) -> String {
+  let params = serde_urlencoded::to_string(params)
+    .ok()
+    .and_then(|query| serde_urlencoded::from_str(&query).ok())
+    .unwrap_or_default();
+  routes::format_pattern(pattern, &params)
+}
+
+/// Returns the route table for the Aleph server as JSON, used by the `routes!` macro.
+pub fn routes_manifest<R: Routable>() -> String {
+  serde_json::to_string(&R::routes()).unwrap()
+}
+
+fn from_match<R: Routable>(matched: &RouteMatch) -> Option<R> {
+  let params = matched
+    .params
+    .iter()
+    .map(|(name, value)| (name.as_str(), value.as_str()))
+    .collect();
+  R::from_path(&matched.pattern, &params)
+}
+
+/// The params of a dynamic route split into a chunk, the `routes!` macro doesn't compile the page module so the values
+/// are strings. The chunk crate receives them as the JSON props, e.g. `{"id": "1"}`.
+pub type ChunkParams = BTreeMap<String, String>;
+
+/// The properties of the `RouteChunk` component.
+#[derive(Properties, PartialEq)]
+pub struct RouteChunkProps {
+  /// The wasm-bindgen module of the chunk, e.g. `/pkg/todos/todos.js`.
+  pub module: AttrValue,
+  /// The name passed to the `ssr_island` and `hydrate_island` functions of the chunk.
+  pub name: AttrValue,
+  /// The props of the chunk as JSON.
+  #[prop_or(AttrValue::Static("null"))]
+  pub props: AttrValue,
+}
+
+/// The JSON output of the `ssr_island` function of a chunk.
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChunkOutput {
+  html: String,
+  #[serde(default)]
+  head_collection: Vec<String>,
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen(inline_js = "export function import_chunk(url, wasmURL) {
+  return import(url).then(async (mod) => {
+    await mod.default(wasmURL);
+    return mod;
+  });
+}")]
+extern "C" {
+  fn import_chunk(url: &str, wasm_url: &str) -> js_sys::Promise;
+}
+
+thread_local! {
+  // the initialized chunks in the browser
+  static CHUNKS: RefCell<HashMap<String, js_sys::Promise>> = RefCell::new(HashMap::new());
+}
+
+/// Renders a page split into its own wasm module, the route chunk is a crate that exports the `ssr_island` and
+/// `hydrate_island` functions like the islands of React pages, see `IslandHandle`. It's rendered by the `routes!` macro
+/// for the pages that declare the `CHUNK` const, the page file only declares it and is not compiled into the app
+/// crate, see the `chunks/about` crate of the `yew-app` example:
+///
+/// ```ignore
+/// // src/routes/todos/[id].rs
+/// pub const CHUNK: &str = "/pkg/todos/todos.js";
+/// ```
+///
+/// On the server, the chunk module must be loaded by the `chunks` option of the rust plugin, the html of the page is
+/// inserted by the `HtmlRewriter` and the chunk is preloaded with `<link rel="modulepreload">`. In the browser, the
+/// chunk is imported on demand then hydrates the server-rendered html, or renders the page after a navigation.
+#[function_component]
+pub fn RouteChunk(props: &RouteChunkProps) -> HtmlResult {
+  let ctx = use_ssr_context();
+  let root = use_node_ref();
+  let rendered = use_mut_ref(|| false);
+  let pending = use_mut_ref(|| None::<Suspension>);
+  // the html rendered on the server is moved out before the app is hydrated, the app never renders the children
+  let ssr_nodes = {
+    let name = props.name.clone();
+    let is_server = ctx.is_some();
+    use_mut_ref(move || if is_server { None } else { take_chunk_nodes(&name) })
+  };
+
+  if let Some(ctx) = ctx {
+    if !*rendered.borrow() {
+      if let Some(suspension) = &*pending.borrow() {
+        if !suspension.resumed() {
+          return Err(suspension.clone().into());
+        }
+      }
+      let (suspension, handle) = Suspension::new();
+      *pending.borrow_mut() = Some(suspension.clone());
+      let (module, name, chunk_props) = (props.module.clone(), props.name.clone(), props.props.clone());
+      ::yew::platform::spawn_local(async move {
+        match render_chunk(&ctx, &module, &name, &chunk_props).await {
+          Ok(output) => insert_chunk(&ctx, &module, &name, output),
+          Err(err) => render_chunk_error(&name, err),
+        }
+        *rendered.borrow_mut() = true;
+        handle.resume();
+      });
+      return Err(suspension.into());
+    }
+  }
+
+  {
+    let root = root.clone();
+    use_effect_with_deps(
+      move |(module, name, chunk_props): &(AttrValue, AttrValue, AttrValue)| {
+        let handle = Rc::new(RefCell::new(None::<JsValue>));
+        let disposed = Rc::new(RefCell::new(false));
+        if let Some(el) = root.cast::<web_sys::Element>() {
+          if let Some(nodes) = ssr_nodes.borrow_mut().take() {
+            let _ = el.append_child(&nodes);
+          }
+          let (module, name, chunk_props) = (module.to_string(), name.to_string(), chunk_props.to_string());
+          let (handle, disposed) = (handle.clone(), disposed.clone());
+          wasm_bindgen_futures::spawn_local(async move {
+            let mounted = match load_chunk(&module).await {
+              Ok(chunk) if !*disposed.borrow() => call(
+                &chunk,
+                "hydrate_island",
+                &[name.as_str().into(), el.into(), chunk_props.into()],
+              ),
+              Ok(_) => return,
+              Err(err) => Err(err),
+            };
+            match mounted {
+              Ok(mounted) => *handle.borrow_mut() = Some(mounted),
+              Err(err) => web_sys::console::error_2(
+                &format!("[aleph] Failed to hydrate the chunk \"{}\":", name).into(),
+                &err,
+              ),
+            }
+          });
+        }
+        move || {
+          *disposed.borrow_mut() = true;
+          if let Some(handle) = handle.borrow_mut().take() {
+            let _ = call(&handle, "destroy", &[]);
+            let _ = call(&handle, "free", &[]);
+          }
+        }
+      },
+      (props.module.clone(), props.name.clone(), props.props.clone()),
+    );
+  }
+
+  Ok(html! { <div ref={root} data-aleph-chunk={props.name.clone()} /> })
+}
+
+/// Serializes the params of a route for the `RouteChunk`, used by the `routes!` macro.
+pub fn chunk_props<P: Serialize>(params: &P) -> AttrValue {
+  AttrValue::from(serde_json::to_string(params).unwrap_or_else(|_| "null".to_owned()))
+}
+
+/// Registers the chunks of the routes for the prefetching of links, used by the `routes!` macro.
+pub fn register_route_chunks(chunks: &[(&str, &str)]) {
+  for (pattern, module) in chunks {
+    prefetch::register_route_chunk(pattern, module);
+  }
+}
+
+/// Renders the chunk with the module loaded by the rust plugin on the server.
+#[cfg(target_arch = "wasm32")]
+async fn render_chunk(ctx: &SsrContext, module: &str, name: &str, props: &str) -> Result<ChunkOutput, JsValue> {
+  let registry = js_sys::Reflect::get(&js_sys::global(), &"__ALEPH_RUST_ISLANDS".into())?;
+  let chunk = if registry.is_object() {
+    js_sys::Reflect::get(&registry, &chunk_key(module).into())?
+  } else {
+    JsValue::UNDEFINED
+  };
+  if chunk.is_undefined() {
+    return Err(JsValue::from_str(&format!(
+      "The chunk module \"{}\" is not loaded, please add it to the `chunks` option of the rust plugin",
+      module
+    )));
+  }
+  let island_ctx = serde_json::json!({
+    "url": ctx.url(),
+    "nonce": ctx.nonce(),
+    "deploymentId": ctx.deployment_id(),
+    // the panics of the chunk reject the render of the page
+    "renderId": ctx.render_id(),
+  })
+  .to_string();
+  let output = call(&chunk, "ssr_island", &[name.into(), island_ctx.into(), props.into()])?;
+  let output = wasm_bindgen_futures::JsFuture::from(js_sys::Promise::resolve(&output)).await?;
+  let output = output.as_string().unwrap_or_default();
+  serde_json::from_str(&output).map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+/// The chunks are rendered by the wasm modules in the JS runtime, the native SSR leaves them to the browser.
+#[cfg(not(target_arch = "wasm32"))]
+async fn render_chunk(_: &SsrContext, _: &str, _: &str, _: &str) -> Result<ChunkOutput, String> {
+  Err("the route chunks are rendered in the browser by the native SSR".to_owned())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn render_chunk_error(name: &str, err: JsValue) {
+  web_sys::console::error_2(
+    &format!("[aleph] Failed to render the chunk \"{}\":", name).into(),
+    &err,
+  );
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn render_chunk_error(name: &str, err: String) {
+  eprintln!("[aleph] Failed to render the chunk \"{}\": {}", name, err);
+}
+
+/// Inserts the html of the chunk into its root element and preloads the chunk for the hydration.
+fn insert_chunk(ctx: &SsrContext, module: &str, name: &str, output: ChunkOutput) {
+  let ChunkOutput { html, head_collection } = output;
+  let selector = format!(
+    "div[data-aleph-chunk=\"{}\"]",
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+  );
+  ctx.html_rewriter().on(
+    selector,
+    HtmlRewriterHandlers::new().element(move |el| {
+      el.set_inner_content(&html, ContentType::Html);
+      el.set_attribute("data-aleph-ssr", "");
+    }),
+  );
+  for html in head_collection {
+    ctx.append_head(html);
+  }
+  // the same urls as the `load_chunk` function, versioned with the deployment id
+  let (module, wasm) = chunk_urls(module);
+  let q = ctx.deployment_id().map(|v| format!("?v={}", v)).unwrap_or_default();
+  ctx.append_head(format!(
+    "<link rel=\"modulepreload\" href=\"{}{}\" data-module-id=\"{}\">",
+    module, q, module
+  ));
+  ctx.append_head(format!(
+    "<link rel=\"preload\" href=\"{}{}\" as=\"fetch\" type=\"application/wasm\" crossorigin data-module-id=\"{}\">",
+    wasm, q, wasm
+  ));
+}
+
+/// Takes the html rendered on the server out of the root element of the chunk, once for the hydration.
+fn take_chunk_nodes(name: &str) -> Option<web_sys::DocumentFragment> {
+  let document = web_sys::window()?.document()?;
+  let roots = document
+    .query_selector_all("div[data-aleph-chunk][data-aleph-ssr]")
+    .ok()?;
+  for i in 0..roots.length() {
+    let root = roots.item(i)?.dyn_into::<web_sys::Element>().ok()?;
+    if root.get_attribute("data-aleph-chunk").as_deref() == Some(name) {
+      root.remove_attribute("data-aleph-ssr").ok()?;
+      let nodes = document.create_document_fragment();
+      while let Some(child) = root.first_child() {
+        nodes.append_child(&child).ok()?;
+      }
+      return Some(nodes);
+    }
+  }
+  None
+}
+
+/// Imports the chunk module and initializes it with the `*_bg.wasm` file next to it, once per module.
+async fn load_chunk(module: &str) -> Result<JsValue, JsValue> {
+  let promise = CHUNKS.with(|chunks| {
+    chunks
+      .borrow_mut()
+      .entry(module.to_owned())
+      .or_insert_with(|| {
+        let (module, wasm) = chunk_urls(module);
+        import_chunk(&prefetch::versioned_url(&module), &prefetch::versioned_url(&wasm))
+      })
+      .clone()
+  });
+  wasm_bindgen_futures::JsFuture::from(promise).await
+}
+
+/// Returns the key of the chunk module, the same as the registry of the rust plugin.
+fn chunk_key(module: &str) -> String {
+  format!("/{}", module.trim_start_matches("./").trim_start_matches('/'))
+}
+
+/// Returns the urls of the js glue and the `*_bg.wasm` file of the chunk module.
+fn chunk_urls(module: &str) -> (String, String) {
+  let module = chunk_key(module);
+  let wasm = format!("{}_bg.wasm", module.trim_end_matches(".js"));
+  (module, wasm)
+}
+
+fn call(target: &JsValue, method: &str, args: &[JsValue]) -> Result<JsValue, JsValue> {
+  let func: js_sys::Function = js_sys::Reflect::get(target, &method.into())?.dyn_into()?;
+  func.apply(target, &args.iter().collect())
+}
+
+/// The properties of the `Link` component.
+#[derive(Properties, Clone, PartialEq)]
+pub struct LinkProps<R: Routable> {
+  /// The route to link to.
+  pub to: R,
+  /// Replaces the current history entry instead of pushing a new one.
+  #[prop_or_default]
+  pub replace: bool,
+  /// Prefetches the page once the link enters the viewport, otherwise the page is prefetched on hover.
+  #[prop_or_default]
+  pub prefetch: bool,
+  #[prop_or_default]
+  pub classes: Classes,
+  /// The `aria-current` attribute, default is `page` for the links of the app.
+  #[prop_or_default]
+  pub aria_current: Option<AttrValue>,
+  #[prop_or_default]
+  pub children: Children,
+}
+
+/// The `<Link>` component to link between pages, it prefetches the data and the wasm chunks of the page on hover.
+///
+/// ```ignore
+/// html! { <Link<Route> to={Route::Todos} prefetch=true>{"Todos"}</Link<Route>> }
+/// ```
+#[function_component]
+pub fn Link<R: Routable + 'static>(props: &LinkProps<R>) -> Html {
+  let navigator = use_navigator().expect_throw("<Link> must be used in a router");
+  let href = AttrValue::from(match navigator.basename() {
+    Some(base) => format!("{}{}", base, props.to.to_path()),
+    None => props.to.to_path(),
+  });
+  let aria_current = props
+    .aria_current
+    .clone()
+    .or_else(|| href.starts_with('/').then_some(AttrValue::Static("page")));
+  let anchor = use_node_ref();
+  let timer = use_mut_ref(|| None::<i32>);
+
+  {
+    let anchor = anchor.clone();
+    let timer = timer.clone();
+    use_effect_with_deps(
+      move |prefetch_in_viewport| {
+        let el = anchor.cast::<web_sys::Element>();
+        if let (true, Some(el)) = (*prefetch_in_viewport, &el) {
+          prefetch::observe(el);
+        }
+        move || {
+          if let Some(el) = el {
+            prefetch::unobserve(&el);
+          }
+          if let Some(timer) = timer.take() {
+            prefetch::cancel_hover(timer);
+          }
+        }
+      },
+      props.prefetch,
+    );
+  }
+
+  let onmouseenter = {
+    let href = href.clone();
+    let timer = timer.clone();
+    Callback::from(move |e: MouseEvent| {
+      if !e.default_prevented() && timer.borrow().is_none() {
+        *timer.borrow_mut() = prefetch::prefetch_on_hover(href.to_string());
+      }
+    })
+  };
+  let onmouseleave = Callback::from(move |_: MouseEvent| {
+    if let Some(timer) = timer.take() {
+      prefetch::cancel_hover(timer);
+    }
+  });
+  let onclick = {
+    let to = props.to.clone();
+    let replace = props.replace;
+    Callback::from(move |e: MouseEvent| {
+      if e.default_prevented() || is_modified_event(&e) {
+        return;
+      }
+      e.prevent_default();
+      if replace {
+        navigator.replace(&to);
+      } else {
+        navigator.push(&to);
+      }
+    })
+  };
+
+  html! {
+    <a
+      class={props.classes.clone()}
+      {href}
+      aria-current={aria_current}
+      ref={anchor}
+      {onclick}
+      {onmouseenter}
+      {onmouseleave}
+    >
+      { props.children.clone() }
+    </a>
+  }
+}
+
+/// The properties of the `NavLink` component.
+#[derive(Properties, Clone, PartialEq)]
+pub struct NavLinkProps<R: Routable> {
+  /// The route to link to.
+  pub to: R,
+  /// Marks the link active only if the current location matches the route exactly, otherwise the nested routes of
+  /// the route activate the link as well.
+  #[prop_or_default]
+  pub exact: bool,
+  /// The classes added to the link when it's active.
+  #[prop_or_default]
+  pub active_class: Classes,
+  #[prop_or_default]
+  pub replace: bool,
+  #[prop_or_default]
+  pub prefetch: bool,
+  #[prop_or_default]
+  pub classes: Classes,
+  #[prop_or_default]
+  pub children: Children,
+}
+
+/// A special version of the `<Link>` that adds the `active_class` when it matches the current location.
+///
+/// ```ignore
+/// html! { <NavLink<Route> to={Route::Todos} active_class="active">{"Todos"}</NavLink<Route>> }
+/// ```
+#[function_component]
+pub fn NavLink<R: Routable + 'static>(props: &NavLinkProps<R>) -> Html {
+  // re-renders on history changes
+  let location = use_location().expect_throw("<NavLink> must be used in a router");
+  let navigator = use_navigator().expect_throw("<NavLink> must be used in a router");
+  let href = match navigator.basename() {
+    Some(base) => format!("{}{}", base, props.to.to_path()),
+    None => props.to.to_path(),
+  };
+  let active = router::is_active(&href, location.path(), location.query_str(), props.exact);
+  let mut classes = props.classes.clone();
+  if active {
+    classes.push(props.active_class.clone());
+  }
+
+  html! {
+    <Link<R> to={props.to.clone()} replace={props.replace} prefetch={props.prefetch} {classes}>
+      { props.children.clone() }
+    </Link<R>>
+  }
+}
+
+/// Checks if the click should be handled by the browser, e.g. opening the link in a new tab.
+fn is_modified_event(e: &MouseEvent) -> bool {
+  let target = e
+    .current_target()
+    .and_then(|el| el.dyn_into::<web_sys::Element>().ok())
+    .and_then(|el| el.get_attribute("target"));
+  matches!(target, Some(target) if !target.is_empty() && target != "_self")
+    || e.meta_key()
+    || e.ctrl_key()
+    || e.shift_key()
+    || e.alt_key()
+    || e.button() == 1
+}
+
+/// Renders the app with the SSR context sent by the Aleph server.
+pub async fn render<C>(ctx: String) -> Result<JsValue, JsValue>
+where
+  C: BaseComponent,
+  C::Properties: Clone + Default,
+{
+  panic::set_hook();
+  render_html::<C>(ctx)
+    .await
+    .map(|output| JsValue::from_str(&output))
+    .map_err(|err| JsValue::from_str(&err))
+}
+
+/// Renders the app like the `render` function but returns the JSON output as a string, it's used by the native SSR,
+/// see `export_native_ssr!`.
+pub async fn render_html<C>(ctx: String) -> Result<String, String>
+where
+  C: BaseComponent,
+  C::Properties: Clone + Default,
+{
+  let ctx = SsrContext::from_json(&ctx).map_err(|err| err.to_string())?;
+  let html = panic::Render::new(
+    ctx.render_id(),
+    LocalServerRenderer::<Root<C>>::with_props(RootProps {
+      ctx: Some(ctx.clone()),
+      props: C::Properties::default(),
+    })
+    .render(),
+  )
+  .await;
+  Ok(ctx.finish(html))
+}
+
+thread_local! {
+  // the `HistoryListener` of gloo, it's not exported by yew-router
+  static HISTORY_LISTENER: RefCell<Option<Box<dyn Any>>> = const { RefCell::new(None) };
+}
+
+/// Hydrates the app rendered by the `render` function, the `BrowserRouter` of the app is connected to the event bus
+/// of Aleph.js.
+pub fn hydrate<C>()
+where
+  C: BaseComponent,
+  C::Properties: Clone + Default,
+{
+  panic::set_hook();
+  // connect before the history is created, the `popstate` listener of the bus must run first
+  events::connect();
+  let listener = BrowserHistory::new().listen(events::notify_navigation);
+  HISTORY_LISTENER.with(|cell| cell.replace(Some(Box::new(listener))));
+  Renderer::<Root<C>>::with_props(RootProps {
+    ctx: None,
+    props: C::Properties::default(),
+  })
+  .hydrate();
+  // the scheduler of yew hydrates the app in a microtask
+  wasm_bindgen_futures::spawn_local(async { events::notify_hydrated() });
+}
+
+/// Renders the component as an island of a React page, with the SSR context and the JSON props sent by the
+/// `<RustIsland>` component.
+pub async fn render_island<C>(ctx: String, props: String) -> Result<JsValue, JsValue>
+where
+  C: BaseComponent,
+  C::Properties: Clone + DeserializeOwned,
+{
+  panic::set_hook();
+  let ctx = SsrContext::from_json(&ctx).map_err(|err| JsValue::from_str(&err.to_string()))?;
+  let props = serde_json::from_str(&props).map_err(|err| JsValue::from_str(&err.to_string()))?;
+  let html = panic::Render::new(
+    ctx.render_id(),
+    LocalServerRenderer::<Root<C>>::with_props(RootProps {
+      ctx: Some(ctx.clone()),
+      props,
+    })
+    .render(),
+  )
+  .await;
+  Ok(JsValue::from_str(&ctx.finish_island(html)))
+}
+
+/// Hydrates the island rendered by the `render_island` function in the root element, or renders it if the root is
+/// empty, e.g. the island is mounted after a client-side navigation.
+pub fn hydrate_island<C>(root: web_sys::Element, props: String) -> Result<IslandHandle, JsValue>
+where
+  C: BaseComponent,
+  C::Properties: Clone + DeserializeOwned,
+{
+  panic::set_hook();
+  let props = serde_json::from_str(&props).map_err(|err| JsValue::from_str(&err.to_string()))?;
+  let renderer = Renderer::<Root<C>>::with_root_and_props(root.clone(), RootProps { ctx: None, props });
+  let app = if root.has_child_nodes() {
+    renderer.hydrate()
+  } else {
+    renderer.render()
+  };
+  Ok(IslandHandle::new(move || app.destroy()))
+}
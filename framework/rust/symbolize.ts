@@ -0,0 +1,584 @@
+// The symbolication of the wasm frames of the Rust apps, e.g. `wasm://wasm/0011aabb:wasm-function[345]:0x2d4f1` to
+// `src/routes/todos.rs:42:5`, with the DWARF line table of the dev build. It's shared by the server, which symbolizes
+// the logged errors, and the panic overlay in the browser.
+
+/** The panic of the Rust app, passed to the `__ALEPH_RUST_PANIC_HOOK` by the panic hook of the `aleph` crate. */
+export type RustPanic = {
+  /** The panic message with its location, e.g. "panicked at src/routes/todos.rs:42:5:\nboom". */
+  message: string;
+  /** The stack of the JS error created by the hook, with the wasm frames of the panic. */
+  stack: string;
+  /** The memory of the wasm instance that panicked, the instance traps after the hook. */
+  memory?: WebAssembly.Memory;
+  /** The id of the render sent in the ssr input, it's missing if the panic is not in a render. */
+  renderId?: number;
+};
+
+export type SourceLocation = {
+  file: string;
+  line: number;
+  /** The column of the location, `0` if it's unknown. */
+  column: number;
+};
+
+/** The line table of the `.debug_line` section sorted by address, the rows without a file end a sequence. */
+type LineTable = {
+  addresses: Uint32Array;
+  files: Int32Array;
+  lines: Uint32Array;
+  columns: Uint32Array;
+  paths: string[];
+};
+
+// the frames of V8 (`wasm://wasm/<hash>:wasm-function[1]:0x2a` or the url of the wasm file) and Firefox
+const wasmFramePattern = /(?:wasm:\/\/wasm\/[^\s()]*|[^\s()@]*\.wasm[^\s()@]*?):wasm-function\[(\d+)\]:0x([0-9a-f]+)/gi;
+
+const utf8Dec = new TextDecoder();
+
+/** The debug info of a wasm module, e.g. built by wasm-pack with the `dwarf-debug-info` option in dev mode. */
+export class WasmSymbols {
+  #codeStart: number;
+  #importedFunctions: number;
+  #functions: [start: number, end: number][];
+  #sections: Record<string, Uint8Array>;
+  #table?: LineTable;
+
+  private constructor(
+    codeStart: number,
+    importedFunctions: number,
+    functions: [number, number][],
+    sections: Record<string, Uint8Array>,
+  ) {
+    this.#codeStart = codeStart;
+    this.#importedFunctions = importedFunctions;
+    this.#functions = functions;
+    this.#sections = sections;
+  }
+
+  /**
+   * Reads the sections of the wasm module, returns `null` if it has no `.debug_line` section, e.g. a release build.
+   * The line table is parsed on the first lookup.
+   */
+  static parse(wasm: Uint8Array): WasmSymbols | null {
+    if (wasm.length < 8 || wasm[0] !== 0 || wasm[1] !== 0x61 || wasm[2] !== 0x73 || wasm[3] !== 0x6d) {
+      return null;
+    }
+    let codeStart = -1;
+    let importedFunctions = 0;
+    const functions: [number, number][] = [];
+    const sections: Record<string, Uint8Array> = {};
+    let offset = 8;
+    while (offset < wasm.length) {
+      const id = wasm[offset++];
+      const [size, sizeLen] = readVarUint32(wasm, offset);
+      offset += sizeLen;
+      const end = offset + size;
+      if (id === 0) {
+        const [nameLen, nameLenLen] = readVarUint32(wasm, offset);
+        const nameStart = offset + nameLenLen;
+        const name = utf8Dec.decode(wasm.subarray(nameStart, nameStart + nameLen));
+        if (name.startsWith(".debug_")) {
+          sections[name] = wasm.subarray(nameStart + nameLen, end);
+        }
+      } else if (id === 2) {
+        importedFunctions = countImportedFunctions(wasm, offset);
+      } else if (id === 10) {
+        // the addresses of the DWARF info are the offsets in the content of the code section
+        codeStart = offset;
+        const [count, countLen] = readVarUint32(wasm, offset);
+        let p = offset + countLen;
+        for (let i = 0; i < count; i++) {
+          const [bodySize, bodySizeLen] = readVarUint32(wasm, p);
+          p += bodySizeLen;
+          functions.push([p, p + bodySize]);
+          p += bodySize;
+        }
+      }
+      offset = end;
+    }
+    if (codeStart === -1 || !sections[".debug_line"]) {
+      return null;
+    }
+    return new WasmSymbols(codeStart, importedFunctions, functions, sections);
+  }
+
+  /** Returns the source location of the frame, the offset is the byte offset in the module printed in the stack. */
+  lookup(functionIndex: number, offset: number): SourceLocation | null {
+    const body = this.#functions[functionIndex - this.#importedFunctions];
+    if (!body || offset < body[0] || offset >= body[1]) {
+      return null;
+    }
+    const table = this.#table ??= parseLineTable(this.#sections);
+    const address = offset - this.#codeStart;
+    // the last row at or before the address
+    let lo = 0;
+    let hi = table.addresses.length - 1;
+    let index = -1;
+    while (lo <= hi) {
+      const mid = (lo + hi) >>> 1;
+      if (table.addresses[mid] <= address) {
+        index = mid;
+        lo = mid + 1;
+      } else {
+        hi = mid - 1;
+      }
+    }
+    if (index === -1 || table.files[index] === -1) {
+      return null;
+    }
+    return { file: table.paths[table.files[index]], line: table.lines[index], column: table.columns[index] };
+  }
+}
+
+/**
+ * Replaces the wasm frames of the stack with their source locations, the frames not found in the debug info are kept.
+ * The paths in the `rootDir` are printed relative to it, and the ones of the cargo registry start with the crate.
+ */
+export function symbolizeStack(stack: string, symbols: WasmSymbols[], rootDir?: string): string {
+  if (symbols.length === 0) {
+    return stack;
+  }
+  return stack.replace(wasmFramePattern, (frame, index: string, offset: string) => {
+    for (const s of symbols) {
+      const location = s.lookup(Number(index), parseInt(offset, 16));
+      if (location) {
+        const { file, line, column } = location;
+        return `${formatPath(file, rootDir)}:${line}${column > 0 ? ":" + column : ""}`;
+      }
+    }
+    return frame;
+  });
+}
+
+function formatPath(file: string, rootDir?: string): string {
+  const root = rootDir?.replace(/\/+$/, "");
+  if (root && file.startsWith(root + "/")) {
+    return file.slice(root.length + 1);
+  }
+  // e.g. `~/.cargo/registry/src/github.com-1ecc6299db9ec823/yew-0.20.0/src/html/component/mod.rs`
+  const registry = file.match(/\/\.cargo\/registry\/src\/[^/]+\/(.+)$/);
+  if (registry) {
+    return registry[1];
+  }
+  // the std library, e.g. `/rustc/<commit>/library/core/src/panicking.rs`
+  const std = file.match(/^\/rustc\/[0-9a-f]+\/(.+)$/);
+  if (std) {
+    return std[1];
+  }
+  return file;
+}
+
+/** Parses the line programs of the `.debug_line` section, DWARF 2 to 5. */
+function parseLineTable(sections: Record<string, Uint8Array>): LineTable {
+  const data = sections[".debug_line"];
+  const r = new Reader(data);
+  const paths = { list: [] as string[], ids: new Map<string, number>() };
+  const rows: { address: number; file: number; line: number; column: number }[] = [];
+  while (r.offset < data.length) {
+    let unitLength = r.u32();
+    let offsetSize = 4;
+    if (unitLength === 0xffffffff) {
+      unitLength = r.u64();
+      offsetSize = 8;
+    }
+    const unitEnd = r.offset + unitLength;
+    try {
+      parseLineProgram(r, unitEnd, offsetSize, sections, paths, rows);
+    } catch {
+      // an unsupported form of the unit, skip it
+    }
+    r.offset = unitEnd;
+  }
+  // the end of a sequence goes before the start of the next one at the same address
+  rows.sort((a, b) => a.address - b.address || (a.file === -1 ? -1 : 0) - (b.file === -1 ? -1 : 0));
+  return {
+    addresses: Uint32Array.from(rows, (row) => row.address),
+    files: Int32Array.from(rows, (row) => row.file),
+    lines: Uint32Array.from(rows, (row) => row.line),
+    columns: Uint32Array.from(rows, (row) => row.column),
+    paths: paths.list,
+  };
+}
+
+function parseLineProgram(
+  r: Reader,
+  unitEnd: number,
+  offsetSize: number,
+  sections: Record<string, Uint8Array>,
+  paths: { list: string[]; ids: Map<string, number> },
+  rows: { address: number; file: number; line: number; column: number }[],
+) {
+  const version = r.u16();
+  if (version < 2 || version > 5) {
+    return;
+  }
+  if (version >= 5) {
+    // the address size and the segment selector size
+    r.offset += 2;
+  }
+  const headerLength = offsetSize === 8 ? r.u64() : r.u32();
+  const programStart = r.offset + headerLength;
+  const minInstructionLength = r.u8();
+  if (version >= 4) {
+    // the max operations per instruction of VLIW
+    r.u8();
+  }
+  // the default `is_stmt`
+  r.u8();
+  const lineBase = r.i8();
+  const lineRange = r.u8();
+  const opcodeBase = r.u8();
+  const opcodeLengths = [0];
+  for (let i = 1; i < opcodeBase; i++) {
+    opcodeLengths.push(r.u8());
+  }
+
+  const dirs: string[] = [];
+  const files: { name: string; dir: number }[] = [];
+  if (version >= 5) {
+    const readString = (form: number): string => {
+      switch (form) {
+        case 0x08: // DW_FORM_string
+          return r.cstr();
+        case 0x1f: // DW_FORM_line_strp
+          return cstrAt(sections[".debug_line_str"], offsetSize === 8 ? r.u64() : r.u32());
+        case 0x0e: // DW_FORM_strp
+          return cstrAt(sections[".debug_str"], offsetSize === 8 ? r.u64() : r.u32());
+        default:
+          throw new Error(`unsupported form ${form}`);
+      }
+    };
+    const readEntries = () => {
+      const formats: [type: number, form: number][] = [];
+      const formatCount = r.u8();
+      for (let i = 0; i < formatCount; i++) {
+        formats.push([r.uleb(), r.uleb()]);
+      }
+      const entries: { name: string; dir: number }[] = [];
+      const count = r.uleb();
+      for (let i = 0; i < count; i++) {
+        const entry = { name: "", dir: 0 };
+        for (const [type, form] of formats) {
+          if (type === 1) { // DW_LNCT_path
+            entry.name = readString(form);
+          } else if (type === 2) { // DW_LNCT_directory_index
+            entry.dir = form === 0x0b ? r.u8() : form === 0x05 ? r.u16() : r.uleb();
+          } else {
+            r.skipForm(form, offsetSize);
+          }
+        }
+        entries.push(entry);
+      }
+      return entries;
+    };
+    dirs.push(...readEntries().map((entry) => entry.name));
+    files.push(...readEntries());
+  } else {
+    // the directory 0 is the compilation directory, the paths in it are relative
+    dirs.push("");
+    for (let dir = r.cstr(); dir !== ""; dir = r.cstr()) {
+      dirs.push(dir);
+    }
+    // the files start at 1
+    files.push({ name: "", dir: 0 });
+    for (let name = r.cstr(); name !== ""; name = r.cstr()) {
+      const dir = r.uleb();
+      // the modification time and the length
+      r.uleb();
+      r.uleb();
+      files.push({ name, dir });
+    }
+  }
+
+  // the files of the unit in the paths of the table, the paths in the compilation directory are relative to it
+  const compDir = version >= 5 ? dirs[0] : "";
+  const fileIds = new Map<number, number>();
+  const fileId = (index: number): number => {
+    let id = fileIds.get(index);
+    if (id === undefined) {
+      const file = files[index];
+      let path = file ? joinPath(dirs[file.dir] ?? "", file.name) : "<unknown>";
+      if (compDir && path.startsWith(compDir + "/")) {
+        path = path.slice(compDir.length + 1);
+      }
+      id = paths.ids.get(path);
+      if (id === undefined) {
+        id = paths.list.push(path) - 1;
+        paths.ids.set(path, id);
+      }
+      fileIds.set(index, id);
+    }
+    return id;
+  };
+
+  r.offset = programStart;
+  let sequence: typeof rows = [];
+  let address = 0;
+  let file = 1;
+  let line = 1;
+  let column = 0;
+  const reset = () => {
+    address = 0;
+    file = 1;
+    line = 1;
+    column = 0;
+  };
+  const emitRow = () => sequence.push({ address, file: fileId(file), line, column });
+  while (r.offset < unitEnd) {
+    const opcode = r.u8();
+    if (opcode >= opcodeBase) {
+      // special opcode
+      const adjusted = opcode - opcodeBase;
+      address += Math.floor(adjusted / lineRange) * minInstructionLength;
+      line += lineBase + (adjusted % lineRange);
+      emitRow();
+      continue;
+    }
+    switch (opcode) {
+      case 0: { // extended opcode
+        const len = r.uleb();
+        const end = r.offset + len;
+        const sub = r.u8();
+        if (sub === 1) { // DW_LNE_end_sequence
+          sequence.push({ address, file: -1, line: 0, column: 0 });
+          // the sequences of the functions removed by the linker start at the tombstone address
+          const start = sequence[0].address;
+          if (start !== 0 && start < 0xfffffff0) {
+            rows.push(...sequence);
+          }
+          sequence = [];
+          reset();
+        } else if (sub === 2) { // DW_LNE_set_address
+          address = len - 1 === 8 ? r.u64() : r.u32();
+        } else if (sub === 3) { // DW_LNE_define_file
+          const name = r.cstr();
+          const dir = r.uleb();
+          files.push({ name, dir });
+        }
+        r.offset = end;
+        break;
+      }
+      case 1: // DW_LNS_copy
+        emitRow();
+        break;
+      case 2: // DW_LNS_advance_pc
+        address += r.uleb() * minInstructionLength;
+        break;
+      case 3: // DW_LNS_advance_line
+        line += r.sleb();
+        break;
+      case 4: // DW_LNS_set_file
+        file = r.uleb();
+        break;
+      case 5: // DW_LNS_set_column
+        column = r.uleb();
+        break;
+      case 8: // DW_LNS_const_add_pc
+        address += Math.floor((255 - opcodeBase) / lineRange) * minInstructionLength;
+        break;
+      case 9: // DW_LNS_fixed_advance_pc
+        address += r.u16();
+        break;
+      default:
+        // the opcodes without the operands used here, e.g. `DW_LNS_negate_stmt`
+        for (let i = 0; i < opcodeLengths[opcode]; i++) {
+          r.uleb();
+        }
+    }
+  }
+}
+
+function joinPath(dir: string, name: string): string {
+  if (name.startsWith("/") || /^[a-zA-Z]:[\\/]/.test(name) || dir === "") {
+    return name;
+  }
+  return dir.replace(/[\\/]+$/, "") + "/" + name;
+}
+
+function cstrAt(section: Uint8Array | undefined, offset: number): string {
+  if (!section) {
+    throw new Error("missing string section");
+  }
+  const end = section.indexOf(0, offset);
+  return utf8Dec.decode(section.subarray(offset, end === -1 ? section.length : end));
+}
+
+/** Returns the count of the imported functions, the function indexes of the stacks include them. */
+function countImportedFunctions(wasm: Uint8Array, offset: number): number {
+  const r = new Reader(wasm, offset);
+  const count = r.uleb();
+  let functions = 0;
+  for (let i = 0; i < count; i++) {
+    // the module name and the field name
+    r.skipBytes();
+    r.skipBytes();
+    const kind = r.u8();
+    switch (kind) {
+      case 0: // function
+        r.uleb();
+        functions++;
+        break;
+      case 1: // table
+        r.u8();
+        r.skipLimits();
+        break;
+      case 2: // memory
+        r.skipLimits();
+        break;
+      case 3: // global
+        r.u8();
+        r.u8();
+        break;
+      case 4: // tag
+        r.u8();
+        r.uleb();
+        break;
+      default:
+        return functions;
+    }
+  }
+  return functions;
+}
+
+class Reader {
+  data: Uint8Array;
+  offset: number;
+  #view: DataView;
+
+  constructor(data: Uint8Array, offset = 0) {
+    this.data = data;
+    this.offset = offset;
+    this.#view = new DataView(data.buffer, data.byteOffset, data.byteLength);
+  }
+
+  u8(): number {
+    return this.data[this.offset++];
+  }
+
+  i8(): number {
+    return this.#view.getInt8(this.offset++);
+  }
+
+  u16(): number {
+    const value = this.#view.getUint16(this.offset, true);
+    this.offset += 2;
+    return value;
+  }
+
+  u32(): number {
+    const value = this.#view.getUint32(this.offset, true);
+    this.offset += 4;
+    return value;
+  }
+
+  u64(): number {
+    const value = Number(this.#view.getBigUint64(this.offset, true));
+    this.offset += 8;
+    return value;
+  }
+
+  uleb(): number {
+    let value = 0;
+    let scale = 1;
+    while (true) {
+      const byte = this.data[this.offset++];
+      value += (byte & 0x7f) * scale;
+      scale *= 128;
+      if ((byte & 0x80) === 0) {
+        return value;
+      }
+    }
+  }
+
+  sleb(): number {
+    let value = 0;
+    let scale = 1;
+    while (true) {
+      const byte = this.data[this.offset++];
+      value += (byte & 0x7f) * scale;
+      scale *= 128;
+      if ((byte & 0x80) === 0) {
+        return byte & 0x40 ? value - scale : value;
+      }
+    }
+  }
+
+  cstr(): string {
+    const end = this.data.indexOf(0, this.offset);
+    const value = utf8Dec.decode(this.data.subarray(this.offset, end));
+    this.offset = end + 1;
+    return value;
+  }
+
+  /** Skips the bytes prefixed with their length, e.g. a name of the wasm imports. */
+  skipBytes() {
+    const len = this.uleb();
+    this.offset += len;
+  }
+
+  /** Skips the limits of a table or a memory, the max is present with the flag `0x01`. */
+  skipLimits() {
+    const flags = this.u8();
+    this.uleb();
+    if (flags & 0x01) {
+      this.uleb();
+    }
+  }
+
+  /** Skips the value of a DWARF form, e.g. the timestamp or the MD5 of a file entry. */
+  skipForm(form: number, offsetSize: number) {
+    switch (form) {
+      case 0x0b: // DW_FORM_data1
+      case 0x11: // DW_FORM_flag
+        this.offset += 1;
+        break;
+      case 0x05: // DW_FORM_data2
+        this.offset += 2;
+        break;
+      case 0x06: // DW_FORM_data4
+        this.offset += 4;
+        break;
+      case 0x07: // DW_FORM_data8
+        this.offset += 8;
+        break;
+      case 0x1e: // DW_FORM_data16
+        this.offset += 16;
+        break;
+      case 0x0f: // DW_FORM_udata
+        this.uleb();
+        break;
+      case 0x0d: // DW_FORM_sdata
+        this.sleb();
+        break;
+      case 0x08: // DW_FORM_string
+        this.cstr();
+        break;
+      case 0x0e: // DW_FORM_strp
+      case 0x1f: // DW_FORM_line_strp
+      case 0x17: // DW_FORM_sec_offset
+        this.offset += offsetSize;
+        break;
+      case 0x09: // DW_FORM_block
+        this.skipBytes();
+        break;
+      default:
+        throw new Error(`unsupported form ${form}`);
+    }
+  }
+}
+
+/** Reads the LEB128 unsigned integer, e.g. the sizes of the wasm sections. */
+export function readVarUint32(bytes: Uint8Array, offset: number): [value: number, length: number] {
+  let value = 0;
+  let shift = 0;
+  let length = 0;
+  while (true) {
+    const byte = bytes[offset + length++];
+    value |= (byte & 0x7f) << shift;
+    if ((byte & 0x80) === 0 || length === 5) {
+      break;
+    }
+    shift += 7;
+  }
+  return [value >>> 0, length];
+}
@@ -0,0 +1,156 @@
+import { path } from "../../server/deps.ts";
+import { MockServer } from "../../server/mock.ts";
+import type { AlephConfig, Middleware, RouterInit, SessionOptions } from "../../server/types.ts";
+import { PageCache } from "./cache.ts";
+import RustPlugin, { buildCSSModules, type PluginOptions, type SSROutput } from "./plugin.ts";
+
+export type BuildCrateOptions = {
+  /** The directory of the crate, e.g. "./examples/yew-app". */
+  cwd: string;
+  /** The directory of the built wasm-bindgen module, relative to the crate, default is "target/aleph/test-pkg". */
+  outDir?: string;
+  /** The name of the built module, default is the name of the crate. */
+  outName?: string;
+  /** The cargo features of the build, e.g. `["ssr"]` for the server module of a Leptos app. */
+  features?: string[];
+  /**
+   * Builds without the network: cargo is run with `--offline`, so the dependencies must be vendored or in the cargo
+   * cache already, and wasm-pack uses the `wasm-bindgen` CLI in the `PATH` instead of downloading it. Default is
+   * `true` if the `CARGO_NET_OFFLINE` env is set to `true`.
+   */
+  offline?: boolean;
+  /** The wasm-pack binary, default is the `WASM_PACK` env or "wasm-pack", e.g. the one of a vendored toolchain. */
+  wasmPack?: string;
+};
+
+// the builds of the crates, each crate is built once per test process
+const builds = new Map<string, Promise<void>>();
+
+/**
+ * Builds the crate with `wasm-pack build --target web --dev` for the SSR, the CSS modules are built before for the
+ * `css_module!` macro. The build output is not optimized and doesn't replace the `pkg` directory of the app.
+ */
+export function buildCrate(options: BuildCrateOptions): Promise<void> {
+  const { outDir = "target/aleph/test-pkg", outName, features } = options;
+  const cwd = path.resolve(options.cwd);
+  const offline = options.offline ?? Deno.env.get("CARGO_NET_OFFLINE") === "true";
+  const cmd = [options.wasmPack ?? Deno.env.get("WASM_PACK") ?? "wasm-pack", "build", "--target", "web", "--dev"];
+  cmd.push("--out-dir", outDir);
+  if (outName) {
+    cmd.push("--out-name", outName);
+  }
+  if (offline) {
+    cmd.push("--mode", "no-install");
+  }
+  // the args after `--` are passed to cargo
+  cmd.push("--");
+  if (features?.length) {
+    cmd.push("--features", features.join(","));
+  }
+  if (offline) {
+    cmd.push("--offline");
+  }
+  const key = cmd.join(" ") + "@" + cwd;
+  let build = builds.get(key);
+  if (!build) {
+    build = (async () => {
+      await buildCSSModules({ cwd, minify: false });
+      const p = Deno.run({ cmd, cwd, stdout: "null", stderr: "piped" });
+      const [status, stderr] = await Promise.all([p.status(), p.stderrOutput()]);
+      p.close();
+      if (!status.success) {
+        throw new Error(`Failed to build the crate ${cwd}:\n${new TextDecoder().decode(stderr)}`);
+      }
+    })();
+    builds.set(key, build);
+  }
+  return build;
+}
+
+/**
+ * Checks if the wasm-pack is installed, the Rust integration tests are ignored without it. It throws instead if the
+ * `REQUIRE_WASM_PACK` env is `true`, so the tests of the CI job building the Rust apps fail rather than being skipped.
+ */
+export async function hasWasmPack(wasmPack = Deno.env.get("WASM_PACK") ?? "wasm-pack"): Promise<boolean> {
+  let installed = false;
+  try {
+    const p = Deno.run({ cmd: [wasmPack, "--version"], stdout: "null", stderr: "null" });
+    installed = (await p.status()).success;
+    p.close();
+  } catch (_e) {
+    // not found
+  }
+  if (!installed && Deno.env.get("REQUIRE_WASM_PACK") === "true") {
+    throw new Error(`The wasm-pack "${wasmPack}" is required by the \`REQUIRE_WASM_PACK\` env, but it's not installed`);
+  }
+  return installed;
+}
+
+export type MockRustServerOptions = Omit<PluginOptions, "pageCache"> & {
+  /** The directory of the app, e.g. "./examples/yew-app". */
+  appDir: string;
+  /**
+   * Builds the crate before loading the `module`, the `module` is replaced with the built one, and the `clientModule`
+   * is still the `module` for the preload links, set it to `false` to load the `module` as is.
+   */
+  build?: Omit<BuildCrateOptions, "cwd"> | false;
+  /**
+   * Enables the page cache, the pages are cached in memory instead of the `.ssr-cache` directory, and they are
+   * invalidated by the `invalidatePageCache` function. Default is `false`.
+   */
+  pageCache?: boolean;
+  origin?: string;
+  router?: RouterInit;
+  middlewares?: Middleware[];
+  session?: SessionOptions;
+};
+
+/**
+ * Creates the `MockServer` that renders the pages with the wasm module of the Rust app, the crate is built once for
+ * all the tests. The page cache is disabled unless the `pageCache` option is set.
+ *
+ * @example
+ * ```ts
+ * import { assertEquals, assertStringIncludes } from "std/testing/asserts.ts";
+ * import { createMockServer } from "aleph/framework/rust/testing.ts";
+ *
+ * Deno.test("todos", async () => {
+ *   const api = await createMockServer({ appDir: "./examples/yew-app", module: "./pkg/yew_app.js" });
+ *   const res = await api.fetch("/todos");
+ *   assertEquals(res.status, 200);
+ *   assertStringIncludes(await res.text(), "<h1><span>Todos</span>");
+ * });
+ * ```
+ */
+export async function createMockServer(options: MockRustServerOptions): Promise<MockServer> {
+  const { appDir, build = {}, pageCache = false, origin, router, middlewares, session, ...pluginOptions } = options;
+  const plugin: PluginOptions = { ...pluginOptions, pageCache };
+  if (pageCache && !Reflect.has(globalThis, "__ALEPH_RUST_PAGE_CACHE")) {
+    // the plugin reuses the shared cache instead of loading the one of the output dir
+    Reflect.set(globalThis, "__ALEPH_RUST_PAGE_CACHE", new PageCache<SSROutput>());
+  }
+  if (build) {
+    const outDir = build.outDir ?? "target/aleph/test-pkg";
+    const outName = build.outName ?? path.basename(options.module, ".js");
+    await buildCrate({ ...build, cwd: appDir, outDir, outName });
+    plugin.module = "./" + path.join(outDir, outName + ".js");
+    plugin.clientModule = options.clientModule ?? options.module;
+  }
+
+  // the plugin loads the modules and the message catalogs from the app directory
+  const config: AlephConfig = {};
+  const appDirKey = "__ALEPH_APP_DIR";
+  const globalAppDir = Reflect.get(globalThis, appDirKey);
+  Reflect.set(globalThis, appDirKey, path.resolve(appDir));
+  try {
+    await RustPlugin(plugin).setup(config, { isDev: false });
+  } finally {
+    if (globalAppDir === undefined) {
+      Reflect.deleteProperty(globalThis, appDirKey);
+    } else {
+      Reflect.set(globalThis, appDirKey, globalAppDir);
+    }
+  }
+
+  return new MockServer({ appDir, origin, router, middlewares, session, ssr: config.ssr });
+}
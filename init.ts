@@ -88,10 +88,12 @@ export default async function init(nameArg?: string, options?: Options) {
   const isRsApp = rsApps.includes(template);
 
   let alephPkgUri: string;
+  let alephCrate: string;
   if (import.meta.url.startsWith("file://")) {
     const src = `examples/${withUnocss ? "with-unocss/" : ""}${template}-app/`;
     await copyDir(src, name);
     alephPkgUri = "..";
+    alephCrate = `path = "../framework/rust"`;
   } else {
     console.log(
       `${dim("↓")} Downloading template(${
@@ -140,6 +142,16 @@ export default async function init(nameArg?: string, options?: Options) {
       }
     }
     alephPkgUri = `https://deno.land/x/aleph@${VERSION}`;
+    alephCrate = `git = "https://github.com/${repo}", tag = "${VERSION}"`;
+  }
+
+  if (isRsApp) {
+    // the template depends on the `aleph` crate in the repository
+    const cargoToml = await Deno.readTextFile(join(appDir, "Cargo.toml"));
+    await Deno.writeTextFile(
+      join(appDir, "Cargo.toml"),
+      cargoToml.replace(/path = "(\.\.\/)+framework\/rust"/, alephCrate),
+    );
   }
 
   const serverCode = await Deno.readTextFile(join(appDir, "server.ts"));
@@ -209,6 +221,12 @@ export default async function init(nameArg?: string, options?: Options) {
       "@unocss/preset-uno": `https://esm.sh/v${ESM_VERSION}/@unocss/preset-uno@0.50.6`,
     });
   }
+  if (isRsApp) {
+    Object.assign(importMap.imports, {
+      "aleph/rust": `${alephPkgUri}/framework/rust/mod.ts`,
+      "aleph/plugins/rust": `${alephPkgUri}/framework/rust/plugin.ts`,
+    });
+  }
   switch (template) {
     case "react-mdx":
       Object.assign(importMap.imports, {
@@ -0,0 +1,299 @@
+import { cleanPath } from "../shared/util.ts";
+import { path } from "./deps.ts";
+import { getAppDir, getFiles, regJsxFile } from "./helpers.ts";
+import log from "./log.ts";
+import type { AlephConfig, AtomicCSSEngine } from "./types.ts";
+
+const inputCache = new Map<string, { mtime: number; input: string }>();
+
+// the files of the app directories, they're listed once and invalidated by the watcher in development
+const filesCache = new Map<string, Promise<string[]>>();
+
+/** Builds the atomic CSS with all the files matched by `atomicCSS.test`, the result is cached in production. */
+export async function buildAtomicCSS(config: AlephConfig, isDev?: boolean): Promise<string | undefined> {
+  const { atomicCSS, build } = config;
+  if (!atomicCSS) {
+    return undefined;
+  }
+  const cached = Reflect.get(globalThis, "__ALEPH_ATOMICCSS_BUILD");
+  if (cached) {
+    return cached;
+  }
+  const t = performance.now();
+  const { test = regJsxFile } = atomicCSS;
+  const appDir = getAppDir();
+  let files = filesCache.get(appDir);
+  if (!files) {
+    files = getFiles(appDir);
+    filesCache.set(appDir, files);
+    // don't cache the failed listing
+    files.catch(() => filesCache.delete(appDir));
+  }
+  const outputDir = "." + cleanPath(build?.outputDir ?? "./output");
+  const inputSources = await Promise.all(
+    (await files).filter((name) => test.test(name) && !name.startsWith(outputDir)).map((name) =>
+      getAtomicCSSInput(path.join(appDir, name))
+    ),
+  );
+  if (inputSources.length > 0) {
+    const ret = await atomicCSS.generate(inputSources.join("\n"), {
+      minify: !isDev,
+    });
+    if (ret.matched.size > 0) {
+      if (!isDev) {
+        Reflect.set(globalThis, "__ALEPH_ATOMICCSS_BUILD", ret.css);
+      }
+      log.debug(
+        `Atomic CSS generated in ${(performance.now() - t).toFixed(2)}ms`,
+        atomicCSS.name && atomicCSS.version ? `(Powered by ${atomicCSS.name}@${atomicCSS.version})` : "",
+      );
+      return ret.css;
+    }
+  }
+}
+
+/**
+ * Invalidates the cached files of the atomic CSS input, the watcher calls it when a file is created or removed. The
+ * modified files are checked by their mtime.
+ */
+export function invalidateAtomicCSSFiles(filename?: string): void {
+  filesCache.clear();
+  if (filename) {
+    inputCache.delete(filename);
+  }
+}
+
+/** Generates the atomic CSS for the given class names. */
+export async function generateAtomicCSS(
+  atomicCSS: AtomicCSSEngine,
+  classes: string[],
+  isDev?: boolean,
+): Promise<string | undefined> {
+  if (classes.length > 0) {
+    const ret = await atomicCSS.generate(classes.join(" "), { minify: !isDev });
+    if (ret.matched.size > 0) {
+      return ret.css;
+    }
+  }
+}
+
+/**
+ * Returns the input of the atomic CSS generator for the given file, the result is cached until the file is modified.
+ * For Rust source files, only the class names used in the `html!`/`view!` macros are returned.
+ */
+export async function getAtomicCSSInput(filename: string): Promise<string> {
+  // follows the symlinks, so a symlinked source is invalidated when its target is modified
+  const mtime = (await Deno.stat(filename)).mtime?.getTime();
+  const cached = inputCache.get(filename);
+  if (mtime && cached?.mtime === mtime) {
+    return cached.input;
+  }
+  const content = await Deno.readTextFile(filename);
+  const input = filename.endsWith(".rs") ? extractRustClasses(content).join(" ") : content;
+  if (mtime) {
+    inputCache.set(filename, { mtime, input });
+  }
+  return input;
+}
+
+/** Extracts the class names of the `class` attributes in the html, e.g. the classes of the `index.html` shell. */
+export function extractHTMLClasses(html: string): string[] {
+  const classes = new Set<string>();
+  for (const [, , value] of html.matchAll(/\sclass\s*=\s*(["'])(.*?)\1/gs)) {
+    value.split(/\s+/).forEach((name) => name && classes.add(name));
+  }
+  return [...classes];
+}
+
+/**
+ * Extracts the class names from a Rust source file:
+ * - `class="..."`/`classes={...}` attributes and `class:name=` directives in the `html!`/`view!` macros
+ * - arguments of the `classes!(...)` macro
+ * - `format!("...")` literals in the class attributes, the class names with placeholders are ignored
+ */
+export function extractRustClasses(source: string): string[] {
+  const code = stripComments(source);
+  const classes = new Set<string>();
+  const add = (literal: string) => {
+    literal.replace(/\S*\{[^{}]*\}\S*/g, " ").split(/\s+/).forEach((name) => name && classes.add(name));
+  };
+
+  for (const match of code.matchAll(/\bclasses!\s*[({[]/g)) {
+    const start = match.index! + match[0].length - 1;
+    readStringLiterals(code.slice(start, findClosing(code, start))).forEach(add);
+  }
+
+  for (const match of code.matchAll(/\b(?:html|view)!\s*[({[]/g)) {
+    const start = match.index! + match[0].length - 1;
+    const body = code.slice(start + 1, findClosing(code, start) - 1);
+    for (const attr of body.matchAll(/(?<![\w-])(class(?:es)?\s*=|class:([\w-]+))\s*/g)) {
+      if (attr[2]) {
+        classes.add(attr[2]);
+        continue;
+      }
+      const i = attr.index! + attr[0].length;
+      const ch = body.charAt(i);
+      if (ch === '"' || (ch === "r" && /^r#*"/.test(body.slice(i, i + 8)))) {
+        add(readString(body, i)[0]);
+      } else if (ch === "{" || ch === "(") {
+        readStringLiterals(body.slice(i, findClosing(body, i))).forEach(add);
+      } else {
+        // an expression without braces, e.g. `class=move || if active { "active" } else { "" }`
+        readStringLiterals(body.slice(i, findExpressionEnd(body, i))).forEach(add);
+      }
+    }
+  }
+
+  return [...classes];
+}
+
+/** Replaces the comments with spaces, keeps the string literals. */
+function stripComments(code: string): string {
+  let output = "";
+  let i = 0;
+  while (i < code.length) {
+    const ch = code.charAt(i);
+    if (ch === "/" && code.charAt(i + 1) === "/") {
+      const end = code.indexOf("\n", i);
+      i = end === -1 ? code.length : end;
+      output += " ";
+    } else if (ch === "/" && code.charAt(i + 1) === "*") {
+      // block comments can be nested in Rust
+      let depth = 0;
+      while (i < code.length) {
+        if (code.startsWith("/*", i)) {
+          depth++;
+          i += 2;
+        } else if (code.startsWith("*/", i)) {
+          depth--;
+          i += 2;
+          if (depth === 0) {
+            break;
+          }
+        } else {
+          i++;
+        }
+      }
+      output += " ";
+    } else if (isStringStart(code, i)) {
+      const [, end] = readString(code, i);
+      output += code.slice(i, end);
+      i = end;
+    } else {
+      const end = skipCharLiteral(code, i);
+      output += code.slice(i, end);
+      i = end;
+    }
+  }
+  return output;
+}
+
+function isStringStart(code: string, i: number): boolean {
+  const ch = code.charAt(i);
+  if (ch === '"') {
+    return true;
+  }
+  // raw strings like `r"..."` or `r#"..."#`, but not identifiers end with `r`
+  return ch === "r" && /^r#*"/.test(code.slice(i, i + 8)) && !/\w/.test(code.charAt(i - 1));
+}
+
+/** Reads the string literal at the given position, returns the value and the end position. */
+function readString(code: string, i: number): [value: string, end: number] {
+  if (code.charAt(i) === "r") {
+    const hashes = /^r(#*)"/.exec(code.slice(i))![1];
+    const start = i + hashes.length + 2;
+    const end = code.indexOf('"' + hashes, start);
+    return end === -1 ? [code.slice(start), code.length] : [code.slice(start, end), end + hashes.length + 1];
+  }
+  let value = "";
+  let j = i + 1;
+  while (j < code.length) {
+    const ch = code.charAt(j);
+    if (ch === "\\") {
+      const next = code.charAt(j + 1);
+      value += next === "n" || next === "t" || next === "\n" ? " " : next;
+      j += 2;
+    } else if (ch === '"') {
+      return [value, j + 1];
+    } else {
+      value += ch;
+      j++;
+    }
+  }
+  return [value, j];
+}
+
+/** Reads all the string literals in the code. */
+function readStringLiterals(code: string): string[] {
+  const literals: string[] = [];
+  let i = 0;
+  while (i < code.length) {
+    if (isStringStart(code, i)) {
+      const [value, end] = readString(code, i);
+      literals.push(value);
+      i = end;
+    } else {
+      i = skipCharLiteral(code, i);
+    }
+  }
+  return literals;
+}
+
+/** Skips the char literal like `'{'`, returns the next position. */
+function skipCharLiteral(code: string, i: number): number {
+  if (code.charAt(i) === "'") {
+    const m = /^'(?:\\.|\\u\{[0-9a-fA-F]+\}|[^\\'])'/.exec(code.slice(i, i + 12));
+    if (m) {
+      return i + m[0].length;
+    }
+  }
+  return i + 1;
+}
+
+/** Finds the position after the closing bracket of the bracket at the given position. */
+function findClosing(code: string, start: number): number {
+  let depth = 0;
+  let i = start;
+  while (i < code.length) {
+    const ch = code.charAt(i);
+    if (isStringStart(code, i)) {
+      i = readString(code, i)[1];
+      continue;
+    }
+    if (ch === "'") {
+      i = skipCharLiteral(code, i);
+      continue;
+    }
+    if (ch === "(" || ch === "{" || ch === "[") {
+      depth++;
+    } else if (ch === ")" || ch === "}" || ch === "]") {
+      depth--;
+      if (depth === 0) {
+        return i + 1;
+      }
+    }
+    i++;
+  }
+  return code.length;
+}
+
+/** Finds the end of an attribute value expression without braces. */
+function findExpressionEnd(code: string, start: number): number {
+  let i = start;
+  while (i < code.length) {
+    const ch = code.charAt(i);
+    if (ch === "(" || ch === "{" || ch === "[") {
+      i = findClosing(code, i);
+    } else if (isStringStart(code, i)) {
+      i = readString(code, i)[1];
+    } else if (ch === ">" || (ch === "/" && code.charAt(i + 1) === ">")) {
+      return i;
+    } else if (/\s/.test(ch) && /^\s+[\w:-]+=(?!=)/.test(code.slice(i, i + 64))) {
+      // the next attribute
+      return i;
+    } else {
+      i = skipCharLiteral(code, i);
+    }
+  }
+  return code.length;
+}
@@ -1,4 +1,5 @@
-import { cleanPath, isLikelyHttpURL, splitBy, trimPrefix } from "../shared/util.ts";
+import type { RouteMeta, Router } from "../framework/core/router.ts";
+import { cleanPath, isLikelyHttpURL, isPlainObject, splitBy, trimPrefix, trimSuffix, utf8Dec } from "../shared/util.ts";
 import { colors, path, TransformCSSOptions } from "./deps.ts";
 import { ensureDir, esbuild, transformCSS } from "./deps.ts";
 import depGraph from "./graph.ts";
@@ -10,18 +11,49 @@ import {
   getAlephConfig,
   getAlephPkgUri,
   getAppDir,
+  getDeploymentId,
   globalIt,
   isNpmPkg,
+  precompressedEncodings,
   restoreUrl,
   toLocalPath,
 } from "./helpers.ts";
-import { parseHtmlLinks } from "./html.ts";
+import { loadIndexHtml, parseHtmlLinks } from "./html.ts";
 import log from "./log.ts";
+import { getContentType } from "./media_type.ts";
 import { initRouter } from "./router.ts";
-import type { ConnInfo } from "./types.ts";
+import transformer from "./transformer.ts";
+import type { ConnInfo, Plugin } from "./types.ts";
+
+/** The version of the build manifest, it's bumped for the breaking changes of the format. */
+export const buildManifestVersion = 1;
+
+/**
+ * The manifest of the build output written to `<outputDir>/manifest.json`, it's a stable JSON format for the servers
+ * not written in JS, e.g. the `aleph-axum` crate serves the build output with it.
+ */
+export type BuildManifest = {
+  version: number;
+  deploymentId?: string;
+  /** The `index.html` fixed for SSR, the `<ssr-body>` element is replaced with the rendered html. */
+  indexHtml: string | null;
+  /** The router manifest injected into the html as `<script id="router-manifest">`. */
+  router: { routes: RouteMeta[]; prefix: string };
+  /** The files to serve keyed by the pathname. */
+  assets: Record<string, BuildAsset>;
+};
+
+export type BuildAsset = {
+  /** The file path relative to the app dir, e.g. "output/main.js". */
+  file: string;
+  contentType: string;
+  /** The precompressed files next to the file in the order of preference, e.g. `["br", "gzip"]`. */
+  encodings?: string[];
+};
 
 export async function build(
   serverHandler: (req: Request, connInfo: ConnInfo) => Promise<Response> | Response,
+  plugins: Plugin[] = [],
 ) {
   const start = performance.now();
   const appDir = getAppDir();
@@ -49,7 +81,8 @@ export async function build(
   const routeFiles: string[] = [];
   const ssgOptions = options.ssg === true ? {} : options.ssg ?? false;
   const ssgPaths: string[] = [];
-  const { routes } = await globalIt("__ALEPH_ROUTER", () => initRouter(appDir, config.router));
+  const router: Router = await globalIt("__ALEPH_ROUTER", () => initRouter(appDir, config.router));
+  const { routes } = router;
 
   routes.forEach(([_, { filename }]) => {
     routeFiles.push(filename);
@@ -293,12 +326,103 @@ export async function build(
   esbuild.stop();
   memFS.clear();
 
+  // run the build hooks of the plugins, e.g. optimizing the wasm modules of Rust apps
+  for (const plugin of plugins) {
+    if (plugin.build) {
+      await plugin.build(outputDir);
+    }
+  }
+
+  // write the build manifest for the servers not written in JS
+  const ssrRoot = isPlainObject(config.ssr) ? config.ssr.root : undefined;
+  const manifest = await createBuildManifest(appDir, outputDir, router, ssrRoot);
+  await Deno.writeTextFile(path.join(outputDir, "manifest.json"), JSON.stringify(manifest, undefined, 2));
+
   log.info(`${colors.bold(routeFiles.length.toString())} routes found`);
   log.info(`${colors.bold(clientModules.size.toString())} client modules built`);
   log.info(`Done in ${(performance.now() - start).toFixed(2)}ms`);
   Deno.exit(0);
 }
 
+/** Creates the manifest of the build output, see `BuildManifest`. */
+export async function createBuildManifest(
+  appDir: string,
+  outputDir: string,
+  router: Router,
+  ssrRoot?: string,
+): Promise<BuildManifest> {
+  const indexHtml = await loadIndexHtml(path.join(appDir, "index.html"), { ssr: { root: ssrRoot } });
+  const assets: Record<string, BuildAsset> = {};
+  const addAsset = (pathname: string, file: string, contentType: string, files: Set<string>) => {
+    const encodings = precompressedEncodings.filter(([_, ext]) => files.has(file + ext)).map(([name]) => name);
+    assets[pathname] = {
+      file: path.relative(appDir, file).split(path.SEP).join("/"),
+      contentType,
+      encodings: encodings.length > 0 ? encodings : undefined,
+    };
+  };
+
+  // the static files served from the app dir
+  const outputDirName = path.relative(appDir, outputDir).split(path.SEP)[0];
+  const staticFiles = await walkFiles(appDir, (name) => name !== outputDirName && name !== "target");
+  for (const file of staticFiles) {
+    const pathname = "/" + path.relative(appDir, file).split(path.SEP).join("/");
+    const contentType = getContentType(pathname);
+    if (contentType !== "application/octet-stream" && !transformer.test(pathname) && pathname !== "/index.html") {
+      addAsset(pathname, file, contentType, staticFiles);
+    }
+  }
+
+  // the client modules and the optimized wasm modules of the build, they override the files of the app dir
+  const outputFiles = await walkFiles(outputDir);
+  for (const file of outputFiles) {
+    let pathname = "/" + path.relative(outputDir, file).split(path.SEP).join("/");
+    const isPrecompressed = precompressedEncodings.some(([_, ext]) =>
+      file.endsWith(ext) && outputFiles.has(trimSuffix(file, ext))
+    );
+    if (pathname === "/manifest.json" || isPrecompressed) {
+      continue;
+    }
+    if (pathname.startsWith("/-/") && pathname.endsWith(".js") && isNpmPkg(restoreUrl(pathname.slice(0, -3)))) {
+      pathname = pathname.slice(0, -3);
+    }
+    let contentType = getContentType(file);
+    if (contentType === "application/javascript" || contentType === "text/css") {
+      contentType += "; charset=utf-8";
+    }
+    addAsset(pathname, file, contentType, outputFiles);
+  }
+
+  return {
+    version: buildManifestVersion,
+    deploymentId: getDeploymentId(),
+    indexHtml: indexHtml ? utf8Dec.decode(indexHtml) : null,
+    router: { routes: router.routes.map(([_, meta]) => meta), prefix: router.prefix },
+    assets,
+  };
+}
+
+/**
+ * Returns all the files in the dir, the hidden entries and the `node_modules` are skipped, and the `filter` skips the
+ * entries of the top level.
+ */
+async function walkFiles(dir: string, filter?: (name: string) => boolean, files = new Set<string>()) {
+  if (await existsDir(dir)) {
+    for await (const entry of Deno.readDir(dir)) {
+      if (entry.name.startsWith(".") || entry.name === "node_modules" || (filter && !filter(entry.name))) {
+        continue;
+      }
+      const filename = path.join(dir, entry.name);
+      if (entry.isDirectory) {
+        await walkFiles(filename, undefined, files);
+      } else if (entry.isFile) {
+        files.add(filename);
+      }
+    }
+  }
+  return files;
+}
+
 /** Bundle the css using `parcel-css` with `nesting` and `customMedia` draft support. */
 export async function bundleCSS(
   specifier: string,
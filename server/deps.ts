@@ -17,6 +17,7 @@ export * from "https://deno.land/x/aleph_compiler@0.9.3/mod.ts";
 export * from "https://deno.land/x/aleph_compiler@0.9.3/types.ts";
 export { default as initLolHtml, HTMLRewriter } from "https://deno.land/x/lol_html@0.0.6/mod.ts";
 export { default as lolHtmlWasm } from "https://deno.land/x/lol_html@0.0.6/wasm.js";
+export { compress as brotliCompress } from "https://deno.land/x/brotli@0.1.7/mod.ts";
 
 // npm
 export { default as mitt, type Emitter } from "https://esm.sh/mitt@3.0.0?pin=v110";
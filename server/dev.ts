@@ -1,6 +1,7 @@
 /** @format */
 
 import { isFilledString } from "../shared/util.ts";
+import { invalidateAtomicCSSFiles } from "./atomic_css.ts";
 import { colors, Emitter, ensureDir, mitt, parseCliArgs, parseDeps, path } from "./deps.ts";
 import depGraph from "./graph.ts";
 import { builtinModuleExts, findFile, getAlephConfig, getImportMap, watchFs } from "./helpers.ts";
@@ -73,6 +74,9 @@ export function watch(appDir: string, onRouterChange?: () => void) {
       } else {
         depGraph.update(specifier);
       }
+      if (kind !== "modify") {
+        invalidateAtomicCSSFiles(pathname);
+      }
       if (kind === "modify") {
         watchFsEmitters.forEach((e) => {
           e.emit("modify", { specifier });
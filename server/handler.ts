@@ -1,10 +1,13 @@
 import { generateErrorHtml, TransformError } from "../framework/core/error.ts";
 import type { Router } from "../framework/core/router.ts";
 import { isPlainObject, trimSuffix } from "../shared/util.ts";
+import { buildAtomicCSS } from "./atomic_css.ts";
 import { createContext, NEXT } from "./context.ts";
 import { handleHMR } from "./dev.ts";
 import { HTMLRewriter, path } from "./deps.ts";
+import type { BuildManifest } from "./build.ts";
 import {
+  acceptsEncoding,
   existsDir,
   existsFile,
   fetchCode,
@@ -15,6 +18,7 @@ import {
   getJSXConfig,
   globalIt,
   isNpmPkg,
+  precompressedEncodings,
   regFullVersion,
   restoreUrl,
   toLocalPath,
@@ -69,6 +73,19 @@ export function createHandler(config: AlephConfig) {
       return Response.json([]);
     }
 
+    // the full atomic CSS for the client navigation
+    if (pathname === "/-/atomic.css") {
+      const css = await buildAtomicCSS(config, isDev);
+      if (css === undefined) {
+        return new Response("Not Found", { status: 404 });
+      }
+      const headers = new Headers({ "Content-Type": "text/css; charset=utf-8" });
+      if (searchParams.get("v")) {
+        headers.append("Cache-Control", "public, max-age=31536000, immutable");
+      }
+      return new Response(css, { headers });
+    }
+
     // check if the `out` directory exists
     const outDir = await globalIt("__ALEPH_OUT_DIR", async () => {
       if (!isDev && !buildMode) {
@@ -80,6 +97,14 @@ export function createHandler(config: AlephConfig) {
       return null;
     });
 
+    // the build manifest lists the files precompressed by the build, see `BuildAsset`
+    const buildManifest = await globalIt("__ALEPH_BUILD_MANIFEST", async () => {
+      if (outDir && await existsFile(path.join(outDir, "manifest.json"))) {
+        return JSON.parse(await Deno.readTextFile(path.join(outDir, "manifest.json"))) as BuildManifest;
+      }
+      return null;
+    });
+
     // transform modules
     let loader: ModuleLoader | undefined;
     if (
@@ -159,6 +184,10 @@ export function createHandler(config: AlephConfig) {
     if (!pathname.startsWith("/.") && contentType !== "application/octet-stream") {
       try {
         let filePath = path.join(appDir, pathname);
+        // the wasm modules optimized by the build, see the rust plugin
+        if (outDir && contentType === "application/wasm" && await existsFile(path.join(outDir, pathname))) {
+          filePath = path.join(outDir, pathname);
+        }
         let stat = await Deno.lstat(filePath);
         if (stat.isDirectory && pathname !== "/") {
           filePath = `${trimSuffix(filePath, "/")}/index.html`;
@@ -166,6 +195,26 @@ export function createHandler(config: AlephConfig) {
         }
         if (stat.isFile) {
           const headers = new Headers({ "Content-Type": contentType });
+          // serve the precompressed file next to the original one, e.g. `app_bg.wasm.br`, only the files listed in
+          // the build manifest are looked up
+          const asset = buildManifest?.assets[pathname];
+          const encodings = asset && path.join(appDir, asset.file) === filePath ? asset.encodings ?? [] : [];
+          const acceptEncoding = req.headers.get("Accept-Encoding");
+          let encoding: string | undefined;
+          let encodedFilePath = filePath;
+          if (encodings.length > 0) {
+            headers.set("Vary", "Accept-Encoding");
+          }
+          for (const [name, ext] of precompressedEncodings) {
+            if (encodings.includes(name) && acceptsEncoding(acceptEncoding, name)) {
+              encoding = name;
+              encodedFilePath = filePath + ext;
+              break;
+            }
+          }
+          if (encoding) {
+            headers.set("Content-Encoding", encoding);
+          }
           const deployId = getDeploymentId();
           let etag: string | null = null;
           if (deployId) {
@@ -177,6 +226,9 @@ export function createHandler(config: AlephConfig) {
               headers.append("Last-Modified", new Date(mtime).toUTCString());
             }
           }
+          if (etag && encoding) {
+            etag += `-${encoding}`;
+          }
           if (etag) {
             if (req.headers.get("If-None-Match") === etag) {
               return new Response(null, { status: 304 });
@@ -186,7 +238,7 @@ export function createHandler(config: AlephConfig) {
           if (searchParams.get("v") || regFullVersion.test(pathname)) {
             headers.append("Cache-Control", "public, max-age=31536000, immutable");
           }
-          const file = await Deno.open(filePath, { read: true });
+          const file = await Deno.open(encodedFilePath, { read: true });
           return new Response(file.readable, { headers });
         }
       } catch (err) {
@@ -349,10 +401,15 @@ export function createHandler(config: AlephConfig) {
       if (err instanceof Response) {
         return err;
       }
+      const res = onError?.(err, "ssr", req, ctx);
+      if (res instanceof Response) {
+        return res;
+      }
       let message: string;
       if (err instanceof Error) {
-        message = err.stack as string;
+        // the stack is formatted by the logger, e.g. the wasm frames of a Rust app
         log.error("SSR", err);
+        message = err.stack as string;
       } else {
         message = err?.toString?.() || String(err);
       }
@@ -375,11 +432,12 @@ export function createHandler(config: AlephConfig) {
       connInfo,
       sessionOptions: config.session,
     });
-    const next = (i: number): Promise<Response> | Response => {
+    // a middleware may pass a rewritten request to the next ones, e.g. the url without the locale prefix
+    const next = (i: number, req: Request): Promise<Response> | Response => {
       if (Array.isArray(middlewares) && i < middlewares.length) {
         const mw = middlewares[i];
         try {
-          Reflect.set(ctx, NEXT, next.bind(null, i + 1));
+          Reflect.set(ctx, NEXT, (nextReq?: Request) => next(i + 1, nextReq ?? req));
           return mw.fetch(req, ctx);
         } catch (err) {
           const res = onError?.(err, "middleware", req, ctx);
@@ -395,6 +453,6 @@ export function createHandler(config: AlephConfig) {
       }
       return routeHandler(req, ctx);
     };
-    return next(0);
+    return next(0, req);
   };
 }
@@ -12,6 +12,29 @@ export const regJsxFile = /\.(jsx|tsx|mdx)$/;
 export const regFullVersion = /@\d+\.\d+\.\d+/;
 export const builtinModuleExts = ["tsx", "ts", "mts", "jsx", "js", "mjs"];
 
+/** The precompressed files served by the static file handler, in the order of preference. */
+export const precompressedEncodings: [encoding: string, ext: string][] = [["br", ".br"], ["gzip", ".gz"]];
+
+/**
+ * Checks whether the encoding is accepted by the `Accept-Encoding` header, an encoding with `q=0` is not accepted and
+ * the `*` matches the encodings not listed.
+ */
+export function acceptsEncoding(acceptEncoding: string | null, encoding: string): boolean {
+  let wildcard = false;
+  for (const part of (acceptEncoding ?? "").split(",")) {
+    const [name, ...params] = part.split(";").map((s) => s.trim().toLowerCase());
+    const q = params.find((p) => p.startsWith("q="));
+    const accepted = !q || Number(q.slice(2)) > 0;
+    if (name === encoding) {
+      return accepted;
+    }
+    if (name === "*") {
+      wildcard = accepted;
+    }
+  }
+  return wildcard;
+}
+
 /** Stores and returns the `fn` output in the `globalThis` object. */
 export async function globalIt<T>(
   name: string,
@@ -9,8 +9,12 @@ export enum Level {
   Error = 3,
 }
 
+/** Rewrites the stack of the logged errors, e.g. the symbolication of the wasm frames of the Rust apps. */
+export type StackFormatter = (stack: string) => string;
+
 export class Logger {
   #level: Level = Level.Info;
+  #stackFormatters: StackFormatter[] = [];
 
   get level(): Level {
     return this.#level;
@@ -33,6 +37,10 @@ export class Logger {
     }
   }
 
+  addStackFormatter(formatter: StackFormatter): void {
+    this.#stackFormatters.push(formatter);
+  }
+
   debug(...args: unknown[]): void {
     if (this.#level <= Level.Debug) {
       console.debug(colors.dim("DEBUG"), ...args);
@@ -53,14 +61,22 @@ export class Logger {
 
   error(...args: unknown[]): void {
     if (this.#level <= Level.Error) {
-      console.error(colors.red("ERROR"), ...args);
+      console.error(colors.red("ERROR"), ...args.map((arg) => this.#formatStack(arg)));
     }
   }
 
   fatal(...args: unknown[]): never {
-    console.error(colors.red("FATAL"), ...args);
+    console.error(colors.red("FATAL"), ...args.map((arg) => this.#formatStack(arg)));
     return Deno.exit(1);
   }
+
+  /** Formats the stack of the error in place, so the error page of the server shows the same stack. */
+  #formatStack(arg: unknown): unknown {
+    if (arg instanceof Error && typeof arg.stack === "string" && this.#stackFormatters.length > 0) {
+      arg.stack = this.#stackFormatters.reduce((stack, format) => format(stack), arg.stack);
+    }
+    return arg;
+  }
 }
 
 export default new Logger();
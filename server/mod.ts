@@ -34,7 +34,7 @@ export async function serve(options?: ServeOptions): Promise<void> {
 
   // build the app for production
   if (Deno.args.includes("--build")) {
-    return build(handler);
+    return build(handler, plugins);
   }
 
   // watch file changes in development mode
@@ -1,10 +1,11 @@
 import { FetchError } from "../framework/core/error.ts";
 import { matchRoutes, type RouteModule, type Router } from "../framework/core/router.ts";
-import { cleanPath, isFilledString, isPlainObject, utf8Enc } from "../shared/util.ts";
+import { isFilledString, isPlainObject, utf8Dec, utf8Enc } from "../shared/util.ts";
+import { buildAtomicCSS, extractHTMLClasses, generateAtomicCSS } from "./atomic_css.ts";
 import { CUSTOM_HTML_REWRITER } from "./context.ts";
-import { HTMLRewriter, path } from "./deps.ts";
+import { HTMLRewriter } from "./deps.ts";
 import depGraph from "./graph.ts";
-import { getAlephConfig, getAppDir, getDeploymentId, getFiles, regJsxFile, toLocalPath } from "./helpers.ts";
+import { getAlephConfig, getAlephPkgUri, getDeploymentId, toLocalPath } from "./helpers.ts";
 import log from "./log.ts";
 import { importRouteModule } from "./router.ts";
 import type { HTMLRewriterHandlers, SSR, SSRContext, SuspenseMarker } from "./types.ts";
@@ -23,25 +24,39 @@ export default {
     const isFn = typeof ssr === "function";
     const CSP = isFn ? undefined : ssr.CSP;
     const render = isFn ? ssr : ssr.render;
-    const [url, modules, deferedData] = await initSSR(req, ctx, router);
+    const [url, modules, dataModules, deferedData] = await initSSR(req, ctx, router);
     const headCollection: string[] = [];
     const customHTMLRewriter = Reflect.get(ctx, CUSTOM_HTML_REWRITER) as [string, HTMLRewriterHandlers][];
 
     let status = 200;
     let suspenseMarker: SuspenseMarker | undefined;
     let nonce: string | undefined;
+    let atomicCSSClasses: string[] | undefined;
 
     const ssrContext: SSRContext = {
       url,
+      headers: req.headers,
       modules,
+      dataModules,
       headCollection,
       signal: req.signal,
+      htmlRewriter: {
+        on: (selector, handlers) => {
+          customHTMLRewriter.push([selector, handlers]);
+        },
+      },
       setStatus: (code) => {
         status = code;
       },
+      setHeader: (name, value) => {
+        headers.set(name, value);
+      },
       setSuspenseMarker: (selector, test) => {
         suspenseMarker = { selector, test };
       },
+      setAtomicCSSClasses: (classes) => {
+        atomicCSSClasses = [...classes];
+      },
     };
 
     if (!isDev && CSP) {
@@ -66,47 +81,34 @@ export default {
     depGraph.shallowWalk(modules.map(({ filename }) => filename), (mod) => {
       const { specifier, inlineCSS } = mod;
       if (inlineCSS) {
-        headCollection.push(`<style data-module-id="${specifier}" ssr>${inlineCSS}</style>`);
+        headCollection.push(`<style data-module-id="${specifier}"${nonceAttr(nonce)} ssr>${inlineCSS}</style>`);
       }
     });
 
     // build unocss
     const config = getAlephConfig();
     if (config?.atomicCSS) {
-      const { atomicCSS, build } = config;
-      const { test = regJsxFile, resetCSS } = atomicCSS;
-      let css = Reflect.get(globalThis, "__ALEPH_ATOMICCSS_BUILD");
-      if (!css) {
-        const t = performance.now();
-        const appDir = getAppDir();
-        const files = await getFiles(appDir);
-        const outputDir = "." + cleanPath(build?.outputDir ?? "./output");
-        const inputSources = await Promise.all(
-          files.filter((name) => test.test(name) && !name.startsWith(outputDir)).map((name) =>
-            Deno.readTextFile(path.join(appDir, name))
-          ),
+      const { resetCSS } = config.atomicCSS;
+      let css: string | undefined;
+      if (atomicCSSClasses) {
+        // only inline the css used by the rendered page and the `index.html` shell, then load the full stylesheet once
+        // the page is idle, interacted or navigated
+        const classes = new Set([...atomicCSSClasses, ...getShellClasses(indexHtml)]);
+        css = await generateAtomicCSS(config.atomicCSS, [...classes], isDev);
+        const deployId = getDeploymentId();
+        const href = `/-/atomic.css${deployId ? `?v=${deployId}` : ""}`;
+        headCollection.push(
+          `<link rel="prefetch" href="${href}" as="style">`,
+          `<script type="module"${nonceAttr(nonce)}>${atomicCSSLoader(href)}</script>`,
         );
-        if (inputSources.length > 0) {
-          const ret = await atomicCSS.generate(inputSources.join("\n"), {
-            minify: !isDev,
-          });
-          if (ret.matched.size > 0) {
-            css = ret.css;
-            if (!isDev) {
-              Reflect.set(globalThis, "__ALEPH_ATOMICCSS_BUILD", css);
-            }
-            log.debug(
-              `Atomic CSS generated in ${(performance.now() - t).toFixed(2)}ms`,
-              atomicCSS.name && atomicCSS.version ? `(Powered by ${atomicCSS.name}@${atomicCSS.version})` : "",
-            );
-          }
-        }
+      } else {
+        css = await buildAtomicCSS(config, isDev);
       }
       if (css) {
         if (resetCSS) {
           headCollection.push(`<link rel="stylesheet" href="${toLocalPath(resetCSS)}">`);
         }
-        headCollection.push(`<style>${css}</style>`);
+        headCollection.push(`<style${nonceAttr(nonce)}>${css}</style>`);
       }
     }
 
@@ -153,7 +155,13 @@ export default {
                 dataCacheTtl,
                 data: defered ? undefined : data instanceof Error ? undefined : data,
                 dataDefered: defered,
-                error: data instanceof Error ? { message: data.message, stack: data.stack } : undefined,
+                error: data instanceof Error
+                  ? {
+                    status: data instanceof FetchError ? data.status : undefined,
+                    message: data.message,
+                    stack: data.stack,
+                  }
+                  : undefined,
               };
             });
 
@@ -244,6 +252,11 @@ export default {
               }
             },
           });
+          rewriter.on("style", {
+            element(el) {
+              el.setAttribute("nonce", nonce!);
+            },
+          });
         }
 
         try {
@@ -267,6 +280,48 @@ export default {
   },
 };
 
+// the classes of the `index.html` shell, it's reloaded as a new buffer in development
+const shellClasses = new WeakMap<Uint8Array, string[]>();
+
+function getShellClasses(indexHtml: Uint8Array): string[] {
+  let classes = shellClasses.get(indexHtml);
+  if (!classes) {
+    classes = extractHTMLClasses(utf8Dec.decode(indexHtml));
+    shellClasses.set(indexHtml, classes);
+  }
+  return classes;
+}
+
+/**
+ * The inline script to load the full atomic CSS when the page is idle, or on the first interaction or client navigation
+ * before that. The navigations are followed with the event bus of Aleph.js.
+ */
+function atomicCSSLoader(href: string): string {
+  const eventsModule = `${toLocalPath(getAlephPkgUri())}/framework/core/events.ts`;
+  return [
+    `import events from ${JSON.stringify(eventsModule)};`,
+    `const triggers = ["pointerdown", "keydown", "touchstart", "scroll"];`,
+    `const load = () => {`,
+    `  triggers.forEach((type) => removeEventListener(type, load));`,
+    `  events.off("popstate", load);`,
+    `  if (!document.querySelector("link[data-atomic-css]")) {`,
+    `    const link = document.createElement("link");`,
+    `    link.rel = "stylesheet";`,
+    `    link.href = ${JSON.stringify(href)};`,
+    `    link.dataset.atomicCss = "";`,
+    `    document.head.appendChild(link);`,
+    `  }`,
+    `};`,
+    `triggers.forEach((type) => addEventListener(type, load, { once: true, passive: true }));`,
+    `events.on("popstate", load);`,
+    `if (typeof requestIdleCallback === "function") requestIdleCallback(load); else setTimeout(load, 200);`,
+  ].join("");
+}
+
+function nonceAttr(nonce: string | undefined): string {
+  return nonce ? ` nonce="${nonce}"` : "";
+}
+
 /** import route modules and fetch data for SSR */
 async function initSSR(
   req: Request,
@@ -275,6 +330,7 @@ async function initSSR(
 ): Promise<[
   url: URL,
   routing: RouteModule[],
+  dataModules: RouteModule[],
   deferedData: Record<string, unknown>,
 ]> {
   const url = new URL(req.url);
@@ -363,6 +419,8 @@ async function initSSR(
   return [
     url,
     modules.filter(({ exports }) => exports.default !== undefined),
+    // the data-only modules, e.g. the `data` of a page rendered by the Rust app
+    modules.filter(({ exports, withData }) => exports.default === undefined && withData),
     deferedData,
   ];
 }
@@ -100,14 +100,13 @@ export async function fetchRoute(req: Request, ctx: Context, router: Router): Pr
         }
         if (typeof fetcher === "function") {
           Object.assign(ctx.params as Record<string, string>, ret.pathname.groups);
+          const formNavigation = method === "POST" && isFormNavigation(req);
           const res = await fetcher(req, ctx);
           const headers = new Headers({
             "Cache-Control": cacheTtl ? `public, max-age=${cacheTtl}` : "no-cache, no-store, must-revalidate",
           });
-          if (res instanceof Response) {
-            return res;
-          }
-          return toResponse(res, { headers });
+          const response = res instanceof Response ? res : toResponse(res, { headers });
+          return formNavigation ? redirectBack(req, response) : response;
         }
         return new Response("Method Not Allowed", { status: 405 });
       }
@@ -115,6 +114,43 @@ export async function fetchRoute(req: Request, ctx: Context, router: Router): Pr
   }
 }
 
+/** Checks if the request is a form submitted by the browser without JS, e.g. before the wasm of the page is loaded. */
+export function isFormNavigation(req: Request): boolean {
+  const contentType = req.headers.get("Content-Type") ?? "";
+  if (!contentType.startsWith("application/x-www-form-urlencoded") && !contentType.startsWith("multipart/form-data")) {
+    return false;
+  }
+  const mode = req.headers.get("Sec-Fetch-Mode");
+  if (mode) {
+    return mode === "navigate";
+  }
+  return (req.headers.get("Accept") ?? "").includes("text/html");
+}
+
+/**
+ * Redirects the form submitted without JS back to the page after the mutation, like the "Post/Redirect/Get" pattern.
+ * The redirects and the errors of the mutation are returned as they are.
+ */
+export function redirectBack(req: Request, res: Response): Response {
+  if (res.status >= 300) {
+    return res;
+  }
+  const url = new URL(req.url);
+  const referer = req.headers.get("Referer");
+  let location = url.pathname + url.search;
+  if (referer) {
+    try {
+      const refererUrl = new URL(referer);
+      if (refererUrl.origin === url.origin) {
+        location = refererUrl.pathname + refererUrl.search;
+      }
+    } catch (_) {
+      // invalid referer
+    }
+  }
+  return new Response(null, { status: 303, headers: { "Location": location } });
+}
+
 /** initialize router from routes config */
 export async function initRouter(appDir: string, init: RouterInit = {}): Promise<Router> {
   const reg = toRouterRegExp(init);
@@ -28,6 +28,8 @@ export type AlephConfig = {
 export interface Plugin {
   name?: string;
   setup(config: AlephConfig, env: PluginENV): void | Promise<void>;
+  /** Runs after the client modules are built into the output directory by `--build`. */
+  build?(outputDir: string): void | Promise<void>;
 }
 
 export type PluginENV = {
@@ -146,8 +148,8 @@ export interface Context extends Record<string, unknown> {
   readonly htmlRewriter: HTMLRewriter;
   /** Returns the `Session` object. */
   getSession: <T extends Record<string, unknown> = Record<string, unknown>>() => Promise<Session<T>>;
-  /** Calls next middleware/handler. */
-  next: () => Promise<Response> | Response;
+  /** Calls next middleware/handler, with the request rewritten by the middleware if it's given. */
+  next: (req?: Request) => Promise<Response> | Response;
   /** Renders the route page. */
   render: () => Promise<Response>;
 }
@@ -216,12 +218,25 @@ export type SSGOptions = {
 
 export type SSRContext = {
   readonly url: URL;
+  /** The headers of the request. */
+  readonly headers: Headers;
   readonly modules: RouteModule[];
+  /**
+   * The matched route modules exporting the `data` without a component, e.g. the data of a page rendered by the Rust
+   * app. They are not in the `modules` and the `ssr-data` of the page.
+   */
+  readonly dataModules: RouteModule[];
   readonly headCollection: string[];
   readonly signal: AbortSignal;
   readonly nonce?: string;
+  /** The HtmlRewriter to rewrite the html output. */
+  readonly htmlRewriter: HTMLRewriter;
   setStatus(code: number): void;
+  /** Sets a header of the response, e.g. the `Vary` header of the content negotiation. */
+  setHeader(name: string, value: string): void;
   setSuspenseMarker(selector: string, test: (el: Element) => boolean): void;
+  /** Inlines the atomic CSS of the given classes only, the full stylesheet is loaded on client navigation. */
+  setAtomicCSSClasses(classes: Iterable<string>): void;
 };
 
 export type SuspenseMarker = {
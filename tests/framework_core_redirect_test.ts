@@ -0,0 +1,47 @@
+import { assertEquals } from "std/testing/asserts.ts";
+import events from "../framework/core/events.ts";
+import { redirect } from "../framework/core/redirect.ts";
+
+Deno.test("[unit] framework/core/redirect.ts: redirect with the Rust router", () => {
+  const url = new URL("http://localhost/");
+  const pushed: string[] = [];
+  const emitted: Record<string, unknown>[] = [];
+  const onpopstate = (e: Record<string, unknown>) => emitted.push(e);
+  const descriptors = Object.getOwnPropertyDescriptors(globalThis);
+  Object.defineProperty(globalThis, "location", { value: url, configurable: true });
+  Object.defineProperty(globalThis, "history", {
+    value: {
+      pushState: (_state: unknown, _title: string, to: URL) => {
+        pushed.push(to.href);
+        url.href = to.href;
+      },
+    },
+    configurable: true,
+  });
+  events.on("popstate", onpopstate);
+  try {
+    assertEquals(Reflect.get(globalThis, "__ALEPH_EVENTS"), events);
+
+    // redirects before the router is ready
+    redirect("/todos");
+    assertEquals(pushed, ["http://localhost/todos"]);
+    assertEquals(emitted.length, 0);
+
+    // the Rust router emits the `router` event without the route table
+    events.emit("router", { type: "router", framework: "rust" });
+    assertEquals(emitted.map((e) => (e.url as URL).href), ["http://localhost/todos"]);
+
+    redirect("/about");
+    assertEquals(pushed, ["http://localhost/todos", "http://localhost/about"]);
+    assertEquals(emitted.map((e) => (e.url as URL).href), ["http://localhost/todos", "http://localhost/about"]);
+  } finally {
+    events.off("popstate", onpopstate);
+    for (const name of ["location", "history"]) {
+      if (descriptors[name]) {
+        Object.defineProperty(globalThis, name, descriptors[name]);
+      } else {
+        Reflect.deleteProperty(globalThis, name);
+      }
+    }
+  }
+});
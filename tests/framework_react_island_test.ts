@@ -0,0 +1,76 @@
+import { assertEquals, assertStringIncludes } from "std/testing/asserts.ts";
+import { createElement } from "react";
+import { renderToReadableStream } from "react-dom/server";
+import { RouterContext } from "../framework/react/context.ts";
+import { RustIsland } from "../framework/react/island.ts";
+
+Deno.test("[unit] framework/react/island.ts: render Rust island on the server", async () => {
+  const inputs: unknown[][] = [];
+  Reflect.set(globalThis, "__ALEPH_RUST_ISLANDS", {
+    "/pkg/islands.js": {
+      ssr_island: (name: string, ctx: string, props: string) => {
+        inputs.push([name, ctx, props]);
+        const { count } = JSON.parse(props);
+        return Promise.resolve(JSON.stringify({
+          html: `<button class="a">${count}</button>`,
+          headCollection: [`<style data-module-id="./src/counter.module.css">.a{color:red}</style>`],
+        }));
+      },
+    },
+  });
+  try {
+    const headCollection: string[] = [];
+    const stream = await renderToReadableStream(
+      createElement(
+        RouterContext.Provider,
+        { value: { url: new URL("http://localhost/counter"), params: {}, ssrHeadCollection: headCollection } },
+        createElement(RustIsland, { module: "./pkg/islands.js", name: "counter", props: { count: 1 } }),
+      ),
+    );
+    await stream.allReady;
+    const html = await new Response(stream).text();
+    assertEquals(inputs, [["counter", `{"url":"http://localhost/counter"}`, `{"count":1}`]]);
+    assertStringIncludes(html, `<div data-rust-island="counter"><button class="a">1</button></div>`);
+    assertEquals(headCollection, [`<style data-module-id="./src/counter.module.css">.a{color:red}</style>`]);
+  } finally {
+    Reflect.deleteProperty(globalThis, "__ALEPH_RUST_ISLANDS");
+  }
+});
+
+Deno.test("[unit] framework/react/island.ts: pass the CSP nonce to the Rust island", async () => {
+  Reflect.set(globalThis, "__ALEPH_RUST_ISLANDS", {
+    "/pkg/islands.js": {
+      ssr_island: (_name: string, ctx: string) => {
+        const { nonce } = JSON.parse(ctx);
+        return Promise.resolve(JSON.stringify({
+          html: `<button class="a">1</button>`,
+          headCollection: [`<style data-module-id="./src/counter.module.css" nonce="${nonce}">.a{color:red}</style>`],
+        }));
+      },
+    },
+  });
+  try {
+    const headCollection: string[] = [];
+    const stream = await renderToReadableStream(
+      createElement(
+        RouterContext.Provider,
+        {
+          value: {
+            url: new URL("http://localhost/counter"),
+            params: {},
+            ssrHeadCollection: headCollection,
+            ssrNonce: "abc",
+          },
+        },
+        createElement(RustIsland, { module: "./pkg/islands.js", name: "counter" }),
+      ),
+    );
+    await stream.allReady;
+    await new Response(stream).text();
+    assertEquals(headCollection, [
+      `<style data-module-id="./src/counter.module.css" nonce="abc">.a{color:red}</style>`,
+    ]);
+  } finally {
+    Reflect.deleteProperty(globalThis, "__ALEPH_RUST_ISLANDS");
+  }
+});
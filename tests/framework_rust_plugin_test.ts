@@ -0,0 +1,632 @@
+import { assertEquals, assertRejects, assertStringIncludes } from "std/testing/asserts.ts";
+import "../server/html.ts";
+import {
+  i18nMiddleware,
+  loadModule,
+  nativeLibName,
+  panicError,
+  preloadLinks,
+  render,
+  stripCustomSections,
+  type SSROutput,
+  type WasmModule,
+} from "../framework/rust/plugin.ts";
+import { FetchError } from "../framework/core/error.ts";
+import { PageCache } from "../framework/rust/cache.ts";
+import { Catalogs, flattenMessages, parseAcceptLanguage } from "../framework/rust/i18n.ts";
+import { symbolizeStack, WasmSymbols } from "../framework/rust/symbolize.ts";
+import { createContext } from "../server/context.ts";
+import { path } from "../server/deps.ts";
+import { getAppDir } from "../server/helpers.ts";
+import renderer from "../server/renderer.ts";
+import type { ConnInfo, Context, HTMLRewriterHandlers, SSRContext } from "../server/types.ts";
+import { utf8Enc } from "../shared/util.ts";
+
+/** The SSR context of the tests, it records the rewriters, the status and the headers set by the render. */
+type MockContext = SSRContext & {
+  rewriters: [string, HTMLRewriterHandlers][];
+  status: number;
+  responseHeaders: Headers;
+  atomicCSSClasses: string[];
+};
+
+function mockModule(ssr: (ctx: string) => unknown, exports: Record<string, unknown> = {}): WasmModule {
+  return { ssr, ...exports } as unknown as WasmModule;
+}
+
+function mockContext(href: string, init: Record<string, unknown> = {}): MockContext {
+  const ctx: MockContext = {
+    url: new URL(href, "http://localhost"),
+    headers: new Headers(),
+    dataModules: [],
+    headCollection: [],
+    rewriters: [],
+    status: 200,
+    responseHeaders: new Headers(),
+    atomicCSSClasses: [],
+    htmlRewriter: {
+      on: (selector: string, handlers: HTMLRewriterHandlers) => ctx.rewriters.push([selector, handlers]),
+    },
+    setStatus: (code: number) => {
+      ctx.status = code;
+    },
+    setHeader: (name: string, value: string) => ctx.responseHeaders.set(name, value),
+    setAtomicCSSClasses: (classes: Iterable<string>) => {
+      ctx.atomicCSSClasses = [...classes];
+    },
+    ...init,
+  } as unknown as MockContext;
+  return ctx;
+}
+
+Deno.test("[unit] framework/rust/plugin.ts: render", async () => {
+  const inputs: string[] = [];
+  const mod = mockModule((ctx) => {
+    inputs.push(ctx);
+    return JSON.stringify({
+      html: `<a href="https://alephjs.org" target="_blank">Aleph.js</a><a href="/docs">Docs</a>`,
+      htmlRewriter: {
+        id: 1,
+        selectors: [{ selector: "a[target=_blank]", element: true, text: false, comments: false }],
+      },
+    });
+  }, {
+    __aleph_html_rewriter: (id: number, index: number, kind: string, node: unknown) => {
+      assertEquals([id, index, kind], [1, 0, "element"]);
+      (node as { setAttribute(name: string, value: string): void }).setAttribute("rel", "noopener noreferrer");
+    },
+    __aleph_html_rewriter_free: () => {},
+  });
+  const ctx = mockContext("http://localhost/?foo=bar");
+
+  const html = await render(mod, ctx);
+  assertEquals(inputs, [`{"url":"http://localhost/?foo=bar"}`]);
+  assertEquals(
+    html,
+    `<a href="https://alephjs.org" target="_blank" rel="noopener noreferrer">Aleph.js</a><a href="/docs">Docs</a>`,
+  );
+  assertEquals(ctx.rewriters.length, 1);
+  assertEquals(ctx.rewriters[0][0], "a[target=_blank]");
+  assertEquals(Object.keys(ctx.rewriters[0][1]), ["element"]);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with CSP nonce", async () => {
+  const inputs: string[] = [];
+  const mod = mockModule((ctx) => {
+    inputs.push(ctx);
+    return JSON.stringify({
+      html: [
+        `<style>.app{color:red}</style>`,
+        `<script>window.__app=1</script>`,
+        `<script src="/app.js"></script>`,
+        `<script type="application/json">{}</script>`,
+      ].join(""),
+    });
+  });
+  const ctx = mockContext("http://localhost/", { nonce: "abc" });
+
+  const html = await render(mod, ctx);
+  assertEquals(inputs, [`{"url":"http://localhost/","nonce":"abc"}`]);
+  assertEquals(
+    html,
+    [
+      `<style nonce="abc">.app{color:red}</style>`,
+      `<script nonce="abc">window.__app=1</script>`,
+      `<script src="/app.js"></script>`,
+      `<script type="application/json">{}</script>`,
+    ].join(""),
+  );
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: collect classes for atomic CSS", async () => {
+  const mod = mockModule(() =>
+    JSON.stringify({ html: `<div class="flex  items-center"><p class="text-sm flex">Hello</p></div>` })
+  );
+  const ctx = mockContext("http://localhost/");
+
+  Reflect.set(globalThis, "__ALEPH_CONFIG", { atomicCSS: {} });
+  try {
+    await render(mod, ctx);
+  } finally {
+    Reflect.deleteProperty(globalThis, "__ALEPH_CONFIG");
+  }
+  assertEquals(ctx.atomicCSSClasses, ["flex", "items-center", "text-sm"]);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with head collection", async () => {
+  const mod = mockModule(() =>
+    JSON.stringify({
+      html: `<header class="Xh_header">Aleph.js</header>`,
+      headCollection: [`<style data-module-id="./src/header.module.css">.Xh_header{color:red}</style>`],
+    })
+  );
+  const ctx = mockContext("http://localhost/");
+
+  const html = await render(mod, ctx);
+  assertEquals(html, `<header class="Xh_header">Aleph.js</header>`);
+  assertEquals(ctx.headCollection, [`<style data-module-id="./src/header.module.css">.Xh_header{color:red}</style>`]);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with the route table", async () => {
+  const inputs: string[] = [];
+  const mod = mockModule((ctx) => {
+    inputs.push(ctx);
+    return JSON.stringify({ html: "" });
+  }, { __aleph_routes: () => JSON.stringify(["/", "/todos/new", "/todos/:id", "/blog/:path+"]) });
+  for (const href of ["/todos/new", "/todos/1", "/blog/2023/hello%20world", "/about"]) {
+    await render(mod, mockContext(href));
+  }
+  assertEquals(inputs, [
+    `{"url":"http://localhost/todos/new","route":{"pattern":"/todos/new","params":{}}}`,
+    `{"url":"http://localhost/todos/1","route":{"pattern":"/todos/:id","params":{"id":"1"}}}`,
+    `{"url":"http://localhost/blog/2023/hello%20world","route":{"pattern":"/blog/:path+","params":{"path":"2023/hello world"}}}`,
+    `{"url":"http://localhost/about","route":null}`,
+  ]);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with the renderer of the server", async () => {
+  const inputs: string[] = [];
+  const mod = mockModule((ctx) => {
+    inputs.push(ctx);
+    return JSON.stringify({ html: "<h1>Todo</h1>" });
+  }, { __aleph_routes: () => JSON.stringify(["/", "/todos/:id"]) });
+  const render_ = async (href: string) => {
+    const req = new Request(href);
+    const ctx = createContext(() => new Response(null), { req, connInfo: {} as ConnInfo });
+    const res = await renderer.fetch(req, ctx as unknown as Record<string, unknown>, {
+      indexHtml: utf8Enc.encode(`<!DOCTYPE html><html><head></head><body><ssr-body></ssr-body></body></html>`),
+      router: { prefix: "", routes: [] },
+      ssr: (ctx) => render(mod, ctx),
+    });
+    return [res.status, await res.text()] as const;
+  };
+
+  const [status, html] = await render_("http://localhost/todos/1");
+  assertEquals(status, 200);
+  assertStringIncludes(html, "<body><h1>Todo</h1></body>");
+  assertEquals((await render_("http://localhost/"))[0], 200);
+  assertEquals(inputs, [
+    `{"url":"http://localhost/todos/1","route":{"pattern":"/todos/:id","params":{"id":"1"}}}`,
+    `{"url":"http://localhost/","route":{"pattern":"/","params":{}}}`,
+  ]);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: preload the wasm module with the deployment id", async () => {
+  const inputs: string[] = [];
+  const mod = mockModule((ctx) => {
+    inputs.push(ctx);
+    return JSON.stringify({ html: "" });
+  });
+  await render(mod, mockContext("http://localhost/"), { deploymentId: "1a2b3c4d" });
+  assertEquals(inputs, [`{"url":"http://localhost/","deploymentId":"1a2b3c4d"}`]);
+
+  Deno.env.set("DENO_DEPLOYMENT_ID", "1a2b3c4d");
+  try {
+    assertEquals(preloadLinks("./pkg/yew_app.js"), [
+      `<link rel="modulepreload" href="/pkg/yew_app.js?v=1a2b3c4d" data-module-id="/pkg/yew_app.js">`,
+      `<link rel="preload" href="/pkg/yew_app_bg.wasm?v=1a2b3c4d" as="fetch" type="application/wasm" crossorigin>`,
+    ]);
+  } finally {
+    Deno.env.delete("DENO_DEPLOYMENT_ID");
+  }
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: strip the custom sections of wasm", () => {
+  const custom = (name: string, body: number[]) => {
+    const payload = [name.length, ...new TextEncoder().encode(name), ...body];
+    return [0, payload.length, ...payload];
+  };
+  const header = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+  const typeSection = [0x01, 0x04, 0x01, 0x60, 0x00, 0x00];
+  // the size of the debug section is encoded with two bytes
+  const debugSection = [0x00, 0x87, 0x02, 11, ...new TextEncoder().encode(".debug_info"), ...new Array(251).fill(7)];
+  const wasm = new Uint8Array([
+    ...header,
+    ...typeSection,
+    ...custom("name", [1, 2, 3]),
+    ...custom("target_features", [0]),
+    ...debugSection,
+    ...custom("producers", [9]),
+  ]);
+  const stripped = stripCustomSections(wasm);
+  assertEquals(stripped, new Uint8Array([...header, ...typeSection, ...custom("target_features", [0])]));
+  assertEquals(WebAssembly.validate(stripped), true);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: native SSR", async () => {
+  assertEquals(nativeLibName("./target/release/yew_app", "linux"), "target/release/libyew_app.so");
+  assertEquals(nativeLibName("./target/release/yew_app", "darwin"), "target/release/libyew_app.dylib");
+  assertEquals(nativeLibName("./target/release/yew_app", "windows"), "target/release/yew_app.dll");
+
+  // the native library returns the error instead of throwing it
+  const mod = mockModule(() => JSON.stringify({ error: "panicked: not found" }));
+  await assertRejects(() => render(mod, mockContext("http://localhost/")), Error, "panicked: not found");
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: cache the pages", async () => {
+  let count = 0;
+  const mod = mockModule((ctx) => {
+    const { url } = JSON.parse(ctx);
+    count++;
+    return JSON.stringify({
+      html: `<p>${count}</p>`,
+      cache: url.endsWith("/stale") ? { ttl: 0, tags: [] } : { ttl: 60, tags: ["todos"] },
+    });
+  });
+  const cache = new PageCache<SSROutput>();
+  const render_ = (href: string) => render(mod, mockContext(href), { cache });
+
+  assertEquals(await render_("http://localhost/todos"), "<p>1</p>");
+  assertEquals(await render_("http://localhost/todos"), "<p>1</p>");
+  assertEquals(await render_("http://localhost/todos?filter=done"), "<p>2</p>");
+  assertEquals(count, 2);
+
+  // the stale page is served while it's regenerated in the background
+  assertEquals(await render_("http://localhost/stale"), "<p>3</p>");
+  assertEquals(await render_("http://localhost/stale"), "<p>3</p>");
+  await new Promise((resolve) => setTimeout(resolve, 0));
+  assertEquals(count, 4);
+  assertEquals(await render_("http://localhost/stale"), "<p>4</p>");
+
+  assertEquals(await cache.invalidate({ path: "/todos" }), 2);
+  assertEquals(await render_("http://localhost/todos"), "<p>6</p>");
+  assertEquals(await cache.invalidate({ tag: "todos" }), 1);
+  assertEquals(await cache.invalidate({ tag: "todos" }), 0);
+
+  // the html rewritten by the handlers of the app is cached
+  const mod2 = mockModule(() => {
+    count++;
+    return JSON.stringify({
+      html: `<a href="https://alephjs.org" target="_blank">${count}</a>`,
+      htmlRewriter: { id: count, selectors: [{ selector: "a[target=_blank]", element: true }] },
+      cache: { ttl: 60, tags: [] },
+    });
+  }, {
+    __aleph_html_rewriter: (_id: number, _index: number, _kind: string, node: unknown) => {
+      (node as { setAttribute(name: string, value: string): void }).setAttribute("rel", "noopener");
+    },
+    __aleph_html_rewriter_free: () => {},
+  });
+  const ctx = mockContext("http://localhost/about");
+  const expected = `<a href="https://alephjs.org" target="_blank" rel="noopener">7</a>`;
+  assertEquals(await render(mod2, ctx, { cache }), expected);
+  assertEquals(await render(mod2, ctx, { cache }), expected);
+  assertEquals(cache.get("/about")?.output.htmlRewriter, undefined);
+  assertEquals(count, 7);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: cache the pages without the nonce and the data", async () => {
+  let count = 0;
+  const mod = mockModule((ctx) => {
+    const { nonce } = JSON.parse(ctx);
+    count++;
+    return JSON.stringify({
+      html: `<style nonce="${nonce}">p{}</style><p>${count}</p>`,
+      headCollection: [`<style data-module-id="./app.module.css" nonce="${nonce}">.a{}</style>`],
+      cache: { ttl: 60, tags: [] },
+    });
+  });
+  const cache = new PageCache<SSROutput>();
+  const render_ = async (nonce: string, data?: unknown) => {
+    const url = new URL("http://localhost/todos");
+    const dataModules = data ? [{ url, filename: "./routes/todos.ts", data, withData: true }] : [];
+    const ctx = mockContext(url.href, { nonce, dataModules });
+    return [await render(mod, ctx, { cache }), ...ctx.headCollection];
+  };
+
+  assertEquals(await render_("a"), [
+    `<style nonce="a">p{}</style><p>1</p>`,
+    `<style data-module-id="./app.module.css" nonce="a">.a{}</style>`,
+  ]);
+  // the nonce of the request is applied to the cached page
+  assertEquals(await render_("b"), [
+    `<style nonce="b">p{}</style><p>1</p>`,
+    `<style data-module-id="./app.module.css" nonce="b">.a{}</style>`,
+  ]);
+  assertEquals(cache.get("/todos")?.output.html, `<style>p{}</style><p>1</p>`);
+  // the pages with the data are rendered for every request
+  assertEquals((await render_("c", { todos: [] }))[0], `<style nonce="c">p{}</style><p>2</p>`);
+  assertEquals((await render_("c", { todos: [] }))[0], `<style nonce="c">p{}</style><p>3</p>`);
+});
+
+Deno.test("[unit] framework/rust/i18n.ts: detect the locale", () => {
+  const catalogs = new Catalogs({ locales: ["en", "zh-CN", "de"] });
+  const detect = (headers: Record<string, string> = {}) => catalogs.detect(new Headers(headers));
+
+  assertEquals(detect(), "en");
+  assertEquals(detect({ cookie: "foo=bar; locale=de" }), "de");
+  assertEquals(detect({ cookie: "locale=zh-cn", "accept-language": "de" }), "zh-CN");
+  assertEquals(detect({ "accept-language": "fr;q=0.9, de;q=0.5, zh-TW" }), "zh-CN");
+  assertEquals(detect({ cookie: "locale=fr", "accept-language": "fr, *" }), "en");
+
+  assertEquals(catalogs.stripLocale("/zh-cn/todos"), ["zh-CN", "/todos"]);
+  assertEquals(catalogs.stripLocale("/de"), ["de", "/"]);
+  assertEquals(catalogs.stripLocale("/todos"), null);
+  assertEquals(catalogs.stripLocale("/"), null);
+
+  assertEquals(parseAcceptLanguage("en-US,en;q=0.8, zh;q=0.9 ,*;q=0.1, fr;q=0"), ["en-US", "zh", "en"]);
+  assertEquals(flattenMessages({ index: { title: "Hi", n: 1 }, $number: { decimal: "," } }), {
+    "index.title": "Hi",
+    "index.n": "1",
+    "$number.decimal": ",",
+  });
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with i18n", async () => {
+  const dir = await Deno.makeTempDir();
+  await Deno.writeTextFile(path.join(dir, "en.json"), JSON.stringify({ index: { title: "Hello", more: "More" } }));
+  await Deno.writeTextFile(path.join(dir, "zh-CN.json"), JSON.stringify({ index: { title: "你好" } }));
+  const catalogs = new Catalogs({ locales: ["en", "zh-CN"], dir: path.relative(getAppDir(), dir) });
+  await catalogs.load();
+  await Deno.remove(dir, { recursive: true });
+
+  const inputs: Record<string, unknown>[] = [];
+  const mod = mockModule((ctx) => {
+    inputs.push(JSON.parse(ctx));
+    return JSON.stringify({ html: `<p>${inputs.length}</p>`, cache: { ttl: 60, tags: [] } });
+  });
+  const cache = new PageCache<SSROutput>();
+  const render_ = async (lang: string) => {
+    const ctx = mockContext("http://localhost/", { headers: new Headers({ "accept-language": lang }) });
+    const html = await render(mod, ctx, { cache, catalogs });
+    assertEquals(ctx.rewriters.map(([selector]) => selector), ["html"]);
+    assertEquals(ctx.responseHeaders.get("Vary"), "Accept-Language, Cookie");
+    return html;
+  };
+
+  assertEquals(await render_("zh-CN,zh;q=0.9"), "<p>1</p>");
+  assertEquals(inputs[0].i18n, { locale: "zh-CN", messages: { "index.title": "你好", "index.more": "More" } });
+  assertEquals(await render_("en-US"), "<p>2</p>");
+  assertEquals((inputs[1].i18n as { locale: string }).locale, "en");
+  // the pages are cached per locale
+  assertEquals(await render_("zh"), "<p>1</p>");
+  assertEquals(inputs.length, 2);
+  assertEquals(await cache.invalidate({ path: "/" }), 2);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with i18n by the locale prefix", async () => {
+  const catalogs = new Catalogs({ locales: ["en", "zh-CN"] });
+  const inputs: Record<string, unknown>[] = [];
+  const mod = mockModule((ctx) => {
+    inputs.push(JSON.parse(ctx));
+    return JSON.stringify({ html: "" });
+  }, { __aleph_routes: () => JSON.stringify(["/todos"]) });
+  const middleware = i18nMiddleware(catalogs);
+  for (const href of ["/zh-CN/todos?filter=done", "/todos"]) {
+    let req = new Request(new URL(href, "http://localhost"), { headers: { "accept-language": "en-US" } });
+    const next = (rewritten?: Request) => {
+      req = rewritten ?? req;
+      return new Response(null);
+    };
+    await middleware.fetch(req, { next } as unknown as Context);
+    await render(mod, mockContext(req.url, { headers: req.headers }), { catalogs });
+  }
+  // the prefix is stripped before the routing, the path without the prefix detects the locale by the headers
+  assertEquals(inputs.map(({ url, route, i18n }) => [url, route, (i18n as { locale: string }).locale]), [
+    ["http://localhost/todos?filter=done", { pattern: "/todos", params: {} }, "zh-CN"],
+    ["http://localhost/todos", { pattern: "/todos", params: {} }, "en"],
+  ]);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with the page data", async () => {
+  const inputs: Record<string, unknown>[] = [];
+  const mod = mockModule((ctx) => {
+    inputs.push(JSON.parse(ctx));
+    return JSON.stringify({ html: "<p>todos</p>" });
+  });
+  const url = new URL("http://localhost/todos?page=1");
+  const headCollection: string[] = [];
+  const render_ = (dataModules: Record<string, unknown>[]) =>
+    render(mod, mockContext(url.href, { dataModules, headCollection }), {});
+
+  const html = await render_([
+    { url: new URL("http://localhost/"), withData: true, data: { title: "app" } },
+    { url, withData: true, data: { todos: [] } },
+  ]);
+  assertEquals(html, "<p>todos</p>");
+  // the data of the page, the last matched module with data
+  assertEquals(inputs[0].data, { todos: [] });
+  // it's embedded for the hydration, the data-only modules are not in the `ssr-data`
+  const json = `[{"url":"\\/todos?page=1","withData":true,"data":{"todos":[]}}]`;
+  assertEquals(headCollection, [`<script id="aleph-data" type="application/json">${json}</script>`]);
+  // the errors and the defered data are not sent to the Rust app
+  await render_([{ url, withData: true, data: new Error("oops") }]);
+  assertEquals(inputs[1].data, undefined);
+  await render_([{ url, withData: true, data: () => Promise.resolve({}) }, { url, withData: false }]);
+  assertEquals(inputs[2].data, undefined);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with errors", async () => {
+  const inputs: Record<string, unknown>[] = [];
+  const mod = mockModule((ctx) => {
+    inputs.push(JSON.parse(ctx));
+    return JSON.stringify({ html: "<p>404</p>", status: 404, errors: [{ status: 404, message: "Not Found" }] });
+  });
+  const url = new URL("http://localhost/todos/1");
+  const ctx = mockContext(url.href, {
+    dataModules: [{ url, withData: true, data: new FetchError(404, "Not Found") }],
+  });
+  const errors: [unknown, string][] = [];
+
+  Reflect.set(globalThis, "__ALEPH_CONFIG", {
+    onError: (error: unknown, cause: string) => {
+      errors.push([error, cause]);
+    },
+  });
+  try {
+    assertEquals(await render(mod, ctx), "<p>404</p>");
+    // the error thrown by the `data` is sent to the `ErrorBoundary`
+    assertEquals(inputs[0].error, { status: 404, message: "Not Found" });
+    assertEquals(ctx.status, 404);
+    assertEquals(errors.length, 1);
+    assertEquals(errors[0][1], "ssr");
+    assertEquals((errors[0][0] as FetchError).status, 404);
+
+    // the `onError` may respond the error instead
+    const res = new Response("Custom 404", { status: 404 });
+    Reflect.set(globalThis, "__ALEPH_CONFIG", { onError: () => res });
+    let thrown: unknown;
+    try {
+      await render(mod, ctx);
+    } catch (err) {
+      thrown = err;
+    }
+    assertEquals(thrown, res);
+  } finally {
+    Reflect.deleteProperty(globalThis, "__ALEPH_CONFIG");
+  }
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with a redirect", async () => {
+  const mod = mockModule(() => JSON.stringify({ html: "", redirect: { location: "/login", status: 303 } }));
+  const ctx = mockContext("http://localhost/dashboard");
+
+  let thrown: unknown;
+  try {
+    await render(mod, ctx);
+  } catch (err) {
+    thrown = err;
+  }
+  // the server responds the thrown response as is
+  const res = thrown as Response;
+  assertEquals(res instanceof Response, true);
+  assertEquals(res.status, 303);
+  assertEquals(res.headers.get("Location"), "/login");
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: reject the render that panicked only", async () => {
+  // the js glue of a wasm module, the renders of the first instance never finish after the panic like a trap
+  const dir = await Deno.makeTempDir();
+  await Deno.writeTextFile(
+    path.join(dir, "app.js"),
+    `
+    const instance = new URL(import.meta.url).searchParams.get("instance") ?? "0";
+    let memory;
+    export default async function init() {
+      memory = new WebAssembly.Memory({ initial: 1 });
+      return { memory };
+    }
+    export function ssr(ctx) {
+      const { url, renderId } = JSON.parse(ctx);
+      const { pathname } = new URL(url);
+      if (pathname === "/panic") {
+        globalThis.__ALEPH_RUST_PANIC_HOOK({ message: "boom", stack: "", memory, renderId });
+        return new Promise(() => {});
+      }
+      if (pathname === "/slow" && instance === "0") {
+        return new Promise(() => {});
+      }
+      return JSON.stringify({ html: "<p>" + pathname + " " + instance + "</p>" });
+    }
+    `,
+  );
+  await Deno.writeFile(path.join(dir, "app_bg.wasm"), new Uint8Array([0, 0x61, 0x73, 0x6d, 1, 0, 0, 0]));
+  const mod = await loadModule(path.relative(getAppDir(), path.join(dir, "app.js")));
+  const render_ = (pathname: string) => render(mod, mockContext(pathname));
+
+  const slow = render_("/slow");
+  const panic = render_("/panic");
+  const err = await assertRejects(() => panic, Error, "boom");
+  assertEquals(err.name, "Panic");
+  // the other render is retried with the new instance
+  assertEquals(await slow, "<p>/slow 1</p>");
+  assertEquals(await render_("/"), "<p>/ 1</p>");
+  await Deno.remove(dir, { recursive: true });
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with the hydration check", async () => {
+  const mod = mockModule(() => JSON.stringify({ html: "<p>hi</p>" }));
+  const headCollection: string[] = [];
+  const ctx = mockContext("http://localhost/", { headCollection });
+
+  await render(mod, ctx, { hydrationCheck: true });
+  assertEquals(headCollection.length, 1);
+  assertEquals(headCollection[0].includes("/framework/rust/hydration.ts"), true);
+  assertEquals(headCollection[0].includes("watchHydration()"), true);
+  // it's enabled in development mode only
+  headCollection.length = 0;
+  await render(mod, ctx);
+  assertEquals(headCollection.length, 0);
+});
+
+Deno.test("[unit] framework/rust/symbolize.ts: symbolize the wasm frames", () => {
+  const enc = new TextEncoder();
+  const section = (id: number, payload: number[]) => [id, payload.length, ...payload];
+  const custom = (name: string, body: number[]) => section(0, [name.length, ...enc.encode(name), ...body]);
+  const cstr = (s: string) => [...enc.encode(s), 0];
+  // a DWARF 4 line program: `src/routes/todos.rs` at the address 3 (line 42) and 4 (line 43) of the code section
+  const header = [
+    ...[1, 1, 1, 0xfb, 14, 13, 0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1],
+    ...cstr("src/routes"),
+    0,
+    ...cstr("todos.rs"),
+    ...[1, 0, 0],
+    0,
+  ];
+  const program = [
+    ...[0, 5, 2, 3, 0, 0, 0], // DW_LNE_set_address 3
+    ...[5, 5, 3, 41, 1], // column 5, line 42, copy
+    ...[2, 1, 5, 9, 3, 1, 1], // address 4, column 9, line 43, copy
+    ...[2, 2, 0, 1, 1], // DW_LNE_end_sequence at 6
+  ];
+  const unit = [4, 0, header.length, 0, 0, 0, ...header, ...program];
+  const module = [
+    ...[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00],
+    ...section(1, [1, 0x60, 0, 0]),
+    // an imported function, the index of the frames counts it
+    ...section(2, [1, 1, 0x6d, 1, 0x66, 0, 0]),
+    ...section(3, [1, 0]),
+    // the body starts at 0x1f: no locals, nop, unreachable, end
+    ...section(10, [1, 4, 0, 0x01, 0x00, 0x0b]),
+  ];
+  const wasm = new Uint8Array([...module, ...custom(".debug_line", [unit.length, 0, 0, 0, ...unit])]);
+  assertEquals(WebAssembly.validate(wasm), true);
+  const symbols = WasmSymbols.parse(wasm)!;
+  assertEquals(symbols.lookup(1, 0x21), { file: "src/routes/todos.rs", line: 43, column: 9 });
+  assertEquals(symbols.lookup(1, 0x20), { file: "src/routes/todos.rs", line: 42, column: 5 });
+  assertEquals(symbols.lookup(0, 0x21), null);
+
+  const stack = [
+    "RuntimeError: unreachable",
+    "    at yew_app::routes::todos::Page (wasm://wasm/0011aabb:wasm-function[1]:0x21)",
+    "    at yew_app::routes::todos::Page (wasm://wasm/0011aabb:wasm-function[1]:0x40)",
+    "    at ssr (file:///app/pkg/yew_app.js:120:10)",
+  ].join("\n");
+  assertEquals(
+    symbolizeStack(stack, [symbols]).split("\n").slice(1),
+    [
+      "    at yew_app::routes::todos::Page (src/routes/todos.rs:43:9)",
+      "    at yew_app::routes::todos::Page (wasm://wasm/0011aabb:wasm-function[1]:0x40)",
+      "    at ssr (file:///app/pkg/yew_app.js:120:10)",
+    ],
+  );
+  // the frames of Firefox
+  assertEquals(
+    symbolizeStack("Page@http://localhost:3000/pkg/yew_app_bg.wasm?v=1:wasm-function[1]:0x21", [symbols]),
+    "Page@src/routes/todos.rs:43:9",
+  );
+  // a release build without the debug info
+  assertEquals(WasmSymbols.parse(new Uint8Array(module)), null);
+});
+
+Deno.test("[unit] framework/rust/plugin.ts: render with a panic", async () => {
+  const message = "panicked at src/routes/todos.rs:42:5:\nboom";
+  const err = panicError({
+    message,
+    stack: "Error\n    at __wbg_new_abc (file:///app/pkg/yew_app.js:10:5)\n    at x (wasm://wasm/1:wasm-function[1]:0x21)",
+  });
+  assertEquals(err.name, "Panic");
+  assertEquals(err.message, message);
+  assertEquals(err.stack!.split("\n").slice(2), [
+    "    at __wbg_new_abc (file:///app/pkg/yew_app.js:10:5)",
+    "    at x (wasm://wasm/1:wasm-function[1]:0x21)",
+  ]);
+
+  // the panic overlay is enabled in development mode
+  const mod = mockModule(() => JSON.stringify({ html: "<p>hi</p>" }));
+  const headCollection: string[] = [];
+  await render(mod, mockContext("http://localhost/", { headCollection }), { panicOverlay: true });
+  assertEquals(headCollection.length, 1);
+  assertEquals(headCollection[0].includes("/framework/rust/panic.ts"), true);
+  assertEquals(headCollection[0].includes("watchPanics()"), true);
+});
@@ -0,0 +1,29 @@
+import { assertEquals, assertStringIncludes } from "std/testing/asserts.ts";
+import { createMockServer, hasWasmPack } from "aleph/framework/rust/testing.ts";
+
+Deno.test({
+  name: "[integration] examples/leptos-app",
+  // requires the rust toolchain with the `wasm32-unknown-unknown` target and wasm-pack
+  ignore: !(await hasWasmPack()),
+  fn: async (t) => {
+    // the server module is built with the `ssr` feature, the `hydrate` one of `clientModule` is loaded by the browser
+    const api = await createMockServer({
+      appDir: "./examples/leptos-app",
+      module: "./pkg/server.js",
+      clientModule: "./pkg/client.js",
+      build: { features: ["ssr"] },
+    });
+
+    await t.step("GET /", async () => {
+      const res = await api.fetch("/");
+      const html = await res.text();
+      assertEquals(res.status, 200);
+      assertEquals(res.headers.get("Content-Type"), "text/html; charset=utf-8");
+      assertStringIncludes(html, `<title>Leptos - Aleph.js</title>`);
+      assertStringIncludes(html, `<link rel="modulepreload" href="/pkg/client.js`);
+      assertStringIncludes(html, `<main`);
+      assertStringIncludes(html, `>Clear</button>`);
+      assertStringIncludes(html, `Value: `);
+    });
+  },
+});
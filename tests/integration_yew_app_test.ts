@@ -0,0 +1,114 @@
+import { assert, assertEquals, assertStringIncludes } from "std/testing/asserts.ts";
+import { invalidatePageCache } from "aleph/framework/rust/plugin.ts";
+import { buildCrate, createMockServer, hasWasmPack } from "aleph/framework/rust/testing.ts";
+
+Deno.test({
+  name: "[integration] examples/yew-app",
+  // requires the rust toolchain with the `wasm32-unknown-unknown` target and wasm-pack
+  ignore: !(await hasWasmPack()),
+  fn: async (t) => {
+    // the `/about` page is split into the wasm module of the `chunks/about` crate
+    await buildCrate({ cwd: "./examples/yew-app/chunks/about", outDir: "../../pkg/about" });
+    const api = await createMockServer({
+      appDir: "./examples/yew-app",
+      module: "./pkg/yew_app.js",
+      chunks: ["./pkg/about/about.js"],
+      i18n: { locales: ["en", "zh-CN"] },
+    });
+
+    await t.step("GET /", async () => {
+      const res = await api.fetch("/");
+      const html = await res.text();
+      assertEquals(res.status, 200);
+      assertEquals(res.headers.get("Content-Type"), "text/html; charset=utf-8");
+      assertStringIncludes(html, `<html lang="en">`);
+      assertStringIncludes(html, `<title>Yew - Aleph.js</title>`);
+      assertStringIncludes(html, `<link rel="modulepreload" href="/pkg/yew_app.js`);
+      assertStringIncludes(html, `<script id="aleph-route" type="application/json">`);
+      assertStringIncludes(html, `<h1>The Fullstack Framework in Deno.</h1>`);
+      assertStringIncludes(html, `>Todos App Demo</a>`);
+    });
+
+    await t.step("GET /about", async () => {
+      const res = await api.fetch("/about");
+      const html = await res.text();
+      assertEquals(res.status, 200);
+      assertStringIncludes(html, `<div data-aleph-chunk="/about" data-aleph-ssr=""><div class="about screen">`);
+      assertStringIncludes(html, `<h1>About</h1>`);
+      assertStringIncludes(html, `<link rel="modulepreload" href="/pkg/about/about.js"`);
+    });
+
+    await t.step("split the /about page from the app module", async () => {
+      // the latin1 text of the wasm files, the string literals of the page are only in the chunk
+      const read = async (filename: string) => new TextDecoder("latin1").decode(await Deno.readFile(filename));
+      const marker = "This page is split into its own wasm module";
+      const app = await read("./examples/yew-app/target/aleph/test-pkg/yew_app_bg.wasm");
+      const chunk = await read("./examples/yew-app/pkg/about/about_bg.wasm");
+      assert(!app.includes(marker), "the page of the chunk is compiled into the app module");
+      assert(chunk.includes(marker));
+    });
+
+    await t.step("GET / (Accept-Language: zh-CN)", async () => {
+      const res = await api.fetch("/", { headers: { "Accept-Language": "zh-CN,zh;q=0.9" } });
+      const html = await res.text();
+      assertEquals(res.status, 200);
+      assertStringIncludes(html, `<html lang="zh-CN">`);
+    });
+
+    await t.step("GET / (page cache, CSP nonce)", async () => {
+      const api = await createMockServer({
+        appDir: "./examples/yew-app",
+        module: "./pkg/yew_app.js",
+        pageCache: true,
+        ssr: { CSP: { nonce: true, getPolicy: (_url, nonce) => `script-src 'self' 'nonce-${nonce}'` } },
+      });
+      const fetchPage = async () => {
+        const res = await api.fetch("/");
+        const [, nonce] = res.headers.get("Content-Security-Policy")!.match(/'nonce-(\w+)'/)!;
+        return [res.status, nonce, await res.text()] as const;
+      };
+
+      const [status, nonce, html] = await fetchPage();
+      assertEquals(status, 200);
+      assertStringIncludes(html, `<h1>The Fullstack Framework in Deno.</h1>`);
+      assertStringIncludes(html, `nonce="${nonce}"`);
+      // the index page calls `use_page_cache`, the cached page is served with the nonce of the next request
+      await new Promise((resolve) => setTimeout(resolve, 2));
+      const [, nonce2, html2] = await fetchPage();
+      assert(nonce2 !== nonce);
+      assert(!html2.includes(nonce), "the nonce of the first request is cached");
+      assertEquals(html2.split(`nonce="${nonce2}"`).length, html.split(`nonce="${nonce}"`).length);
+      assertEquals(await invalidatePageCache({ path: "/" }), 1);
+    });
+
+    await t.step("POST /todos", async () => {
+      const res = await api.fetch("/todos", {
+        method: "POST",
+        headers: { "Sec-Fetch-Mode": "navigate", "Referer": "http://localhost/todos" },
+        body: new URLSearchParams({ message: "Better Call Saul!" }),
+      });
+      assertEquals(res.status, 303);
+      assertEquals(res.headers.get("Location"), "/todos");
+    });
+
+    await t.step("GET /todos", async () => {
+      const res = await api.fetch("/todos");
+      const html = await res.text();
+      assertEquals(res.status, 200);
+      // the data-only route module is embedded by the rust plugin instead of the `ssr-data`
+      assertStringIncludes(html, `<script id="ssr-data" type="application/json">[]</script>`);
+      assertStringIncludes(html, `<script id="aleph-data" type="application/json">[{"url":"\\/todos","withData":true,`);
+      assertStringIncludes(html, `<h1><span>Todos</span><em>0/1</em></h1>`);
+      assertStringIncludes(html, `<label>Better Call Saul!</label>`);
+      assertStringIncludes(html, `<form method="post">`);
+    });
+
+    await t.step("GET /todos?_data_", async () => {
+      const res = await api.fetch("/todos?_data_");
+      const data = await res.json();
+      assertEquals(res.status, 200);
+      assertEquals(data.todos.length, 1);
+      assertEquals(data.todos.at(0).message, "Better Call Saul!");
+    });
+  },
+});
@@ -0,0 +1,96 @@
+import { assertEquals } from "std/testing/asserts.ts";
+import {
+  buildAtomicCSS,
+  extractHTMLClasses,
+  extractRustClasses,
+  invalidateAtomicCSSFiles,
+} from "../server/atomic_css.ts";
+import { path } from "../server/deps.ts";
+
+Deno.test("[unit] server/atomic_css.ts: extractRustClasses", async (t) => {
+  await t.step("html!", () => {
+    const source = `
+      // <div class="comment">
+      #[function_component]
+      pub fn App() -> Html {
+        let active = use_state(|| false);
+        let quote = '"';
+        html! {
+          <div class="flex items-center" title={"not-a-class"}>
+            <p classes={classes!("text-sm", active.then(|| "font-bold"))}>{"Hello world"}</p>
+            <span class={format!("m-{} text-red-500", 2)} />
+            /* <b class="block-comment"></b> */
+            <svg><path d="M5.86,6.11c0,.07,0,.18.06.31s0"/></svg>
+          </div>
+        }
+      }
+    `;
+    assertEquals(extractRustClasses(source), ["text-sm", "font-bold", "flex", "items-center", "text-red-500"]);
+  });
+
+  await t.step("view!", () => {
+    const source = `
+      #[component]
+      pub fn Counter(cx: Scope) -> impl IntoView {
+        view! { cx,
+          <button class="px-2 rounded" class:hidden=move || hidden.get() class=move || if on() { "on" } else { "off" } id="btn">
+            {move || value.get()}
+          </button>
+        }
+      }
+    `;
+    assertEquals(extractRustClasses(source), ["px-2", "rounded", "hidden", "on", "off"]);
+  });
+
+  await t.step("classes!", () => {
+    const source = `let class = classes!("btn", r#"btn-primary"#, disabled.then_some("opacity-50"));`;
+    assertEquals(extractRustClasses(source), ["btn", "btn-primary", "opacity-50"]);
+  });
+});
+
+Deno.test("[unit] server/atomic_css.ts: extractHTMLClasses", () => {
+  const html = `<html class="dark"><body class='min-h-screen  bg-white'><div id="root" class="flex"></div></body></html>`;
+  assertEquals(extractHTMLClasses(html), ["dark", "min-h-screen", "bg-white", "flex"]);
+});
+
+Deno.test("[unit] server/atomic_css.ts: buildAtomicCSS", async (t) => {
+  const appDir = await Deno.makeTempDir();
+  const inputs: string[] = [];
+  const atomicCSS = {
+    test: /\.rs$/,
+    generate: (input: string | string[]) => {
+      inputs.push(String(input));
+      return Promise.resolve({ css: ".css{}", matched: new Set(["css"]) });
+    },
+  };
+  Reflect.set(globalThis, "__ALEPH_APP_DIR", appDir);
+  await Deno.writeTextFile(path.join(appDir, "a.rs"), `html! { <p class="a" /> }`);
+
+  await t.step("cache the files", async () => {
+    await buildAtomicCSS({ atomicCSS }, true);
+    await Deno.writeTextFile(path.join(appDir, "b.rs"), `html! { <p class="b" /> }`);
+    await buildAtomicCSS({ atomicCSS }, true);
+    assertEquals(inputs.pop(), "a");
+  });
+
+  await t.step("invalidate the files", async () => {
+    invalidateAtomicCSSFiles(path.join(appDir, "b.rs"));
+    await buildAtomicCSS({ atomicCSS }, true);
+    assertEquals(inputs.pop()?.split("\n").sort(), ["a", "b"]);
+  });
+
+  await t.step("follow the symlinks", async () => {
+    const target = path.join(await Deno.makeTempDir(), "c.rs");
+    await Deno.writeTextFile(target, `html! { <p class="c" /> }`);
+    await Deno.symlink(target, path.join(appDir, "c.rs"));
+    invalidateAtomicCSSFiles(path.join(appDir, "c.rs"));
+    await buildAtomicCSS({ atomicCSS }, true);
+    await Deno.writeTextFile(target, `html! { <p class="d" /> }`);
+    await Deno.utime(target, new Date(), new Date(Date.now() + 1000));
+    await buildAtomicCSS({ atomicCSS }, true);
+    assertEquals(inputs.pop()?.split("\n").sort(), ["a", "b", "d"]);
+  });
+
+  Reflect.deleteProperty(globalThis, "__ALEPH_APP_DIR");
+  invalidateAtomicCSSFiles();
+});
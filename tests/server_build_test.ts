@@ -0,0 +1,50 @@
+import { assertEquals } from "std/testing/asserts.ts";
+import { join } from "std/path/mod.ts";
+import { buildManifestVersion, createBuildManifest } from "../server/build.ts";
+
+Deno.test("[unit] server/build.ts: createBuildManifest", async () => {
+  const appDir = await Deno.makeTempDir();
+  const files: Record<string, string> = {
+    "index.html": `<html><head></head><body><div id="root"></div><script type="module" src="./main.ts"></script></body></html>`,
+    "main.ts": `console.log("main")`,
+    "assets/logo.svg": "<svg></svg>",
+    "pkg/app_bg.wasm": "",
+    "target/debug/app.svg": "<svg></svg>",
+    ".cache/logo.svg": "<svg></svg>",
+    "output/main.js": `console.log("main")`,
+    "output/pkg/app_bg.wasm": "",
+    "output/pkg/app_bg.wasm.br": "",
+    "output/pkg/app_bg.wasm.gz": "",
+    "output/-/esm.sh/react@18.2.0.js": "",
+  };
+  for (const [name, content] of Object.entries(files)) {
+    await Deno.mkdir(join(appDir, name, ".."), { recursive: true });
+    await Deno.writeTextFile(join(appDir, name), content);
+  }
+  Deno.env.set("DENO_DEPLOYMENT_ID", "1a2b3c4d");
+  try {
+    const router = {
+      prefix: "/routes",
+      routes: [],
+    };
+    const manifest = await createBuildManifest(appDir, join(appDir, "output"), router);
+    assertEquals(manifest.version, buildManifestVersion);
+    assertEquals(manifest.deploymentId, "1a2b3c4d");
+    assertEquals(manifest.router, { routes: [], prefix: "/routes" });
+    assertEquals(manifest.indexHtml?.includes(`<div id="root"><ssr-body></ssr-body></div>`), true);
+    assertEquals(manifest.indexHtml?.includes(`src="/main.ts?v=1a2b3c4d"`), true);
+    assertEquals(manifest.assets, {
+      "/assets/logo.svg": { file: "assets/logo.svg", contentType: "image/svg+xml", encodings: undefined },
+      "/main.js": { file: "output/main.js", contentType: "application/javascript; charset=utf-8", encodings: undefined },
+      "/pkg/app_bg.wasm": { file: "output/pkg/app_bg.wasm", contentType: "application/wasm", encodings: ["br", "gzip"] },
+      "/-/esm.sh/react@18.2.0": {
+        file: "output/-/esm.sh/react@18.2.0.js",
+        contentType: "application/javascript; charset=utf-8",
+        encodings: undefined,
+      },
+    });
+  } finally {
+    Deno.env.delete("DENO_DEPLOYMENT_ID");
+    await Deno.remove(appDir, { recursive: true });
+  }
+});
@@ -1,5 +1,5 @@
 import { assertEquals } from "std/testing/asserts.ts";
-import { MagicString, restoreUrl, toLocalPath } from "../server/helpers.ts";
+import { acceptsEncoding, MagicString, restoreUrl, toLocalPath } from "../server/helpers.ts";
 import { parseDeps } from "../server/deps.ts";
 
 Deno.test("server/helper.ts", async (t) => {
@@ -19,6 +19,17 @@ Deno.test("server/helper.ts", async (t) => {
     assertEquals(restoreUrl("/-/http_foo.com_8080/bar"), "http://foo.com:8080/bar");
   });
 
+  await t.step("acceptsEncoding", () => {
+    assertEquals(acceptsEncoding("gzip, deflate, br", "br"), true);
+    assertEquals(acceptsEncoding("gzip, br;q=0", "br"), false);
+    assertEquals(acceptsEncoding("br;q=0.5, gzip;q=1.0", "br"), true);
+    assertEquals(acceptsEncoding("gzip", "br"), false);
+    assertEquals(acceptsEncoding("*", "br"), true);
+    assertEquals(acceptsEncoding("gzip, *;q=0", "br"), false);
+    assertEquals(acceptsEncoding("br;q=0, *", "br"), false);
+    assertEquals(acceptsEncoding(null, "gzip"), false);
+  });
+
   await t.step("MagicString", async () => {
     const code = `// Deno 🦕 App (应用)
       import React from "htts://esm.sh/react";
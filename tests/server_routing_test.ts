@@ -1,7 +1,7 @@
 import { assertEquals } from "std/testing/asserts.ts";
 import { dirname, join } from "std/path/mod.ts";
 import { matchRoutes } from "../framework/core/router.ts";
-import { initRouter } from "../server/router.ts";
+import { initRouter, isFormNavigation, redirectBack } from "../server/router.ts";
 
 Deno.test("[unit] server/router.ts: matchRoutes", async () => {
   const tmpDir = await Deno.makeTempDir();
@@ -149,3 +149,23 @@ Deno.test("[unit] server/router.ts: matchRoutes", async () => {
     "./routes/users/$uid/settings/$page.tsx",
   ]);
 });
+
+Deno.test("[unit] server/router.ts: redirect the form submitted without JS", () => {
+  const form = (headers: Record<string, string>) =>
+    new Request("http://localhost/todos?filter=all", { method: "POST", headers, body: "message=hello" });
+  const urlencoded = "application/x-www-form-urlencoded";
+
+  assertEquals(isFormNavigation(form({ "Content-Type": urlencoded, "Sec-Fetch-Mode": "navigate" })), true);
+  assertEquals(isFormNavigation(form({ "Content-Type": "multipart/form-data; boundary=x", "Accept": "text/html" })), true);
+  assertEquals(isFormNavigation(form({ "Content-Type": urlencoded, "Sec-Fetch-Mode": "cors" })), false);
+  assertEquals(isFormNavigation(form({ "Content-Type": "application/json", "Sec-Fetch-Mode": "navigate" })), false);
+
+  let res = redirectBack(form({ "Referer": "http://localhost/todos?filter=done" }), Response.json({}));
+  assertEquals(res.status, 303);
+  assertEquals(res.headers.get("Location"), "/todos?filter=done");
+  res = redirectBack(form({ "Referer": "https://example.com/" }), Response.json({}));
+  assertEquals(res.headers.get("Location"), "/todos?filter=all");
+  res = redirectBack(form({}), new Response(null, { status: 302, headers: { "Location": "/todos/1" } }));
+  assertEquals(res.headers.get("Location"), "/todos/1");
+  assertEquals(redirectBack(form({}), new Response("Bad Request", { status: 400 })).status, 400);
+});